@@ -0,0 +1,204 @@
+use crate::Error;
+use base64::Engine;
+use mcp_schema::{PromptContent, PromptMessage, ResourceContents, Role};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The output of a tool: content shown to the model, and optionally the same data as structured
+/// JSON for clients that can consume it directly
+#[derive(Clone, Debug, Default)]
+pub struct Contents {
+    pub content: Vec<PromptContent>,
+    pub structured: Option<serde_json::Value>,
+}
+
+/// Conversion of a tool handler's return value into [`Contents`]. Serializable types can derive
+/// it with `#[derive(mcp::IntoContents)]`, which converts them like [`Json`].
+pub trait IntoContents {
+    /// # Errors
+    /// If the value cannot be converted, such as when serialization fails, this will error.
+    fn into_contents(self) -> Result<Contents, Error>;
+}
+
+impl IntoContents for Contents {
+    fn into_contents(self) -> Result<Contents, Error> {
+        Ok(self)
+    }
+}
+
+impl IntoContents for Vec<PromptContent> {
+    fn into_contents(self) -> Result<Contents, Error> {
+        Ok(Contents {
+            content: self,
+            structured: None,
+        })
+    }
+}
+
+impl IntoContents for PromptContent {
+    fn into_contents(self) -> Result<Contents, Error> {
+        vec![self].into_contents()
+    }
+}
+
+impl IntoContents for String {
+    fn into_contents(self) -> Result<Contents, Error> {
+        text(self).into_contents()
+    }
+}
+
+impl IntoContents for &str {
+    fn into_contents(self) -> Result<Contents, Error> {
+        self.to_string().into_contents()
+    }
+}
+
+/// Returns a serializable value as both JSON text and structured content
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> IntoContents for Json<T> {
+    fn into_contents(self) -> Result<Contents, Error> {
+        let structured = serde_json::to_value(self.0)?;
+        Ok(Contents {
+            content: vec![text(serde_json::to_string_pretty(&structured)?)],
+            structured: Some(structured),
+        })
+    }
+}
+
+/// Creates text content
+#[must_use]
+pub fn text(text: impl Into<String>) -> PromptContent {
+    PromptContent::Text(mcp_schema::TextContent {
+        kind: "text".to_string(),
+        text: text.into(),
+        annotated: mcp_schema::Annotated {
+            annotations: None,
+            extra: HashMap::new(),
+        },
+    })
+}
+
+/// Creates image content from raw bytes, such as a PNG
+#[must_use]
+pub fn image(data: &[u8], mime_type: impl Into<String>) -> PromptContent {
+    PromptContent::Image(mcp_schema::ImageContent {
+        kind: "image".to_string(),
+        data: base64::engine::general_purpose::STANDARD.encode(data),
+        mime_type: mime_type.into(),
+        annotated: mcp_schema::Annotated {
+            annotations: None,
+            extra: HashMap::new(),
+        },
+    })
+}
+
+/// Creates content embedding a resource's contents
+#[must_use]
+pub fn embedded(resource: ResourceContents) -> PromptContent {
+    PromptContent::Resource(mcp_schema::EmbeddedResource {
+        kind: "resource".to_string(),
+        resource,
+        annotated: mcp_schema::Annotated {
+            annotations: None,
+            extra: HashMap::new(),
+        },
+    })
+}
+
+/// Marks embedded resources whose contents haven't been read yet
+const PENDING_EMBED: &str = "pendingEmbed";
+
+/// Embeds a registered resource into a prompt message. When the prompt is returned by a
+/// `BasicService` of the server, the placeholder is replaced by the resource's contents, read from
+/// its resource registry, with one message per contents item.
+#[must_use]
+pub fn embed(uri: impl Into<String>) -> PromptContent {
+    PromptContent::Resource(mcp_schema::EmbeddedResource {
+        kind: "resource".to_string(),
+        resource: ResourceContents::Text(mcp_schema::TextResourceContents {
+            uri: uri.into(),
+            mime_type: None,
+            text: String::new(),
+        }),
+        annotated: mcp_schema::Annotated {
+            annotations: None,
+            extra: HashMap::from([(PENDING_EMBED.to_string(), serde_json::Value::Bool(true))]),
+        },
+    })
+}
+
+/// The uri of a resource embedded with [`embed`] whose contents haven't been read yet
+#[doc(hidden)]
+#[must_use]
+pub fn pending_embed(content: &PromptContent) -> Option<&str> {
+    let PromptContent::Resource(resource) = content else {
+        return None;
+    };
+    let ResourceContents::Text(contents) = &resource.resource else {
+        return None;
+    };
+    resource
+        .annotated
+        .extra
+        .contains_key(PENDING_EMBED)
+        .then_some(contents.uri.as_str())
+}
+
+/// Builds the messages of a prompt as an exchange between the user and the assistant, in order.
+///
+/// ```ignore
+/// let messages = PromptMessagesBuilder::new()
+///     .user("What's in this picture?")
+///     .user_content(image(&png, "image/png"))
+///     .assistant("A lighthouse at dusk.")
+///     .user_content(embed("file:///notes/lighthouses.md"))
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PromptMessagesBuilder {
+    messages: Vec<PromptMessage>,
+}
+
+impl PromptMessagesBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text message from the user
+    #[must_use]
+    pub fn user(self, message: impl Into<String>) -> Self {
+        self.message(Role::User, text(message))
+    }
+
+    /// Adds a text message from the assistant
+    #[must_use]
+    pub fn assistant(self, message: impl Into<String>) -> Self {
+        self.message(Role::Assistant, text(message))
+    }
+
+    /// Adds a message from the user with any content, such as an [`image`] or [`embedded`]
+    /// resource
+    #[must_use]
+    pub fn user_content(self, content: PromptContent) -> Self {
+        self.message(Role::User, content)
+    }
+
+    /// Adds a message from the assistant with any content
+    #[must_use]
+    pub fn assistant_content(self, content: PromptContent) -> Self {
+        self.message(Role::Assistant, content)
+    }
+
+    #[must_use]
+    pub fn message(mut self, role: Role, content: PromptContent) -> Self {
+        self.messages.push(PromptMessage { role, content });
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Vec<PromptMessage> {
+        self.messages
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::{Display, Formatter};
+
+/// The class of an error, which decides the JSON-RPC error code sent to the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The message isn't valid JSON
+    ParseError,
+    /// The message isn't a valid request, such as one that is too large
+    InvalidRequest,
+    MethodNotFound,
+    /// The request has invalid arguments, or names a tool or prompt that doesn't exist
+    InvalidParams,
+    Internal,
+    /// The resource of a uri doesn't exist, as defined by MCP
+    ResourceNotFound,
+    /// A code defined by the server, conventionally between -32000 and -32099
+    Custom(i32),
+}
+
+impl ErrorCode {
+    /// The code sent to the client
+    #[must_use]
+    pub const fn code(self) -> i32 {
+        match self {
+            Self::ParseError => -32700,
+            Self::InvalidRequest => -32600,
+            Self::MethodNotFound => -32601,
+            Self::InvalidParams => -32602,
+            Self::Internal => -32603,
+            Self::ResourceNotFound => -32002,
+            Self::Custom(code) => code,
+        }
+    }
+
+    /// Whether the error is a problem with the request rather than a failure while handling it.
+    /// Tools report other errors to the model as results marked with `isError`, so it can react
+    /// to them, while these are sent as JSON-RPC errors.
+    #[must_use]
+    pub const fn is_protocol(self) -> bool {
+        matches!(
+            self,
+            Self::ParseError | Self::InvalidRequest | Self::MethodNotFound | Self::InvalidParams
+        )
+    }
+
+    #[must_use]
+    pub const fn from_code(code: i32) -> Self {
+        match code {
+            -32700 => Self::ParseError,
+            -32600 => Self::InvalidRequest,
+            -32601 => Self::MethodNotFound,
+            -32602 => Self::InvalidParams,
+            -32603 => Self::Internal,
+            -32002 => Self::ResourceNotFound,
+            code => Self::Custom(code),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Machine readable details sent to the client alongside the message
+    pub data: Option<serde_json::Value>,
+    /// The report the error was converted from, whose causes and backtrace are only sent to clients
+    /// when enabled with `McpImpl::error_details` of the server
+    report: Option<eyre::Report>,
+}
+
+impl Error {
+    #[must_use]
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+            report: None,
+        }
+    }
+
+    #[must_use]
+    pub fn parse_error(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ParseError, message)
+    }
+
+    #[must_use]
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidRequest, message)
+    }
+
+    #[must_use]
+    pub fn method_not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::MethodNotFound, message)
+    }
+
+    #[must_use]
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidParams, message)
+    }
+
+    #[must_use]
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    #[must_use]
+    pub fn resource_not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ResourceNotFound, message)
+    }
+
+    #[must_use]
+    pub fn custom(code: i32, message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Custom(code), message)
+    }
+
+    /// Adds machine readable details, such as the arguments that failed validation
+    #[must_use]
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// The report the error was converted from as rendered by the installed eyre handler, which
+    /// includes its causes and any backtrace or span trace the handler captured
+    #[must_use]
+    pub fn report(&self) -> Option<String> {
+        self.report.as_ref().map(|report| format!("{report:?}"))
+    }
+
+    /// Adds the causes and report of the error to its data, keeping fields the data already has
+    #[must_use]
+    pub fn with_details(mut self) -> Self {
+        let Some(report) = self.report.take() else {
+            return self;
+        };
+        let mut data = match self.data.take() {
+            Some(serde_json::Value::Object(data)) => data,
+            None => serde_json::Map::new(),
+            Some(data) => {
+                self.data = Some(data);
+                return self;
+            }
+        };
+        let chain: Vec<_> = report.chain().map(ToString::to_string).collect();
+        data.entry("chain").or_insert(serde_json::json!(chain));
+        data.entry("report")
+            .or_insert(serde_json::json!(format!("{report:?}")));
+        self.data = Some(serde_json::Value::Object(data));
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error {}: {}", self.code.code(), self.message)
+    }
+}
+
+impl std::error::Error for Error {
+    // No additional methods needed for this basic implementation
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::internal(format!("{error}"))
+    }
+}
+
+impl From<eyre::Error> for Error {
+    fn from(error: eyre::Report) -> Self {
+        // The backtrace is whatever the eyre handler captured, so nothing is captured here
+        let message = format!("{error}");
+        Self {
+            report: Some(error),
+            ..Self::internal(message)
+        }
+    }
+}
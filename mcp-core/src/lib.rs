@@ -0,0 +1,7 @@
+//! The types shared by every part of mcp: errors, and the content of tool results and prompts.
+//! The `mcp` crate re-exports them along with the server.
+
+pub mod content;
+pub mod error;
+
+pub use error::{Error, ErrorCode};
@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Index, Member, parse_quote};
+
+/// Options of `#[contents(..)]` on a type or field
+#[derive(Default)]
+struct Options {
+    text: bool,
+    unstructured: bool,
+}
+
+fn options(attrs: &[Attribute], on_field: bool) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("contents")) {
+        attr.parse_nested_meta(|meta| {
+            if on_field && meta.path.is_ident("text") {
+                options.text = true;
+                Ok(())
+            } else if !on_field && meta.path.is_ident("unstructured") {
+                options.unstructured = true;
+                Ok(())
+            } else if on_field {
+                Err(meta.error("expected `text`"))
+            } else {
+                Err(meta.error("expected `unstructured`"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// The fields sent as text of their own
+fn text_fields(input: &DeriveInput) -> syn::Result<Vec<Member>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            for field in data.variants.iter().flat_map(|variant| &variant.fields) {
+                if let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("contents")) {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "`#[contents(text)]` is only supported on fields of structs",
+                    ));
+                }
+            }
+            return Ok(Vec::new());
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "IntoContents can't be derived for unions",
+            ));
+        }
+    };
+
+    let mut members = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if !options(&field.attrs, true)?.text {
+            continue;
+        }
+        members.push(
+            field
+                .ident
+                .clone()
+                .map_or_else(|| Member::Unnamed(Index::from(index)), Member::Named),
+        );
+    }
+    Ok(members)
+}
+
+pub(crate) fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let unstructured = options(&input.attrs, false)?.unstructured;
+    let text_fields = text_fields(input)?;
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: ::mcp::__private::serde::Serialize));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let texts = text_fields.iter().map(|member| {
+        quote_spanned! {member.span()=>
+            ::mcp::content::text(::std::string::ToString::to_string(&self.#member))
+        }
+    });
+    let structured = if unstructured {
+        quote!(::std::option::Option::None)
+    } else {
+        quote!(json.structured)
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::mcp::content::IntoContents for #name #ty_generics #where_clause {
+            fn into_contents(
+                self,
+            ) -> ::std::result::Result<::mcp::content::Contents, ::mcp::Error> {
+                let mut content: ::std::vec::Vec<_> = ::std::vec![#(#texts),*];
+                let json = ::mcp::content::IntoContents::into_contents(
+                    ::mcp::content::Json(self),
+                )?;
+                content.extend(json.content);
+                ::std::result::Result::Ok(::mcp::content::Contents {
+                    content,
+                    structured: #structured,
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,128 @@
+//! Procedural macros of mcp. Use them through the `mcp` crate, whose paths the generated code
+//! refers to.
+
+mod contents;
+mod tool;
+mod tool_client;
+mod tool_input;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, ItemFn, parse_macro_input};
+
+/// Converts a serializable struct or enum into tool content: its JSON as text, and the same value
+/// as structured content, so handlers can return it directly.
+///
+/// Fields marked `#[contents(text)]` are also sent as text of their own with their `Display`
+/// implementation, before the JSON, such as a summary for clients that only show text. With
+/// `#[contents(unstructured)]` on the type, only text is sent.
+///
+/// ```ignore
+/// #[derive(Serialize, IntoContents)]
+/// struct Forecast {
+///     #[contents(text)]
+///     summary: String,
+///     temperature: f64,
+/// }
+///
+/// async fn forecast(state: State, input: ForecastInput) -> Result<Forecast, mcp::Error> {
+///     // ...
+/// }
+/// ```
+#[proc_macro_derive(IntoContents, attributes(contents))]
+pub fn derive_into_contents(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    contents::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Folds `#[tool_arg(..)]` attributes on the fields of a tool's input into its JSON schema. It
+/// must come before `#[derive(JsonSchema)]`, which reads the schemars attributes they become.
+///
+/// - `description = "..."`: the description of the field
+/// - `example = value`: an example value, any literal that is serializable
+/// - `min = number`, `max = number`: the inclusive range of a number
+/// - `pattern = "..."`: a regular expression a string must match
+///
+/// ```ignore
+/// #[mcp::tool_input]
+/// #[derive(Deserialize, JsonSchema)]
+/// struct ForecastInput {
+///     #[tool_arg(description = "The city to forecast", example = "London")]
+///     city: String,
+///     #[tool_arg(min = 1, max = 14)]
+///     days: u32,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn tool_input(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return syn::Error::new_spanned(args, "tool_input takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    let input = parse_macro_input!(input as DeriveInput);
+    tool_input::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Checks the signature of a tool handler at compile time and generates `<name>_tool()`, which
+/// builds the tool with the doc comments of the handler as its description.
+///
+/// Handlers are free async functions taking a state, which may be any sub-state of the service's
+/// state, and an input implementing `Deserialize` and `JsonSchema`, returning
+/// `Result<O, mcp::Error>` where `O` implements `IntoContents`. Mistakes are reported at the
+/// offending parameter or type. The name of the tool defaults to the name of the function, and
+/// may be set with `name = "..."`; it must be 1 to 64 ASCII letters, digits, `_` or `-`.
+///
+/// The listed metadata may also be set with `title = "..."`, `version = "..."` and
+/// `annotations(..)` taking `read_only`, `destructive`, `idempotent` and `open_world` hints.
+/// Calls may be bounded with `timeout = "30s"`, taking `ms`, `s`, `m` or `h`, and retried with
+/// `retries = 2`, which requires `annotations(idempotent = true)`. The schema of the structured
+/// output is advertised with `output = Type`, or with `output` for the success type of the
+/// handler, which must implement `JsonSchema`.
+///
+/// ```ignore
+/// /// Forecasts the weather of a city
+/// #[mcp::tool(name = "forecast", title = "Forecast", annotations(read_only = true))]
+/// async fn forecast(state: State, input: ForecastInput) -> Result<Forecast, mcp::Error> {
+///     // ...
+/// }
+///
+/// let service = BasicService::new(state).tool(forecast_tool()?);
+/// ```
+#[proc_macro_attribute]
+pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut tool_args = tool::ToolArgs::default();
+    let parser = syn::meta::parser(|meta| tool_args.parse(&meta));
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(input as ItemFn);
+    tool::expand(tool_args, &function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a trait of typed methods calling the tools listed in a `tools.json` manifest, as
+/// exported with `BasicService::tool_manifest`, and implements it for `mcp::testing::McpClient`,
+/// which needs the `testing` feature. The path is relative to the crate root.
+///
+/// Each tool gets a method named after it in snake case, taking an input struct named after it
+/// in Pascal case with the properties of its input schema. Properties that aren't required are
+/// optional, and values the schema doesn't describe with a simple type are `serde_json::Value`.
+///
+/// ```ignore
+/// mcp::tool_client!(WeatherTools, "tools.json");
+///
+/// let result = client
+///     .get_forecast(GetForecastInput { city: "London".to_string(), ..Default::default() })
+///     .await?;
+/// ```
+#[proc_macro]
+pub fn tool_client(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as tool_client::ClientArgs);
+    tool_client::expand(&args)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
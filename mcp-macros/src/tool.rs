@@ -0,0 +1,327 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{
+    Expr, ExprLit, FnArg, GenericArgument, Ident, ItemFn, Lit, LitBool, LitInt, LitStr, Meta,
+    PathArguments, ReturnType, Signature, Type,
+};
+
+/// The hints of `annotations(..)`, keyed by the fields of `ToolAnnotations` without `_hint`
+const HINTS: [&str; 4] = ["read_only", "destructive", "idempotent", "open_world"];
+
+/// The arguments of `#[tool(..)]`
+#[derive(Default)]
+pub(crate) struct ToolArgs {
+    name: Option<LitStr>,
+    title: Option<LitStr>,
+    version: Option<LitStr>,
+    annotations: Option<Vec<(Ident, LitBool)>>,
+    /// In milliseconds
+    timeout: Option<u64>,
+    retries: Option<LitInt>,
+    output: Option<Output>,
+}
+
+/// The type whose schema is advertised as the tool's output schema
+enum Output {
+    /// `output = Type`
+    Type(Type),
+    /// `output`, the success type of the handler
+    Inferred,
+}
+
+impl ToolArgs {
+    pub(crate) fn parse(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("title") {
+            self.title = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("version") {
+            self.version = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("annotations") {
+            let annotations = self.annotations.get_or_insert_with(Vec::new);
+            meta.parse_nested_meta(|hint| {
+                let Some(ident) = hint
+                    .path
+                    .get_ident()
+                    .filter(|ident| HINTS.contains(&&*ident.to_string()))
+                else {
+                    return Err(hint.error(
+                        "expected `read_only`, `destructive`, `idempotent` or `open_world`",
+                    ));
+                };
+                let ident = format_ident!("{}_hint", ident);
+                annotations.push((ident, hint.value()?.parse()?));
+                Ok(())
+            })?;
+        } else if meta.path.is_ident("timeout") {
+            self.timeout = Some(duration_millis(&meta.value()?.parse()?)?);
+        } else if meta.path.is_ident("retries") {
+            let retries: LitInt = meta.value()?.parse()?;
+            retries.base10_parse::<u32>()?;
+            self.retries = Some(retries);
+        } else if meta.path.is_ident("output") {
+            self.output = Some(if meta.input.peek(syn::Token![=]) {
+                Output::Type(meta.value()?.parse()?)
+            } else {
+                Output::Inferred
+            });
+        } else {
+            return Err(meta.error(
+                "expected `name`, `title`, `version`, `annotations`, `timeout`, `retries` or \
+                 `output`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a duration such as `"500ms"`, `"30s"`, `"5m"` or `"1h"` into milliseconds
+fn duration_millis(duration: &LitStr) -> syn::Result<u64> {
+    let value = duration.value();
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_start);
+    let scale = match unit {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        _ => 0,
+    };
+    amount
+        .parse::<u64>()
+        .ok()
+        .filter(|_| scale > 0)
+        .and_then(|amount| amount.checked_mul(scale))
+        .ok_or_else(|| {
+            syn::Error::new(
+                duration.span(),
+                "expected a duration such as \"500ms\", \"30s\", \"5m\" or \"1h\"",
+            )
+        })
+}
+
+/// Whether clients accept the name, which they may use as an identifier of their own
+fn valid_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-')
+}
+
+/// The doc comments of the handler, which become the description of the tool
+fn description(function: &ItemFn) -> Option<String> {
+    let lines: Vec<String> = function
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(line),
+                    ..
+                }) => Some(line.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+    let description = lines.join("\n").trim().to_string();
+    (!description.is_empty()).then_some(description)
+}
+
+/// The types of the success and error of a `Result<O, E>`
+fn result_types(sig: &Signature) -> syn::Result<(&Type, &Type)> {
+    const EXPECTED: &str =
+        "tool handlers must return `Result<O, mcp::Error>`, where `O` implements `IntoContents`";
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return Err(syn::Error::new(sig.paren_token.span.close(), EXPECTED));
+    };
+    let Type::Path(path) = &**ty else {
+        return Err(syn::Error::new(ty.span(), EXPECTED));
+    };
+    let segment = path
+        .path
+        .segments
+        .last()
+        .filter(|segment| segment.ident == "Result");
+    let Some(PathArguments::AngleBracketed(args)) = segment.map(|segment| &segment.arguments)
+    else {
+        return Err(syn::Error::new(ty.span(), EXPECTED));
+    };
+    let types: Vec<&Type> = args
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect();
+    match types[..] {
+        [ok, error] => Ok((ok, error)),
+        _ => Err(syn::Error::new(ty.span(), EXPECTED)),
+    }
+}
+
+/// The types of the state and input parameters
+fn parameters(function: &ItemFn) -> syn::Result<(&Type, &Type)> {
+    let types = function
+        .sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Typed(input) => Ok(&*input.ty),
+            FnArg::Receiver(receiver) => Err(syn::Error::new(
+                receiver.span(),
+                "tool handlers must be free functions",
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    match types[..] {
+        [state, input] => Ok((state, input)),
+        _ => Err(syn::Error::new(
+            function.sig.inputs.span(),
+            "tool handlers take the state and the input, such as \
+             `async fn search(state: State, input: SearchInput)`",
+        )),
+    }
+}
+
+pub(crate) fn expand(args: ToolArgs, function: &ItemFn) -> syn::Result<TokenStream> {
+    let sig = &function.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new(
+            sig.fn_token.span(),
+            "tool handlers must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "tool handlers can't be generic",
+        ));
+    }
+    let (state, input) = parameters(function)?;
+    let (output, error) = result_types(sig)?;
+
+    let ident = &sig.ident;
+    let name = args.name.unwrap_or_else(|| {
+        let name = ident.to_string();
+        LitStr::new(name.strip_prefix("r#").unwrap_or(&name), ident.span())
+    });
+    if !valid_name(&name.value()) {
+        return Err(syn::Error::new(
+            name.span(),
+            "tool names must be 1 to 64 ASCII letters, digits, `_` or `-`",
+        ));
+    }
+    let description = description(function).map(|description| quote!(.description(#description)));
+    let title = args.title.map(|title| quote!(.title(#title)));
+    let version = args.version.map(|version| quote!(.version(#version)));
+    let retries = match args.retries {
+        Some(retries) if retries.base10_parse::<u32>()? > 0 => {
+            // Retries would repeat the side effects of other tools, which `build()` rejects
+            let idempotent = args
+                .annotations
+                .iter()
+                .flatten()
+                .any(|(field, value)| field == "idempotent_hint" && value.value);
+            if !idempotent {
+                return Err(syn::Error::new(
+                    retries.span(),
+                    "tools with retries must be annotated with `annotations(idempotent = true)`",
+                ));
+            }
+            Some(quote!(.retries(#retries)))
+        }
+        Some(retries) => Some(quote!(.retries(#retries))),
+        None => None,
+    };
+    let timeout = args
+        .timeout
+        .map(|millis| quote!(.timeout(::core::time::Duration::from_millis(#millis))));
+    let output_schema = args.output.map(|schema| {
+        let schema = match schema {
+            Output::Type(ty) => ty,
+            Output::Inferred => output.clone(),
+        };
+        quote_spanned!(schema.span()=> .output::<#schema>())
+    });
+    let annotations = args.annotations.map(|hints| {
+        let (fields, values): (Vec<_>, Vec<_>) = hints.into_iter().unzip();
+        quote! {
+            .annotations(::mcp::registry::ToolAnnotations {
+                #(#fields: ::core::option::Option::Some(#values),)*
+                ..::core::default::Default::default()
+            })
+        }
+    });
+
+    // Each assertion is spanned to what it checks, so that's where a failure is reported
+    let assert_input = quote_spanned! {input.span()=>
+        assert_input::<#input>();
+    };
+    let assert_output = quote_spanned! {output.span()=>
+        assert_output::<#output>();
+    };
+    let assert_error = quote_spanned! {error.span()=>
+        let _: ::core::marker::PhantomData<::mcp::Error> = ::core::marker::PhantomData::<#error>;
+    };
+    let assert_send = quote_spanned! {ident.span()=>
+        assert_send(#ident);
+    };
+
+    let vis = &function.vis;
+    let builder = format_ident!("{}_tool", ident.to_string().trim_start_matches("r#"));
+    let doc = format!(
+        " Builds the `{}` tool, which `{ident}` handles",
+        name.value()
+    );
+    Ok(quote! {
+        #function
+
+        #[doc = #doc]
+        ///
+        /// # Errors
+        /// If the tool can't be built.
+        #vis fn #builder<__State>() -> ::core::result::Result<::mcp::Tool<__State>, ::mcp::Error>
+        where
+            __State: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static,
+            #state: ::mcp::FromRef<__State>,
+        {
+            fn assert_input<T>()
+            where
+                T: ::mcp::__private::serde::de::DeserializeOwned
+                    + ::mcp::__private::schemars::JsonSchema
+                    + ::core::marker::Send,
+            {
+            }
+            fn assert_output<T: ::mcp::content::IntoContents>() {}
+            fn assert_send<S, I, F>(_: impl ::core::ops::Fn(S, I) -> F)
+            where
+                F: ::core::future::Future + ::core::marker::Send + 'static,
+            {
+            }
+            #assert_input
+            #assert_output
+            #assert_error
+            #assert_send
+
+            ::mcp::Tool::builder()
+                .name(#name)
+                #description
+                #title
+                #version
+                #annotations
+                #timeout
+                #retries
+                #output_schema
+                .handler(#ident)
+                .build()
+        }
+    })
+}
@@ -0,0 +1,229 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use serde_json::Value;
+use std::path::Path;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token};
+
+/// The trait to generate and the manifest to read, relative to the crate root
+pub struct ClientArgs {
+    name: Ident,
+    path: LitStr,
+}
+
+impl Parse for ClientArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let path = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { name, path })
+    }
+}
+
+/// `getForecast` or `get-forecast` as `get_forecast`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            snake.push(c);
+        } else if !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+    snake
+}
+
+/// `get_forecast` or `getForecast` as `GetForecast`
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// An identifier for a name from the manifest, which may be a keyword or start with a digit
+fn ident(name: &str) -> Ident {
+    let name = if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("_{name}")
+    } else {
+        name.to_string()
+    };
+    syn::parse_str(&name).unwrap_or_else(|_| Ident::new_raw(&name, Span::call_site()))
+}
+
+/// The Rust type of a JSON schema, and whether it allows null
+fn field_type(schema: &Value) -> (TokenStream, bool) {
+    let (kind, nullable) = match &schema["type"] {
+        Value::String(kind) => (Some(kind.as_str()), false),
+        Value::Array(kinds) => {
+            let kinds: Vec<_> = kinds.iter().filter_map(Value::as_str).collect();
+            let nullable = kinds.contains(&"null");
+            match kinds
+                .iter()
+                .filter(|kind| **kind != "null")
+                .collect::<Vec<_>>()[..]
+            {
+                [kind] => (Some(*kind), nullable),
+                _ => (None, nullable),
+            }
+        }
+        _ => (None, false),
+    };
+    let ty = match kind {
+        Some("string") => quote!(::std::string::String),
+        Some("integer") => quote!(i64),
+        Some("number") => quote!(f64),
+        Some("boolean") => quote!(bool),
+        Some("array") => {
+            let (item, nullable) = field_type(&schema["items"]);
+            if nullable {
+                quote!(::std::vec::Vec<::std::option::Option<#item>>)
+            } else {
+                quote!(::std::vec::Vec<#item>)
+            }
+        }
+        _ => quote!(::mcp::__private::serde_json::Value),
+    };
+    (ty, nullable)
+}
+
+/// Docs of a description from the manifest, if it has one
+fn docs(value: &Value) -> Option<TokenStream> {
+    let description = value["description"].as_str()?;
+    Some(quote!(#[doc = #description]))
+}
+
+pub fn expand(args: &ClientArgs) -> syn::Result<TokenStream> {
+    let error = |message: String| syn::Error::new_spanned(&args.path, message);
+
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error("`CARGO_MANIFEST_DIR` is not set".to_string()))?;
+    let path = Path::new(&root).join(args.path.value());
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|e| error(format!("failed to read {}: {e}", path.display())))?;
+    let manifest: Value = serde_json::from_str(&manifest)
+        .map_err(|e| error(format!("{} is not valid JSON: {e}", path.display())))?;
+    let tools = manifest["tools"]
+        .as_array()
+        .ok_or_else(|| error("the manifest has no `tools` array".to_string()))?;
+
+    let mut inputs = Vec::new();
+    let mut methods = Vec::new();
+    let mut impls = Vec::new();
+    for tool in tools {
+        let name = tool["name"]
+            .as_str()
+            .ok_or_else(|| error("a tool in the manifest has no name".to_string()))?;
+        let method = ident(&snake_case(name));
+        let input = format_ident!("{}Input", pascal_case(name));
+
+        let schema = &tool["inputSchema"];
+        let required: Vec<_> = schema["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        let fields = schema["properties"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(property, schema)| {
+                let field = ident(&snake_case(property));
+                let field_docs = docs(schema);
+                let (ty, nullable) = field_type(schema);
+                if required.contains(&property.as_str()) && !nullable {
+                    quote! {
+                        #field_docs
+                        #[serde(rename = #property)]
+                        pub #field: #ty
+                    }
+                } else {
+                    quote! {
+                        #field_docs
+                        #[serde(rename = #property, skip_serializing_if = "::std::option::Option::is_none")]
+                        pub #field: ::std::option::Option<#ty>
+                    }
+                }
+            });
+
+        let input_docs = format!(" The input of the `{name}` tool");
+        inputs.push(quote! {
+            #[doc = #input_docs]
+            #[derive(
+                ::std::fmt::Debug,
+                ::std::clone::Clone,
+                ::std::default::Default,
+                ::mcp::__private::serde::Serialize,
+            )]
+            #[serde(crate = "::mcp::__private::serde")]
+            pub struct #input {
+                #(#fields,)*
+            }
+        });
+
+        let method_docs = docs(tool);
+        methods.push(quote! {
+            #method_docs
+            fn #method(
+                &mut self,
+                input: #input,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::mcp::__private::mcp_schema::CallToolResult,
+                    ::mcp::Error,
+                >,
+            > + ::std::marker::Send;
+        });
+        impls.push(quote! {
+            fn #method(
+                &mut self,
+                input: #input,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::mcp::__private::mcp_schema::CallToolResult,
+                    ::mcp::Error,
+                >,
+            > + ::std::marker::Send {
+                async move {
+                    let arguments = ::mcp::__private::serde_json::to_value(input)?;
+                    self.call_tool(#name, arguments).await
+                }
+            }
+        });
+    }
+
+    let name = &args.name;
+    let trait_docs = format!(
+        " Typed calls of the tools listed in `{}`",
+        args.path.value()
+    );
+    // Rebuilds the bindings when the manifest changes
+    let path = path.display().to_string();
+    Ok(quote! {
+        const _: &str = ::std::include_str!(#path);
+
+        #(#inputs)*
+
+        #[doc = #trait_docs]
+        pub trait #name {
+            #(#methods)*
+        }
+
+        impl #name for ::mcp::testing::McpClient {
+            #(#impls)*
+        }
+    })
+}
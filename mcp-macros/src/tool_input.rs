@@ -0,0 +1,122 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Expr, Field, Ident, LitStr, parse_quote};
+
+/// Constraints of one `#[tool_arg(..)]`
+#[derive(Default)]
+struct ToolArg {
+    description: Option<LitStr>,
+    example: Option<Expr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    pattern: Option<LitStr>,
+}
+
+impl ToolArg {
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut arg = Self::default();
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(ToString::to_string);
+            match key.as_deref() {
+                Some("description") => arg.description = Some(meta.value()?.parse()?),
+                Some("example") => arg.example = Some(meta.value()?.parse()?),
+                Some("min") => arg.min = Some(meta.value()?.parse()?),
+                Some("max") => arg.max = Some(meta.value()?.parse()?),
+                Some("pattern") => arg.pattern = Some(meta.value()?.parse()?),
+                _ => {
+                    return Err(
+                        meta.error("expected `description`, `example`, `min`, `max` or `pattern`")
+                    );
+                }
+            }
+            Ok(())
+        })?;
+        Ok(arg)
+    }
+
+    /// The schemars attributes with the same meaning, and the function returning the example
+    fn schemars(self, example_fn: &Ident) -> (Vec<Attribute>, Option<TokenStream>) {
+        let mut attrs: Vec<Attribute> = Vec::new();
+        if let Some(description) = self.description {
+            attrs.push(parse_quote!(#[schemars(description = #description)]));
+        }
+        let min = self.min.map(|min| quote!(min = #min));
+        let max = self.max.map(|max| quote!(max = #max));
+        let range: Vec<_> = min.into_iter().chain(max).collect();
+        if !range.is_empty() {
+            attrs.push(parse_quote!(#[schemars(range(#(#range),*))]));
+        }
+        if let Some(pattern) = self.pattern {
+            attrs.push(parse_quote!(#[schemars(regex(pattern = #pattern))]));
+        }
+
+        let Some(example) = self.example else {
+            return (attrs, None);
+        };
+        let path = LitStr::new(&example_fn.to_string(), Span::call_site());
+        attrs.push(parse_quote!(#[schemars(example = #path)]));
+        let example = quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            fn #example_fn() -> impl ::mcp::__private::serde::Serialize {
+                #example
+            }
+        };
+        (attrs, Some(example))
+    }
+}
+
+/// Replaces the `#[tool_arg(..)]` of a field with schemars attributes, returning the function
+/// of its example if it has one
+fn rewrite(field: &mut Field, example_fn: &Ident) -> syn::Result<Option<TokenStream>> {
+    let mut example = None;
+    let mut attrs = Vec::with_capacity(field.attrs.len());
+    for attr in std::mem::take(&mut field.attrs) {
+        if !attr.path().is_ident("tool_arg") {
+            attrs.push(attr);
+            continue;
+        }
+        let arg = ToolArg::parse(&attr)?;
+        if arg.example.is_some() && example.is_some() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "a field can only have one example",
+            ));
+        }
+        let (schemars, example_fn) = arg.schemars(example_fn);
+        attrs.extend(schemars);
+        example = example.or(example_fn);
+    }
+    field.attrs = attrs;
+    Ok(example)
+}
+
+pub(crate) fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = input.ident.clone();
+    let fields: Vec<&mut Field> = match &mut input.data {
+        Data::Struct(data) => data.fields.iter_mut().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter_mut()
+            .flat_map(|variant| variant.fields.iter_mut())
+            .collect(),
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "tool inputs must be structs or enums",
+            ));
+        }
+    };
+
+    let mut examples = Vec::new();
+    for (index, field) in fields.into_iter().enumerate() {
+        let example_fn = format_ident!("__tool_arg_example_{}_{}", name, index);
+        examples.extend(rewrite(field, &example_fn)?);
+    }
+
+    Ok(quote! {
+        #input
+        #(#examples)*
+    })
+}
@@ -0,0 +1,109 @@
+use axum::extract::Request;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use futures::future::BoxFuture;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+type Validator = Arc<dyn Fn(String) -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// A layer that rejects requests without a valid `Authorization: Bearer <token>` header with
+/// `401 Unauthorized`. Pass it to [`crate::serve_over_sse_with_layer`] to protect every MCP
+/// route, or apply it to the router from [`crate::McpImpl::into_router`].
+#[derive(Clone)]
+pub struct BearerAuth {
+    validator: Validator,
+}
+
+impl BearerAuth {
+    /// Accepts a single static token
+    #[must_use]
+    pub fn token(token: impl Into<String>) -> Self {
+        let token = Arc::new(token.into());
+        Self::validator(move |given: String| {
+            let token = token.clone();
+            async move { constant_time_eq(given.as_bytes(), token.as_bytes()) }
+        })
+    }
+
+    /// Accepts the tokens the validator returns `true` for, such as by looking them up in a
+    /// database or introspecting them with an identity provider
+    #[must_use]
+    pub fn validator<F, Fut>(validator: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        Self {
+            validator: Arc::new(move |token| Box::pin(validator(token))),
+        }
+    }
+}
+
+/// Compares tokens without leaking the length of the matching prefix through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+impl<S> Layer<S> for BearerAuth {
+    type Service = BearerAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerAuthService {
+            inner,
+            validator: self.validator.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BearerAuthService<S> {
+    inner: S,
+    validator: Validator,
+}
+
+impl<S> Service<Request> for BearerAuthService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        // The ready service must be the one that is called, so a fresh clone takes its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let validator = self.validator.clone();
+
+        Box::pin(async move {
+            let authorized = match token {
+                Some(token) => validator(token).await,
+                None => false,
+            };
+            if !authorized {
+                return Ok((
+                    StatusCode::UNAUTHORIZED,
+                    [(header::WWW_AUTHENTICATE, "Bearer")],
+                )
+                    .into_response());
+            }
+            inner.call(request).await
+        })
+    }
+}
@@ -0,0 +1,1187 @@
+use crate::catalog::{Capability, CapabilityKind, FIND_CAPABILITY, Ranker};
+use crate::completion::{CompletionResult, Reference};
+use crate::filter::{ContentFilter, ContentForms, SharedContentFilter};
+use crate::inject::Scope;
+use crate::logging::{LogSink, Logger};
+use crate::middleware::{MessageMiddleware, SharedMiddleware};
+use crate::notifier::Notifier;
+use crate::registry::resource::{FixedResourceUri, TemplateResourceUri};
+use crate::registry::tool::TOOL_URI_PREFIX;
+use crate::registry::{HandlerFn, Page, page_map};
+use crate::rpc::ClientMessage;
+use crate::self_check::{SelfCheckReport, check_uri_template};
+use crate::{
+    Error, McpImpl, Prompt, PromptRegistry, Resource, ResourceRegistry, Service, Tool, ToolRegistry,
+};
+use futures::future::Either;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+pub struct BasicService<State> {
+    state: State,
+
+    name: String,
+    version: String,
+
+    instructions: Option<String>,
+    render_instructions: Option<RenderInstructions<State>>,
+    override_capabilities: Option<OverrideCapabilities>,
+    tool_registry: ToolRegistry<State>,
+    prompt_registry: PromptRegistry<State>,
+    resource_registry: ResourceRegistry<State>,
+    content_filters: Vec<SharedContentFilter>,
+    middleware: Vec<SharedMiddleware>,
+    tool_docs: bool,
+    lazy_schemas: bool,
+    latency_hints: bool,
+    page_size: Option<usize>,
+    catalog_ranker: Option<Arc<dyn Ranker + Send + Sync>>,
+    singletons: Scope,
+    scope_hook: Option<ScopeHook>,
+
+    notification_handler: Option<Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>>,
+    log_sink: LogSink,
+    /// Whether a [`Notifier`] was created, so the lists are advertised as changing
+    notifies_list_changed: AtomicBool,
+    resource_subscriptions: Mutex<HashMap<String, JoinHandle<()>>>,
+}
+
+type ScopeHook = Arc<dyn Fn(&mut Scope, &serde_json::Value) + Send + Sync>;
+type OverrideCapabilities = Arc<dyn Fn(&mut mcp_schema::ServerCapabilities) + Send + Sync>;
+type RenderInstructions<State> = Arc<dyn Fn(&BasicService<State>) -> String + Send + Sync>;
+
+/// The names of the tools added, changed and removed since the tools a client has cached
+#[derive(Default)]
+struct ToolChanges {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl ToolChanges {
+    /// Compares the `schemaHash` of each tool by name
+    fn between(
+        known: &serde_json::Map<String, serde_json::Value>,
+        current: &HashMap<String, String>,
+    ) -> Self {
+        let mut changes = Self::default();
+        for (name, hash) in current {
+            match known.get(name) {
+                None => changes.added.push(name.clone()),
+                Some(known) if known.as_str() != Some(hash) => changes.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = known
+            .keys()
+            .filter(|name| !current.contains_key(*name))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    /// Whether the tool is listed, which is only when it was added or changed
+    fn lists(&self, name: &str) -> bool {
+        self.added
+            .iter()
+            .chain(&self.changed)
+            .any(|listed| listed == name)
+    }
+
+    fn into_json(mut self) -> serde_json::Value {
+        self.added.sort_unstable();
+        self.changed.sort_unstable();
+        self.removed.sort_unstable();
+        serde_json::json!({
+            "added": self.added,
+            "changed": self.changed,
+            "removed": self.removed,
+        })
+    }
+}
+
+impl<State: Default> Default for BasicService<State> {
+    fn default() -> Self {
+        Self::new(State::default())
+    }
+}
+
+impl<State> BasicService<State> {
+    /// Creates a service whose tools, prompts and resources are handed the state. Services
+    /// without state use `()`.
+    #[must_use]
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            name: "unnamed".to_string(),
+            version: "0.1.0".to_string(),
+            instructions: None,
+            render_instructions: None,
+            override_capabilities: None,
+            tool_registry: ToolRegistry::default(),
+            prompt_registry: PromptRegistry::default(),
+            resource_registry: ResourceRegistry::default(),
+            content_filters: Vec::new(),
+            middleware: Vec::new(),
+            tool_docs: false,
+            lazy_schemas: false,
+            latency_hints: false,
+            page_size: None,
+            catalog_ranker: None,
+            singletons: Scope::default(),
+            scope_hook: None,
+            notification_handler: None,
+            log_sink: LogSink::default(),
+            notifies_list_changed: AtomicBool::new(false),
+            resource_subscriptions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the state given to [`Self::new`]
+    #[must_use]
+    pub fn state(mut self, state: State) -> Self {
+        self.state = state;
+        self
+    }
+
+    #[must_use]
+    pub fn version(mut self, version: String) -> Self {
+        self.version = version;
+        self
+    }
+
+    #[must_use]
+    pub fn name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Sets instructions that tell the model how to use the server, sent when a client
+    /// initializes
+    #[must_use]
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self.render_instructions = None;
+        self
+    }
+
+    /// Renders the instructions each time a client initializes, such as to list the tools that
+    /// are currently registered. Replaces instructions set with [`Self::instructions`].
+    #[must_use]
+    pub fn instructions_with(
+        mut self,
+        render: impl Fn(&Self) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.render_instructions = Some(Arc::new(render));
+        self
+    }
+
+    /// A logger whose messages are sent to clients, named so that clients can tell where they
+    /// come from. It can be cloned into tools and sources, such as through [`Self::provide`].
+    #[must_use]
+    pub fn logger(&self, name: impl Into<String>) -> Logger {
+        Logger::new(Some(name.into()), self.log_sink.clone())
+    }
+
+    /// A notifier that tells clients when the tools, prompts or resources change. Clients are told
+    /// that the lists may change once any notifier has been created.
+    #[must_use]
+    pub fn notifier(&self) -> Notifier {
+        self.notifies_list_changed.store(true, Ordering::Relaxed);
+        Notifier::new(self.log_sink.clone())
+    }
+
+    /// Adjusts the capabilities advertised when a client initializes. By default, tools, prompts,
+    /// resources and completions are advertised only if any are registered, so this is for cases
+    /// such as registering tools after clients have connected, or handling logging in middleware.
+    #[must_use]
+    pub fn capabilities_with(
+        mut self,
+        adjust: impl Fn(&mut mcp_schema::ServerCapabilities) + Send + Sync + 'static,
+    ) -> Self {
+        self.override_capabilities = Some(Arc::new(adjust));
+        self
+    }
+
+    /// Adds a filter that is applied to the content of every tool result and prompt before it is
+    /// sent to the client. Filters run in the order they were added.
+    #[must_use]
+    pub fn content_filter(mut self, filter: impl ContentFilter + Send + Sync + 'static) -> Self {
+        self.content_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Adds middleware that runs on every message after it is decoded. Middleware runs in the
+    /// order it was added, and the first rejection stops the message.
+    #[must_use]
+    pub fn middleware(
+        mut self,
+        middleware: impl MessageMiddleware + Send + Sync + 'static,
+    ) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Exposes the documentation of every tool that has some as a resource at
+    /// `mcp://tools/{name}/docs`
+    #[must_use]
+    pub const fn tool_docs(mut self, enabled: bool) -> Self {
+        self.tool_docs = enabled;
+        self
+    }
+
+    /// Lists tools without their input schemas, which are instead exposed as resources at
+    /// `mcp://tools/{name}/schema` and linked from each tool's `_meta.schemaUri`. This greatly
+    /// reduces the size of `tools/list` for servers with many tools.
+    #[must_use]
+    pub const fn lazy_schemas(mut self, enabled: bool) -> Self {
+        self.lazy_schemas = enabled;
+        self
+    }
+
+    /// Lists the latency percentiles of each tool's recent calls in its `_meta.latency`, so clients
+    /// can prefer faster tools or set expectations for slow ones. This doesn't change the
+    /// `schemaHash` or `catalogHash` of the listing.
+    #[must_use]
+    pub const fn latency_hints(mut self, enabled: bool) -> Self {
+        self.latency_hints = enabled;
+        self
+    }
+
+    /// Lists tools, prompts, resources and resource templates in pages of at most this many
+    /// entries, instead of all at once
+    #[must_use]
+    pub const fn page_size(mut self, size: usize) -> Self {
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Adds the built-in `find_capability` tool, which searches the registered tools, prompts and
+    /// resources using the given ranker, such as [`crate::catalog::FuzzyRanker`]
+    #[must_use]
+    pub fn catalog_search(mut self, ranker: impl Ranker + Send + Sync + 'static) -> Self {
+        self.catalog_ranker = Some(Arc::new(ranker));
+        self
+    }
+
+    /// Provides a value to every request's [`Scope`], such as an HTTP client or database pool
+    #[must_use]
+    pub fn provide<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.singletons.insert(value);
+        self
+    }
+
+    /// Adds per-request values to the [`Scope`] of every tool call and prompt. The hook is given
+    /// the request parameters, including `_meta`.
+    #[must_use]
+    pub fn request_scope(
+        mut self,
+        hook: impl Fn(&mut Scope, &serde_json::Value) + Send + Sync + 'static,
+    ) -> Self {
+        self.scope_hook = Some(Arc::new(hook));
+        self
+    }
+
+    pub const fn tool_registry(&self) -> &ToolRegistry<State> {
+        &self.tool_registry
+    }
+
+    pub const fn tool_registry_mut(&mut self) -> &mut ToolRegistry<State> {
+        &mut self.tool_registry
+    }
+
+    #[must_use]
+    pub fn tool(mut self, tool: Tool<State>) -> Self {
+        let registry = self.tool_registry_mut();
+        registry.register(tool);
+        self
+    }
+
+    #[must_use]
+    pub fn prompt(mut self, prompt: Prompt<State>) -> Self {
+        let registry = self.prompt_registry_mut();
+        registry.register(prompt);
+        self
+    }
+
+    /// Fails if a tool, prompt or resource was registered more than once, which would otherwise
+    /// silently replace the earlier registration. [`crate::server!`] checks this when it assembles
+    /// a service.
+    ///
+    /// # Errors
+    /// If a name or fixed uri is registered more than once, this will error.
+    pub fn unique(self) -> Result<Self, Error> {
+        let duplicate = self
+            .tool_registry
+            .duplicate_names()
+            .first()
+            .map(|name| format!("tool '{name}'"))
+            .or_else(|| {
+                let name = self.prompt_registry.duplicate_names().first()?;
+                Some(format!("prompt '{name}'"))
+            })
+            .or_else(|| {
+                let uri = self.resource_registry.duplicate_uris().first()?;
+                Some(format!("resource '{uri}'"))
+            });
+        match duplicate {
+            Some(duplicate) => Err(Error::internal(format!(
+                "The {duplicate} is registered more than once"
+            ))),
+            None => Ok(self),
+        }
+    }
+
+    pub const fn prompt_registry(&self) -> &PromptRegistry<State> {
+        &self.prompt_registry
+    }
+
+    pub const fn prompt_registry_mut(&mut self) -> &mut PromptRegistry<State> {
+        &mut self.prompt_registry
+    }
+
+    pub const fn resource_registry(&self) -> &ResourceRegistry<State> {
+        &self.resource_registry
+    }
+
+    pub fn resource_registry_mut(&mut self) -> &mut ResourceRegistry<State> {
+        &mut self.resource_registry
+    }
+
+    fn scope(&self, request: &impl serde::Serialize) -> Scope {
+        let mut scope = self.singletons.share();
+        // Values provided by the transport, such as the authenticated principal
+        scope.extend(&Scope::current());
+        if let Some(hook) = &self.scope_hook {
+            let request = serde_json::to_value(request).unwrap_or_default();
+            hook(&mut scope, &request);
+        }
+        scope
+    }
+
+    /// Number of resource subscription tasks that are still running
+    #[doc(hidden)]
+    pub fn active_subscriptions(&self) -> usize {
+        let mut subscriptions = self.resource_subscriptions.lock().unwrap();
+        subscriptions.retain(|_, handle| !handle.is_finished());
+        subscriptions.len()
+    }
+
+    #[must_use]
+    pub fn fixed_resource(mut self, resource: Resource<State, FixedResourceUri>) -> Self {
+        let registry = self.resource_registry_mut();
+        registry.register_fixed(resource);
+        self
+    }
+
+    #[must_use]
+    pub fn template_resource(mut self, resource: Resource<State, TemplateResourceUri>) -> Self {
+        let registry = self.resource_registry_mut();
+        registry.register_template(resource);
+        self
+    }
+}
+
+impl<State: Send + Sync + 'static> BasicService<State> {
+    /// Validates that every registered schema is a valid tool input schema describing an object,
+    /// every template uri parses, no names collide, and that every tool with
+    /// [`crate::registry::tool::ToolBuilder::dry_run`] arguments succeeds. This is intended as a smoke test in CI.
+    pub async fn self_check(&self) -> SelfCheckReport
+    where
+        State: Clone,
+    {
+        let mut report = SelfCheckReport::default();
+
+        for (name, tool) in self.tool_registry.tools_iter() {
+            let listed = self
+                .list_tool(tool)
+                .map_err(|e| format!("invalid input schema: {e}"))
+                .and_then(|_| {
+                    // Clients only accept objects of arguments
+                    if tool.input_schema()["type"] == "object" {
+                        Ok(())
+                    } else {
+                        Err("input schema must describe an object".to_string())
+                    }
+                });
+            report.check(format_args!("tool '{name}'"), listed);
+        }
+        for name in self.tool_registry.duplicate_names() {
+            report.check(
+                format_args!("tool '{name}'"),
+                Err("registered more than once, only the last registration is served".to_string()),
+            );
+        }
+        if self.catalog_ranker.is_some() && self.tool_registry.get(FIND_CAPABILITY).is_some() {
+            report.check(
+                format_args!("tool '{FIND_CAPABILITY}'"),
+                Err("collides with the built-in catalog search tool".to_string()),
+            );
+        }
+
+        for (name, prompt) in self.prompt_registry.prompts_iter() {
+            let listed = mcp_schema::Prompt::try_from(prompt)
+                .map(drop)
+                .map_err(|e| format!("invalid arguments: {e}"));
+            report.check(format_args!("prompt '{name}'"), listed);
+        }
+        for name in self.prompt_registry.duplicate_names() {
+            report.check(
+                format_args!("prompt '{name}'"),
+                Err("registered more than once, only the last registration is served".to_string()),
+            );
+        }
+
+        for uri in self.resource_registry.duplicate_uris() {
+            report.check(
+                format_args!("resource '{uri}'"),
+                Err("registered more than once, only the last registration is served".to_string()),
+            );
+        }
+        let mut templates = Vec::new();
+        for template in self.resource_registry.template_resources_iter() {
+            let Ok(template) = mcp_schema::ResourceTemplate::try_from(template) else {
+                continue;
+            };
+            let uri = template.uri_template;
+            let result = if templates.contains(&uri) {
+                Err("registered more than once, only the first registration is served".to_string())
+            } else {
+                check_uri_template(&uri)
+            };
+            report.check(format_args!("resource template '{uri}'"), result);
+            templates.push(uri);
+        }
+
+        let dry_runs: Vec<_> = self
+            .tool_registry
+            .tools_iter()
+            .filter_map(|(name, tool)| Some((name, tool, tool.dry_run_args()?)))
+            .collect();
+        let inits: Vec<_> = self
+            .tool_registry
+            .tools_iter()
+            .filter(|(_, tool)| tool.has_init())
+            .collect();
+        if !dry_runs.is_empty() || !inits.is_empty() {
+            let state = &self.state;
+            for (name, tool) in inits {
+                let result = tool.warm_up(state.clone()).await.map_err(|e| e.message);
+                report.check(format_args!("tool '{name}' init"), result);
+            }
+
+            for (name, tool, args) in dry_runs {
+                let result = match tool.run(state.clone(), args.clone()).await {
+                    Ok(result) if result.is_error == Some(true) => {
+                        Err("dry run returned an error result".to_string())
+                    }
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(format!("dry run failed: {e}")),
+                };
+                report.check(format_args!("tool '{name}' dry run"), result);
+            }
+        }
+
+        report
+    }
+
+    /// Runs the init hooks of every tool that initializes at startup. Call this before serving so
+    /// the first calls don't pay for opening connections or loading models.
+    pub async fn warm_up(&self) -> SelfCheckReport
+    where
+        State: Clone,
+    {
+        let mut report = SelfCheckReport::default();
+        let tools = self
+            .tool_registry
+            .tools_iter()
+            .filter(|(_, tool)| tool.has_init() && !tool.init_on_first_call());
+        for (name, tool) in tools {
+            let result = tool
+                .warm_up(self.state.clone())
+                .await
+                .map_err(|e| e.message);
+            report.check(format_args!("tool '{name}' init"), result);
+        }
+        report
+    }
+
+    /// Exports the full listing of every registered tool, including input and output schemas, as
+    /// a `tools.json` manifest that client bindings can be generated from.
+    ///
+    /// # Errors
+    /// If a tool's schema is not a valid tool input schema, this will error.
+    pub fn tool_manifest(&self) -> Result<serde_json::Value, Error> {
+        let tools = self
+            .tool_registry
+            .tools_iter()
+            .map(|(_, tool)| mcp_schema::Tool::try_from(tool))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(serde_json::json!({
+            "server": { "name": self.name, "version": self.version },
+            "tools": tools,
+        }))
+    }
+
+    /// Advertises only what is registered, then applies the override set with
+    /// [`Self::capabilities_with`]
+    fn server_capabilities(&self) -> mcp_schema::ServerCapabilities {
+        let mut extra = HashMap::new();
+        if self.prompt_registry.has_completers() {
+            extra.insert("completions".to_string(), serde_json::json!({}));
+        }
+        // Every registered source can be watched, unlike the resources describing tools
+        let watchable = self
+            .resource_registry
+            .fixed_resources_iter()
+            .next()
+            .is_some()
+            || self
+                .resource_registry
+                .template_resources_iter()
+                .next()
+                .is_some();
+        let resources = watchable || self.tool_resources().next().is_some();
+        let list_changed = Some(self.notifies_list_changed.load(Ordering::Relaxed));
+
+        let mut capabilities = mcp_schema::ServerCapabilities {
+            experimental: None,
+            // Every service handles log levels, so logging is always advertised, as an empty object
+            logging: Some(std::iter::empty::<(String, serde_json::Value)>().collect()),
+            prompts: self
+                .prompt_registry
+                .prompts_iter()
+                .next()
+                .map(|_| mcp_schema::PromptsCapability { list_changed }),
+            resources: resources.then_some(mcp_schema::ResourcesCapability {
+                subscribe: Some(watchable),
+                list_changed,
+            }),
+            tools: (self.tool_registry.tools_iter().next().is_some()
+                || self.catalog_ranker.is_some())
+            .then_some(mcp_schema::ToolsCapability { list_changed }),
+            extra,
+        };
+        if let Some(override_capabilities) = &self.override_capabilities {
+            override_capabilities(&mut capabilities);
+        }
+        capabilities
+    }
+
+    /// Resources describing the registered tools
+    fn tool_resources(&self) -> impl Iterator<Item = mcp_schema::Resource> + '_ {
+        let resource = |uri, name, description, mime_type: &str| mcp_schema::Resource {
+            uri,
+            name,
+            description: Some(description),
+            mime_type: Some(mime_type.to_string()),
+            annotated: mcp_schema::Annotated {
+                annotations: None,
+                extra: HashMap::new(),
+            },
+        };
+
+        self.tool_registry.tools_iter().flat_map(move |(_, tool)| {
+            let docs = (self.tool_docs && tool.docs().is_some()).then(|| {
+                resource(
+                    tool.docs_uri(),
+                    format!("{} documentation", tool.name()),
+                    format!("Usage guide for the {} tool", tool.name()),
+                    "text/markdown",
+                )
+            });
+            let schema = self.lazy_schemas.then(|| {
+                resource(
+                    tool.schema_uri(),
+                    format!("{} input schema", tool.name()),
+                    format!("JSON schema of the arguments of the {} tool", tool.name()),
+                    "application/schema+json",
+                )
+            });
+            docs.into_iter().chain(schema)
+        })
+    }
+
+    /// Takes the page of entries the request's cursor points at if paging is enabled, or else
+    /// every entry
+    fn paged<'a, T: 'a>(
+        &self,
+        request: &mcp_schema::PaginatedParams,
+        all: impl Iterator<Item = &'a T>,
+        page: impl FnOnce(Option<&str>, usize) -> Result<Page<&'a T>, Error>,
+    ) -> Result<(Vec<&'a T>, Option<String>), Error> {
+        let Some(size) = self.page_size else {
+            return Ok((all.collect(), None));
+        };
+        let cursor = serde_json::to_value(request).ok().and_then(|request| {
+            request
+                .get("cursor")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        });
+        let page = page(cursor.as_deref(), size)?;
+        Ok((page.items, page.next_cursor))
+    }
+
+    /// Lists a tool, leaving out its schemas if lazy schemas are enabled
+    fn list_tool(&self, tool: &Tool<State>) -> Result<mcp_schema::Tool, serde_json::Error> {
+        let mut listed = mcp_schema::Tool::try_from(tool)?;
+        let hash = crate::registry::content_hash(&serde_json::to_value(&listed)?);
+        crate::registry::insert_meta(&mut listed.extra, "schemaHash", hash.into());
+        if self.lazy_schemas {
+            listed.input_schema = serde_json::from_value(serde_json::json!({ "type": "object" }))?;
+            listed.extra.remove("outputSchema");
+            crate::registry::insert_meta(&mut listed.extra, "schemaUri", tool.schema_uri().into());
+        }
+        if let Some(latency) = tool.latency().filter(|_| self.latency_hints) {
+            crate::registry::insert_meta(
+                &mut listed.extra,
+                "latency",
+                serde_json::to_value(latency)?,
+            );
+        }
+        Ok(listed)
+    }
+
+    /// Everything registered in this service, for searching with the catalog search tool
+    fn capabilities(&self) -> Vec<Capability> {
+        let tools = self
+            .tool_registry
+            .tools_iter()
+            .filter_map(|(_, tool)| mcp_schema::Tool::try_from(tool).ok())
+            .map(|tool| Capability {
+                kind: CapabilityKind::Tool,
+                name: tool.name,
+                description: tool.description,
+                uri: None,
+            });
+        let prompts = self
+            .prompt_registry
+            .prompts_iter()
+            .filter_map(|(_, prompt)| mcp_schema::Prompt::try_from(prompt).ok())
+            .map(|prompt| Capability {
+                kind: CapabilityKind::Prompt,
+                name: prompt.name,
+                description: prompt.description,
+                uri: None,
+            });
+        let resources = self
+            .resource_registry
+            .fixed_resources_iter()
+            .filter_map(|resource| mcp_schema::Resource::try_from(resource).ok())
+            .chain(self.tool_resources())
+            .map(|resource| Capability {
+                kind: CapabilityKind::Resource,
+                name: resource.name,
+                description: resource.description,
+                uri: Some(resource.uri),
+            });
+        let templates = self
+            .resource_registry
+            .template_resources_iter()
+            .filter_map(|resource| mcp_schema::ResourceTemplate::try_from(resource).ok())
+            .map(|template| Capability {
+                kind: CapabilityKind::ResourceTemplate,
+                name: template.name,
+                description: template.description,
+                uri: Some(template.uri_template),
+            });
+
+        tools
+            .chain(prompts)
+            .chain(resources)
+            .chain(templates)
+            .collect()
+    }
+
+    /// Reads a resource describing a registered tool
+    fn read_tool_resource(&self, uri: &str) -> Option<mcp_schema::ReadResourceResult> {
+        let (name, kind) = uri.strip_prefix(TOOL_URI_PREFIX)?.rsplit_once('/')?;
+        let tool = self.tool_registry.get(name)?;
+        let (mime_type, text) = match kind {
+            "docs" if self.tool_docs => ("text/markdown", tool.docs()?.to_string()),
+            "schema" if self.lazy_schemas => (
+                "application/schema+json",
+                serde_json::to_string_pretty(tool.input_schema()).ok()?,
+            ),
+            _ => return None,
+        };
+
+        Some(mcp_schema::ReadResourceResult {
+            meta: None,
+            contents: vec![mcp_schema::ResourceContents::Text(
+                mcp_schema::TextResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: Some(mime_type.to_string()),
+                    text,
+                },
+            )],
+            extra: HashMap::new(),
+        })
+    }
+}
+
+impl<State: Clone + Send + Sync + 'static> BasicService<State> {
+    /// The MCP endpoints of this service as a router, for nesting into an existing application.
+    /// See [`McpImpl::into_router`]. Unlike [`crate::serve_over_sse`], this doesn't shut the
+    /// service down when the application stops.
+    pub fn into_router<T>(self) -> axum::Router<T> {
+        Arc::new(McpImpl::new(self)).into_router()
+    }
+
+    /// Replaces resources embedded in prompt messages with [`crate::content::embed`] by their
+    /// contents
+    async fn embed_resources(
+        &self,
+        result: mcp_schema::GetPromptResult,
+    ) -> Result<mcp_schema::GetPromptResult, Error> {
+        let mut messages = Vec::with_capacity(result.messages.len());
+        for message in result.messages {
+            let Some(uri) = crate::content::pending_embed(&message.content) else {
+                messages.push(message);
+                continue;
+            };
+            let state = self.state.clone();
+            let read = self
+                .resource_registry
+                .read_resource(state, uri.to_string())
+                .await?;
+            messages.extend(
+                read.contents
+                    .into_iter()
+                    .map(|contents| mcp_schema::PromptMessage {
+                        role: message.role.clone(),
+                        content: crate::content::embedded(contents),
+                    }),
+            );
+        }
+        Ok(mcp_schema::GetPromptResult { messages, ..result })
+    }
+
+    /// Notifies the client of every change in the resource until it unsubscribes. Subscribing to
+    /// the same uri twice replaces the old subscription.
+    fn subscribe_to(&self, uri: String) -> Result<(), Error> {
+        let Some(notification_handler) = self.notification_handler.clone() else {
+            return Err(Error::internal(
+                "Subscribing requires the notification handler that is set when serving",
+            ));
+        };
+        let source = self.resource_registry.get_source(&uri)?;
+        let state = self.state.clone();
+        let watched = uri.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                source
+                    .wait_for_change_erased(state.clone(), watched.clone())
+                    .await;
+                (notification_handler)(mcp_schema::ServerNotification::ResourceUpdated {
+                    json_rpc: mcp_schema::JSONRPC_VERSION.to_string(),
+                    params: mcp_schema::ResourceUpdatedParams {
+                        uri: watched.clone(),
+                        extra: HashMap::new(),
+                    },
+                });
+            }
+        });
+        let previous = self
+            .resource_subscriptions
+            .lock()
+            .unwrap()
+            .insert(uri, handle);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+        Ok(())
+    }
+}
+
+impl<State: Clone + Send + Sync + 'static> Service for BasicService<State> {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    ) {
+        let handler: Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync> = handler.into();
+        let _ = self.log_sink.set(handler.clone());
+        self.notification_handler = Some(handler);
+    }
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> impl Future<Output = Result<mcp_schema::InitializeResult, Error>> + Send {
+        let result = mcp_schema::InitializeResult {
+            capabilities: self.server_capabilities(),
+            instructions: self
+                .render_instructions
+                .as_ref()
+                .map_or_else(|| self.instructions.clone(), |render| Some(render(self))),
+            meta: None,
+            protocol_version: mcp_schema::LATEST_PROTOCOL_VERSION.to_string(),
+            server_info: mcp_schema::Implementation {
+                name: self.name.clone(),
+                version: self.version.clone(),
+                extra: HashMap::new(),
+            },
+            extra: HashMap::new(),
+        };
+
+        async move { Ok(result) }
+    }
+
+    fn ping(
+        &self,
+        _: mcp_schema::PingParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move {
+            Ok(mcp_schema::EmptyResult {
+                meta: None,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourcesResult, Error>> + Send {
+        let result = || {
+            // Resources describing tools and listed by templates are paged along with the
+            // registered ones, by uri
+            let mut all = BTreeMap::new();
+            for resource in self.resource_registry.fixed_resources_iter() {
+                let resource = mcp_schema::Resource::try_from(resource)?;
+                all.insert(resource.uri.clone(), resource);
+            }
+            for resource in self
+                .resource_registry
+                .listed_template_resources(self.state.clone())
+            {
+                all.entry(resource.uri.clone()).or_insert(resource);
+            }
+            all.extend(
+                self.tool_resources()
+                    .map(|resource| (resource.uri.clone(), resource)),
+            );
+            let (page, next_cursor) = self.paged(&request, all.values(), |cursor, limit| {
+                page_map(&all, cursor, limit)
+            })?;
+            let uris: Vec<String> = page
+                .into_iter()
+                .map(|resource| resource.uri.clone())
+                .collect();
+            let resources = uris.iter().filter_map(|uri| all.remove(uri)).collect();
+
+            let result = mcp_schema::ListResourcesResult {
+                meta: None,
+                next_cursor,
+                resources,
+                extra: HashMap::new(),
+            };
+
+            Ok::<_, Error>(result)
+        };
+
+        let result = result();
+
+        async move { Ok(result?) }
+    }
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourceTemplatesResult, Error>> + Send {
+        let result = || {
+            let (resource_templates, next_cursor) = self.paged(
+                &request,
+                self.resource_registry.template_resources_iter(),
+                |cursor, limit| {
+                    self.resource_registry
+                        .template_resources_page(cursor, limit)
+                },
+            )?;
+
+            let result = mcp_schema::ListResourceTemplatesResult {
+                meta: None,
+                next_cursor,
+                resource_templates: resource_templates
+                    .into_iter()
+                    .map(mcp_schema::ResourceTemplate::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+                extra: HashMap::new(),
+            };
+
+            Ok::<_, Error>(result)
+        };
+
+        let result = result();
+
+        async move { Ok(result?) }
+    }
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + Send {
+        if let Some(result) = self.read_tool_resource(&request.uri) {
+            return Either::Left(async move { Ok(result) });
+        }
+
+        let result = &self.resource_registry;
+        Either::Right(result.read_resource(self.state.clone(), request.uri))
+    }
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        let result = self
+            .subscribe_to(request.uri)
+            .map(|()| mcp_schema::EmptyResult {
+                meta: None,
+                extra: HashMap::new(),
+            });
+        async move { result }
+    }
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        let subscription = self
+            .resource_subscriptions
+            .lock()
+            .unwrap()
+            .remove(&request.uri);
+
+        if let Some(subscription) = subscription {
+            subscription.abort();
+        }
+
+        async move {
+            Ok(mcp_schema::EmptyResult {
+                meta: None,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListPromptsResult, Error>> + Send {
+        let result = || {
+            let (prompts, next_cursor) = self.paged(
+                &request,
+                self.prompt_registry
+                    .prompts_iter()
+                    .map(|(_, prompt)| prompt),
+                |cursor, limit| self.prompt_registry.page(cursor, limit),
+            )?;
+            let result = mcp_schema::ListPromptsResult {
+                meta: None,
+                next_cursor,
+                prompts: prompts
+                    .into_iter()
+                    .map(mcp_schema::Prompt::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+                extra: HashMap::new(),
+            };
+            Ok(result)
+        };
+
+        let result = result();
+
+        async move { result }
+    }
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + Send {
+        let state = self.state.clone();
+        let result = crate::inject::scoped(self.scope(&request), || {
+            self.prompt_registry.get_prompt(state, request)
+        });
+        let filters = self.content_filters.clone();
+        let forms = ContentForms::current();
+        async move {
+            let result = self.embed_resources(result.await?).await?;
+            Ok(crate::filter::filter_prompt_result(&filters, forms, result))
+        }
+    }
+
+    /// Tools are listed with a `_meta.schemaHash` each, and the whole listing with a
+    /// `catalogHash`. If the client passes the `catalogHash` it last saw as `ifNoneMatch` and nothing
+    /// changed, the tools are left out and `notModified` is set instead.
+    ///
+    /// Clients may instead pass the `schemaHash` of each tool they have cached by name as
+    /// `knownTools`. Unless tools are paged, only the tools added or changed since are listed then,
+    /// and `changes` holds the `added`, `changed` and `removed` names.
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListToolsResult, Error>> + Send {
+        let page = self.paged(
+            &request,
+            self.tool_registry.tools_iter().map(|(_, tool)| tool),
+            |cursor, limit| self.tool_registry.page(cursor, limit),
+        );
+        let tools = page.and_then(|(tools, next_cursor)| {
+            let last = next_cursor.is_none();
+            let tools = tools
+                .into_iter()
+                .map(|tool| self.list_tool(tool))
+                .chain((last && self.catalog_ranker.is_some()).then(|| Ok(crate::catalog::tool())))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok::<_, Error>((tools, next_cursor))
+        });
+
+        let request = serde_json::to_value(&request).unwrap_or_default();
+        let param = |name: &str| {
+            request
+                .get(name)
+                .or_else(|| request.get("_meta").and_then(|meta| meta.get(name)))
+                .cloned()
+        };
+        let if_none_match = param("ifNoneMatch");
+        let known_tools = param("knownTools").filter(|_| self.page_size.is_none());
+
+        async move {
+            let (mut tools, next_cursor) = tools?;
+
+            let schema_hashes: HashMap<String, String> = tools
+                .iter()
+                .map(|tool| {
+                    let hash = tool
+                        .extra
+                        .get("_meta")
+                        .and_then(|meta| meta.get("schemaHash"))
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or_default();
+                    (tool.name.clone(), hash.to_string())
+                })
+                .collect();
+            let mut hashes: Vec<_> = schema_hashes
+                .iter()
+                .map(|(name, hash)| format!("{name}={hash}"))
+                .collect();
+            hashes.sort_unstable();
+            let catalog_hash = crate::registry::content_hash(&hashes.into());
+
+            let mut extra = HashMap::new();
+            let changes = known_tools
+                .as_ref()
+                .and_then(serde_json::Value::as_object)
+                .map(|known| ToolChanges::between(known, &schema_hashes));
+            if if_none_match.as_ref().and_then(serde_json::Value::as_str)
+                == Some(catalog_hash.as_str())
+            {
+                tools.clear();
+                extra.insert("notModified".to_string(), true.into());
+            } else if let Some(changes) = changes {
+                tools.retain(|tool| changes.lists(&tool.name));
+                extra.insert("changes".to_string(), changes.into_json());
+            }
+            extra.insert("catalogHash".to_string(), catalog_hash.into());
+
+            let result = mcp_schema::ListToolsResult {
+                meta: None,
+                next_cursor,
+                tools,
+                extra,
+            };
+            Ok(result)
+        }
+    }
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send {
+        let result = match &self.catalog_ranker {
+            Some(ranker) if request.name == FIND_CAPABILITY => {
+                Either::Left(std::future::ready(crate::catalog::find_capability(
+                    ranker.as_ref(),
+                    request.arguments.unwrap_or_default(),
+                    self.capabilities(),
+                )))
+            }
+            _ => {
+                let state = self.state.clone();
+                Either::Right(crate::inject::scoped(self.scope(&request), || {
+                    self.tool_registry.call_tool(state, request)
+                }))
+            }
+        };
+        let filters = self.content_filters.clone();
+        let forms = ContentForms::current();
+        async move {
+            Ok(crate::filter::filter_tool_result(
+                &filters,
+                forms,
+                result.await?,
+            ))
+        }
+    }
+
+    /// The level is tracked per session by [`McpImpl`], which filters the messages of
+    /// [`Self::logger`]
+    fn set_level(
+        &self,
+        _request: mcp_schema::SetLevelParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move {
+            Ok(mcp_schema::EmptyResult {
+                meta: None,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    /// Completes prompt arguments with the completers registered on the prompts. Resource
+    /// template arguments have no suggestions.
+    fn complete(
+        &self,
+        request: mcp_schema::CompleteParams,
+    ) -> impl Future<Output = Result<mcp_schema::CompleteResult, Error>> + Send {
+        let values =
+            crate::completion::parse(&request).and_then(|completion| match completion.reference {
+                Reference::Prompt(name) => {
+                    self.prompt_registry
+                        .complete(&name, &completion.argument, completion.value)
+                }
+                Reference::Resource(_) => Ok(Box::pin(async { CompletionResult::new() }) as _),
+            });
+        async move { values?.await.build() }
+    }
+
+    fn active_subscriptions(&self) -> usize {
+        Self::active_subscriptions(self)
+    }
+
+    fn intercept(&self, message: &mut ClientMessage) -> Result<(), Error> {
+        crate::middleware::apply(&self.middleware, message)
+    }
+
+    /// Stops every resource subscription, then runs the teardown hooks of tools and resource
+    /// sources
+    fn shutdown(&self) -> impl Future<Output = ()> + Send {
+        for (_, subscription) in self.resource_subscriptions.lock().unwrap().drain() {
+            subscription.abort();
+        }
+
+        async move {
+            let state = &self.state;
+            futures::future::join_all(
+                self.tool_registry
+                    .tools_iter()
+                    .map(|(_, tool)| tool.teardown(state.clone())),
+            )
+            .await;
+            self.resource_registry.teardown(state.clone()).await;
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name of the built-in tool that searches the catalog
+pub const FIND_CAPABILITY: &str = "find_capability";
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityKind {
+    Tool,
+    Prompt,
+    Resource,
+    ResourceTemplate,
+}
+
+/// A registered tool, prompt, resource or resource template as seen by a [`Ranker`]
+#[derive(Clone, Debug, Serialize)]
+pub struct Capability {
+    pub kind: CapabilityKind,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The uri or uri template of resources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// Scores how well a capability matches a search query. This can be implemented with embeddings
+/// for semantic search over very large catalogs.
+pub trait Ranker {
+    /// Returns a score where higher is better, or [`None`] if the capability doesn't match at all
+    fn score(&self, query: &str, capability: &Capability) -> Option<f32>;
+}
+
+/// Ranks capabilities by fuzzy matching the words of the query against names and descriptions
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FuzzyRanker;
+
+impl Ranker for FuzzyRanker {
+    fn score(&self, query: &str, capability: &Capability) -> Option<f32> {
+        let name = capability.name.to_lowercase();
+        let description = capability
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let score: f32 = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let word = word.to_lowercase();
+                if name
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|part| part == word)
+                {
+                    3.0
+                } else if name.contains(&word) {
+                    2.0
+                } else if description.contains(&word) {
+                    1.0
+                } else if is_subsequence(&word, &name) {
+                    0.5
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+
+        (score > 0.0).then_some(score)
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct FindCapabilityParams {
+    /// Words describing what you want to do
+    query: String,
+    /// Maximum number of results, 10 by default
+    limit: Option<usize>,
+}
+
+pub(crate) fn tool() -> mcp_schema::Tool {
+    mcp_schema::Tool {
+        description: Some(
+            "Search the tools, prompts and resources of this server by name and description"
+                .to_string(),
+        ),
+        input_schema: serde_json::from_value(
+            serde_json::to_value(schemars::schema_for!(FindCapabilityParams)).unwrap(),
+        )
+        .unwrap(),
+        name: FIND_CAPABILITY.to_string(),
+        extra: HashMap::new(),
+    }
+}
+
+/// Runs the built-in search tool
+pub(crate) fn find_capability(
+    ranker: &dyn Ranker,
+    args: HashMap<String, serde_json::Value>,
+    capabilities: impl IntoIterator<Item = Capability>,
+) -> Result<mcp_schema::CallToolResult, Error> {
+    let params: FindCapabilityParams =
+        serde_json::from_value(serde_json::Value::Object(args.into_iter().collect()))
+            .map_err(|e| Error::invalid_params(format!("Failed to deserialize arguments: {e}")))?;
+
+    let matches = search(ranker, &params, capabilities);
+
+    Ok(mcp_schema::CallToolResult {
+        meta: None,
+        content: vec![mcp_schema::PromptContent::Text(mcp_schema::TextContent {
+            kind: "text".to_string(),
+            text: serde_json::to_string_pretty(&matches)?,
+            annotated: mcp_schema::Annotated {
+                annotations: None,
+                extra: HashMap::new(),
+            },
+        })],
+        is_error: Some(false),
+        extra: HashMap::new(),
+    })
+}
+
+/// Returns the best matching capabilities for the query, best first
+fn search(
+    ranker: &dyn Ranker,
+    params: &FindCapabilityParams,
+    capabilities: impl IntoIterator<Item = Capability>,
+) -> Vec<Capability> {
+    let mut matches: Vec<_> = capabilities
+        .into_iter()
+        .filter_map(|capability| Some((ranker.score(&params.query, &capability)?, capability)))
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    matches
+        .into_iter()
+        .take(params.limit.unwrap_or(DEFAULT_LIMIT))
+        .map(|(_, capability)| capability)
+        .collect()
+}
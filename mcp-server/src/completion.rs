@@ -0,0 +1,128 @@
+use crate::Error;
+use serde_json::json;
+
+/// Most values a completion may hold
+const MAX_VALUES: usize = 100;
+
+/// What a `completion/complete` request completes an argument of
+pub(crate) enum Reference {
+    Prompt(String),
+    Resource(String),
+}
+
+pub(crate) struct Completion {
+    pub reference: Reference,
+    pub argument: String,
+    pub value: String,
+}
+
+/// Reads a completion request by its JSON fields, which the schema names after the spec
+pub(crate) fn parse(request: &mcp_schema::CompleteParams) -> Result<Completion, Error> {
+    let request = serde_json::to_value(request)?;
+    let field = |pointer: &str| {
+        request
+            .pointer(pointer)
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| {
+                Error::invalid_params(format!("Completion request is missing '{pointer}'"))
+            })
+    };
+
+    let reference = match field("/ref/type")? {
+        "ref/prompt" => Reference::Prompt(field("/ref/name")?.to_string()),
+        "ref/resource" => Reference::Resource(field("/ref/uri")?.to_string()),
+        kind => {
+            return Err(Error::invalid_params(format!(
+                "Unknown completion reference '{kind}'"
+            )));
+        }
+    };
+    Ok(Completion {
+        reference,
+        argument: field("/argument/name")?.to_string(),
+        value: field("/argument/value")?.to_string(),
+    })
+}
+
+/// Replaces the name of the prompt a completion request references
+pub(crate) fn rename_prompt(
+    request: mcp_schema::CompleteParams,
+    name: &str,
+) -> Result<mcp_schema::CompleteParams, Error> {
+    let mut request = serde_json::to_value(request)?;
+    if let Some(reference) = request.pointer_mut("/ref/name") {
+        *reference = json!(name);
+    }
+    Ok(serde_json::from_value(request)?)
+}
+
+/// Suggested values for a `completion/complete` request. At most 100 values are sent, and when
+/// there are more, the result says how many there are in total.
+///
+/// ```ignore
+/// let cities = search_cities(&value).await;
+/// CompletionResult::new().values(cities.iter().take(10).cloned()).total(cities.len())
+/// ```
+#[derive(Default)]
+pub struct CompletionResult {
+    values: Vec<String>,
+    total: Option<usize>,
+    has_more: bool,
+}
+
+impl CompletionResult {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.values.push(value.into());
+        self
+    }
+
+    #[must_use]
+    pub fn values(mut self, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.values.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the number of values there are, for providers that only return some of them. This is
+    /// the number of values by default.
+    #[must_use]
+    pub const fn total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Says there are more values than were returned, for providers that don't know how many
+    #[must_use]
+    pub const fn has_more(mut self, has_more: bool) -> Self {
+        self.has_more = has_more;
+        self
+    }
+
+    /// # Errors
+    /// If the result doesn't match the schema, this will error.
+    pub fn build(mut self) -> Result<mcp_schema::CompleteResult, Error> {
+        let total = self
+            .total
+            .unwrap_or(self.values.len())
+            .max(self.values.len());
+        self.values.truncate(MAX_VALUES);
+        Ok(serde_json::from_value(json!({
+            "completion": {
+                "values": self.values,
+                "total": total,
+                "hasMore": self.has_more || total > self.values.len(),
+            }
+        }))?)
+    }
+}
+
+impl From<Vec<String>> for CompletionResult {
+    fn from(values: Vec<String>) -> Self {
+        Self::new().values(values)
+    }
+}
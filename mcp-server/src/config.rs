@@ -0,0 +1,583 @@
+use crate::content::text;
+use crate::{Error, Service};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+type NotificationHandler = Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>;
+
+/// Tools, prompts and resources declared in a JSON or TOML file instead of code
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub tools: Vec<ToolConfig>,
+    pub prompts: Vec<PromptConfig>,
+    pub resources: Vec<ResourceConfig>,
+}
+
+/// A tool answering with fixed text, in which `{argument}` is replaced by the argument's value
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default = "object_schema")]
+    pub input_schema: serde_json::Value,
+    pub response: String,
+}
+
+/// A prompt of a single user message, in which `{argument}` is replaced by the argument's value
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PromptConfig {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgumentConfig>,
+    pub template: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PromptArgumentConfig {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A text resource at a fixed uri
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceConfig {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    pub text: String,
+}
+
+fn object_schema() -> serde_json::Value {
+    serde_json::json!({ "type": "object" })
+}
+
+impl Config {
+    /// Reads a configuration file, parsed as TOML if its extension is `toml` and as JSON
+    /// otherwise
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed, this will error.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::internal(format!("Failed to read {}: {e}", path.display())))?;
+
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            Self::parse_toml(&contents)
+        } else {
+            serde_json::from_str(&contents).map_err(|e| {
+                Error::internal(format!("Invalid configuration in {}: {e}", path.display()))
+            })
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    fn parse_toml(contents: &str) -> Result<Self, Error> {
+        toml::from_str(contents).map_err(|e| Error::internal(format!("Invalid configuration: {e}")))
+    }
+
+    #[cfg(not(feature = "toml"))]
+    fn parse_toml(_contents: &str) -> Result<Self, Error> {
+        Err(Error::internal(
+            "TOML configuration requires the toml feature",
+        ))
+    }
+
+    fn resource(&self, uri: &str) -> Option<&ResourceConfig> {
+        self.resources.iter().find(|resource| resource.uri == uri)
+    }
+}
+
+/// Replaces every `{name}` in the template with the value of the argument, with strings
+/// inserted as they are and other values as JSON
+fn render<'a>(
+    template: &str,
+    args: impl IntoIterator<Item = (&'a String, serde_json::Value)>,
+) -> String {
+    args.into_iter()
+        .fold(template.to_string(), |rendered, (name, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            };
+            rendered.replace(&format!("{{{name}}}"), &value)
+        })
+}
+
+fn not_found(kind: &str, name: &str) -> Error {
+    Error::invalid_params(format!("{kind} '{name}' not found"))
+}
+
+const fn empty_result() -> mcp_schema::EmptyResult {
+    mcp_schema::EmptyResult {
+        meta: None,
+        extra: HashMap::new(),
+    }
+}
+
+struct Inner {
+    path: PathBuf,
+    config: RwLock<Arc<Config>>,
+    modified: Mutex<Option<SystemTime>>,
+    notification_handler: RwLock<Option<NotificationHandler>>,
+    subscriptions: Mutex<HashSet<String>>,
+}
+
+impl Inner {
+    fn config(&self) -> Arc<Config> {
+        self.config.read().unwrap().clone()
+    }
+
+    fn notify(&self, method: &str) {
+        let Some(handler) = self.notification_handler.read().unwrap().clone() else {
+            return;
+        };
+        match serde_json::from_value(serde_json::json!({
+            "jsonrpc": mcp_schema::JSONRPC_VERSION,
+            "method": method,
+        })) {
+            Ok(notification) => handler(notification),
+            Err(e) => warn!("Failed to create {method} notification: {e}"),
+        }
+    }
+
+    fn notify_updated(&self, uri: &str) {
+        let Some(handler) = self.notification_handler.read().unwrap().clone() else {
+            return;
+        };
+        handler(mcp_schema::ServerNotification::ResourceUpdated {
+            json_rpc: mcp_schema::JSONRPC_VERSION.to_string(),
+            params: mcp_schema::ResourceUpdatedParams {
+                uri: uri.to_string(),
+                extra: HashMap::new(),
+            },
+        });
+    }
+
+    fn reload(&self) -> Result<(), Error> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let config = Arc::new(Config::load(&self.path)?);
+        *self.modified.lock().unwrap() = modified;
+        let previous = std::mem::replace(&mut *self.config.write().unwrap(), config.clone());
+
+        if previous.tools != config.tools {
+            self.notify("notifications/tools/list_changed");
+        }
+        if previous.prompts != config.prompts {
+            self.notify("notifications/prompts/list_changed");
+        }
+        let listed = |config: &Config| {
+            config
+                .resources
+                .iter()
+                .map(|resource| (&resource.uri, &resource.name, &resource.description))
+                .collect::<Vec<_>>()
+        };
+        if listed(&previous) != listed(&config) {
+            self.notify("notifications/resources/list_changed");
+        }
+
+        let subscriptions = self.subscriptions.lock().unwrap().clone();
+        for uri in subscriptions {
+            if previous.resource(&uri) != config.resource(&uri) {
+                self.notify_updated(&uri);
+            }
+        }
+
+        info!("Reloaded configuration from {}", self.path.display());
+        Ok(())
+    }
+
+    /// Reloads the configuration if the file was modified since it was last loaded
+    fn poll(&self) {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_none() || modified == *self.modified.lock().unwrap() {
+            return;
+        }
+        // A broken file keeps the previous configuration until it is fixed
+        if let Err(e) = self.reload() {
+            warn!("Keeping the previous configuration: {}", e.message);
+            *self.modified.lock().unwrap() = modified;
+        }
+    }
+}
+
+/// A service of tools, prompts and resources declared in a configuration file, see [`Config`].
+/// While watching, changes to the file are applied without restarting the server: the new
+/// configuration replaces the old one at once, and clients are notified of the lists that changed
+/// and of subscribed resources that were updated.
+///
+/// ```ignore
+/// let service = ConfigService::watch("tools.toml", Duration::from_secs(1))?;
+/// mcp::serve_over_stdio(service).await?;
+/// ```
+///
+/// Mount it with [`crate::ServiceRouter`] to serve it next to tools defined in code.
+pub struct ConfigService {
+    inner: Arc<Inner>,
+    watcher: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ConfigService {
+    /// Loads the configuration once, without watching for changes
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed, this will error.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let inner = Arc::new(Inner {
+            path: path.into(),
+            config: RwLock::default(),
+            modified: Mutex::new(None),
+            notification_handler: RwLock::new(None),
+            subscriptions: Mutex::new(HashSet::new()),
+        });
+        inner.reload()?;
+        Ok(Self {
+            inner,
+            watcher: Mutex::new(None),
+        })
+    }
+
+    /// Loads the configuration, then checks the file for changes at every interval. This must be
+    /// called within a Tokio runtime.
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed initially, this will error.
+    pub fn watch(path: impl Into<PathBuf>, interval: Duration) -> Result<Self, Error> {
+        let service = Self::load(path)?;
+        let inner: Weak<Inner> = Arc::downgrade(&service.inner);
+        let watcher = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let Some(inner) = inner.upgrade() else {
+                    break;
+                };
+                inner.poll();
+            }
+        });
+        *service.watcher.lock().unwrap() = Some(watcher);
+        Ok(service)
+    }
+
+    /// Reloads the configuration now, such as on `SIGHUP`
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed, this will error and the previous configuration is
+    /// kept.
+    pub fn reload(&self) -> Result<(), Error> {
+        self.inner.reload()
+    }
+
+    /// The configuration currently served
+    pub fn config(&self) -> Arc<Config> {
+        self.inner.config()
+    }
+}
+
+impl Service for ConfigService {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    ) {
+        *self.inner.notification_handler.write().unwrap() = Some(handler.into());
+    }
+
+    fn init(
+        &self,
+        _request: mcp_schema::InitializeParams,
+    ) -> impl Future<Output = Result<mcp_schema::InitializeResult, Error>> + Send {
+        let result = mcp_schema::InitializeResult {
+            capabilities: mcp_schema::ServerCapabilities {
+                experimental: None,
+                logging: None,
+                prompts: Some(mcp_schema::PromptsCapability {
+                    list_changed: Some(true),
+                }),
+                resources: Some(mcp_schema::ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: Some(true),
+                }),
+                tools: Some(mcp_schema::ToolsCapability {
+                    list_changed: Some(true),
+                }),
+                extra: HashMap::new(),
+            },
+            instructions: None,
+            meta: None,
+            protocol_version: mcp_schema::LATEST_PROTOCOL_VERSION.to_string(),
+            server_info: mcp_schema::Implementation {
+                name: "config".to_string(),
+                version: "0.1.0".to_string(),
+                extra: HashMap::new(),
+            },
+            extra: HashMap::new(),
+        };
+
+        async move { Ok(result) }
+    }
+
+    fn ping(
+        &self,
+        _: mcp_schema::PingParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move { Ok(empty_result()) }
+    }
+
+    fn list_resources(
+        &self,
+        _request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourcesResult, Error>> + Send {
+        let resources = self
+            .inner
+            .config()
+            .resources
+            .iter()
+            .map(|resource| mcp_schema::Resource {
+                uri: resource.uri.clone(),
+                name: resource.name.clone(),
+                description: resource.description.clone(),
+                mime_type: resource.mime_type.clone(),
+                annotated: mcp_schema::Annotated {
+                    annotations: None,
+                    extra: HashMap::new(),
+                },
+            })
+            .collect();
+
+        async move {
+            Ok(mcp_schema::ListResourcesResult {
+                meta: None,
+                next_cursor: None,
+                resources,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn list_resource_templates(
+        &self,
+        _request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourceTemplatesResult, Error>> + Send {
+        async move {
+            Ok(mcp_schema::ListResourceTemplatesResult {
+                meta: None,
+                next_cursor: None,
+                resource_templates: Vec::new(),
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + Send {
+        let config = self.inner.config();
+        let result = config
+            .resource(&request.uri)
+            .ok_or_else(|| not_found("Resource", &request.uri))
+            .map(|resource| mcp_schema::ReadResourceResult {
+                meta: None,
+                contents: vec![mcp_schema::ResourceContents::Text(
+                    mcp_schema::TextResourceContents {
+                        uri: resource.uri.clone(),
+                        mime_type: resource.mime_type.clone(),
+                        text: resource.text.clone(),
+                    },
+                )],
+                extra: HashMap::new(),
+            });
+
+        async move { result }
+    }
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        let result = match self.inner.config().resource(&request.uri) {
+            Some(_) => {
+                self.inner.subscriptions.lock().unwrap().insert(request.uri);
+                Ok(empty_result())
+            }
+            None => Err(not_found("Resource", &request.uri)),
+        };
+
+        async move { result }
+    }
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.inner
+            .subscriptions
+            .lock()
+            .unwrap()
+            .remove(&request.uri);
+
+        async move { Ok(empty_result()) }
+    }
+
+    fn list_prompts(
+        &self,
+        _request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListPromptsResult, Error>> + Send {
+        let prompts = self
+            .inner
+            .config()
+            .prompts
+            .iter()
+            .map(|prompt| mcp_schema::Prompt {
+                description: prompt.description.clone(),
+                arguments: Some(
+                    prompt
+                        .arguments
+                        .iter()
+                        .map(|argument| mcp_schema::PromptArgument {
+                            name: argument.name.clone(),
+                            description: argument.description.clone(),
+                            required: Some(argument.required),
+                            extra: HashMap::new(),
+                        })
+                        .collect(),
+                ),
+                name: prompt.name.clone(),
+                extra: HashMap::new(),
+            })
+            .collect();
+
+        async move {
+            Ok(mcp_schema::ListPromptsResult {
+                meta: None,
+                next_cursor: None,
+                prompts,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + Send {
+        let config = self.inner.config();
+        let result = config
+            .prompts
+            .iter()
+            .find(|prompt| prompt.name == request.name)
+            .ok_or_else(|| not_found("Prompt", &request.name))
+            .map(|prompt| {
+                let arguments = request.arguments.unwrap_or_default();
+                mcp_schema::GetPromptResult {
+                    meta: None,
+                    description: prompt.description.clone(),
+                    messages: vec![mcp_schema::PromptMessage {
+                        role: mcp_schema::Role::User,
+                        content: text(render(
+                            &prompt.template,
+                            arguments
+                                .iter()
+                                .map(|(name, value)| (name, value.clone().into())),
+                        )),
+                    }],
+                    extra: HashMap::new(),
+                }
+            });
+
+        async move { result }
+    }
+
+    fn list_tools(
+        &self,
+        _request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListToolsResult, Error>> + Send {
+        let tools = self
+            .inner
+            .config()
+            .tools
+            .iter()
+            .map(|tool| {
+                Ok(mcp_schema::Tool {
+                    description: tool.description.clone(),
+                    input_schema: serde_json::from_value(tool.input_schema.clone())?,
+                    name: tool.name.clone(),
+                    extra: HashMap::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>();
+
+        async move {
+            Ok(mcp_schema::ListToolsResult {
+                meta: None,
+                next_cursor: None,
+                tools: tools?,
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send {
+        let config = self.inner.config();
+        let result = config
+            .tools
+            .iter()
+            .find(|tool| tool.name == request.name)
+            .ok_or_else(|| not_found("Tool", &request.name))
+            .map(|tool| {
+                let arguments = request.arguments.unwrap_or_default();
+                mcp_schema::CallToolResult {
+                    meta: None,
+                    content: vec![text(render(
+                        &tool.response,
+                        arguments.iter().map(|(name, value)| (name, value.clone())),
+                    ))],
+                    is_error: Some(false),
+                    extra: HashMap::new(),
+                }
+            });
+
+        async move { result }
+    }
+
+    fn set_level(
+        &self,
+        _request: mcp_schema::SetLevelParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move { Ok(empty_result()) }
+    }
+
+    fn active_subscriptions(&self) -> usize {
+        self.inner.subscriptions.lock().unwrap().len()
+    }
+
+    fn shutdown(&self) -> impl Future<Output = ()> + Send {
+        if let Some(watcher) = self.watcher.lock().unwrap().take() {
+            watcher.abort();
+        }
+
+        async {}
+    }
+}
@@ -0,0 +1,168 @@
+use crate::inject::Scope;
+use crate::logging::{LogSink, Logger};
+use crate::registry::FromRef;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::warn;
+
+pub(crate) struct Inner {
+    pub peer: Option<SocketAddr>,
+    pub session_id: Option<String>,
+    pub request_id: Option<serde_json::Value>,
+    pub client_capabilities: Option<Arc<mcp_schema::ClientCapabilities>>,
+    pub progress_token: Option<serde_json::Value>,
+    pub cancelled: watch::Receiver<bool>,
+    pub error_details: bool,
+    /// Sends notifications to the client that made the request
+    pub sink: LogSink,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self {
+            peer: None,
+            session_id: None,
+            request_id: None,
+            client_capabilities: None,
+            progress_token: None,
+            cancelled: watch::channel(false).1,
+            error_details: false,
+            sink: LogSink::default(),
+        }
+    }
+}
+
+/// What a handler can know about the request it is handling and the client that made it, and a
+/// way to tell that client about the request's progress. Handlers receive it by taking a
+/// `RequestContext` parameter in place of the state, and outside of a request it is empty and
+/// drops what is sent through it.
+///
+/// ```ignore
+/// async fn search(context: RequestContext, input: SearchInput) -> Result<String, Error> {
+///     for (done, page) in pages.iter().enumerate() {
+///         if context.is_cancelled() {
+///             break;
+///         }
+///         context.progress(done as f64, Some(pages.len() as f64));
+///         // ...
+///     }
+/// }
+/// ```
+pub struct RequestContext(Arc<Inner>);
+
+impl RequestContext {
+    pub(crate) fn new(inner: Inner) -> Self {
+        Self(Arc::new(inner))
+    }
+
+    /// The context of the request being handled
+    #[must_use]
+    pub fn current() -> Self {
+        Scope::current()
+            .get::<Arc<Inner>>()
+            .map_or_else(|| Self::new(Inner::default()), Self)
+    }
+
+    pub(crate) fn provide<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        crate::inject::provide(self.0.clone(), future)
+    }
+
+    /// The address of the client, when served over HTTP with connection info
+    #[must_use]
+    pub fn peer(&self) -> Option<SocketAddr> {
+        self.0.peer
+    }
+
+    /// The SSE session the request was posted for, or `None` over stdio or without a session
+    #[must_use]
+    pub fn session_id(&self) -> Option<&str> {
+        self.0.session_id.as_deref()
+    }
+
+    /// The id the client gave the request
+    #[must_use]
+    pub fn request_id(&self) -> Option<&serde_json::Value> {
+        self.0.request_id.as_ref()
+    }
+
+    /// The capabilities the client declared when it initialized the session
+    #[must_use]
+    pub fn client_capabilities(&self) -> Option<&mcp_schema::ClientCapabilities> {
+        self.0.client_capabilities.as_deref()
+    }
+
+    /// Whether the client declared that it can list its roots, the directories or files it wants
+    /// the server to work in
+    #[must_use]
+    pub fn supports_roots(&self) -> bool {
+        self.client_capabilities()
+            .and_then(|capabilities| serde_json::to_value(capabilities).ok())
+            .is_some_and(|capabilities| {
+                capabilities
+                    .get("roots")
+                    .is_some_and(|roots| !roots.is_null())
+            })
+    }
+
+    /// The token the client asked to receive progress notifications with
+    #[must_use]
+    pub fn progress_token(&self) -> Option<&serde_json::Value> {
+        self.0.progress_token.as_ref()
+    }
+
+    /// Tells the client how far along the request is, if it asked for progress with a progress
+    /// token. `progress` must increase with each call, and `total` is given if it is known.
+    pub fn progress(&self, progress: f64, total: Option<f64>) {
+        let (Some(token), Some(handler)) = (&self.0.progress_token, self.0.sink.get()) else {
+            return;
+        };
+        let mut params = serde_json::json!({ "progressToken": token, "progress": progress });
+        if let Some(total) = total {
+            params["total"] = total.into();
+        }
+        let notification = serde_json::from_value(serde_json::json!({
+            "jsonrpc": mcp_schema::JSONRPC_VERSION,
+            "method": "notifications/progress",
+            "params": params,
+        }));
+        match notification {
+            Ok(notification) => handler(notification),
+            Err(e) => warn!("Failed to create progress notification: {e}"),
+        }
+    }
+
+    /// A logger whose messages are only sent to the client that made the request, at or above
+    /// the level its session set, unlike [`crate::BasicService::logger`] which logs to every
+    /// client
+    #[must_use]
+    pub fn logger(&self, name: impl Into<String>) -> Logger {
+        Logger::new(Some(name.into()), self.0.sink.clone())
+    }
+
+    /// Whether the client cancelled the request. Handlers stop being polled once it is cancelled,
+    /// so this is for work they spawned or do synchronously.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.cancelled.borrow()
+    }
+
+    /// Whether the causes of errors are sent to the client, see [`crate::McpImpl::error_details`]
+    pub(crate) fn error_details(&self) -> bool {
+        self.0.error_details
+    }
+
+    /// Waits until the client cancels the request, which never happens for requests that finish
+    pub async fn cancelled(&self) {
+        let mut cancelled = self.0.cancelled.clone();
+        if cancelled.wait_for(|cancelled| *cancelled).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl<State> FromRef<State> for RequestContext {
+    fn from_ref(_: &State) -> Self {
+        Self::current()
+    }
+}
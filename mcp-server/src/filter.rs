@@ -0,0 +1,373 @@
+use base64::Engine;
+use mcp_schema::{PromptContent, ResourceContents};
+use std::sync::Arc;
+
+/// A filter applied to every piece of content leaving the server in tool results and prompts
+pub trait ContentFilter {
+    /// Returns the filtered content, or [`None`] if the content should be removed entirely
+    fn filter(&self, content: PromptContent) -> Option<PromptContent>;
+}
+
+impl<F> ContentFilter for F
+where
+    F: Fn(PromptContent) -> Option<PromptContent>,
+{
+    fn filter(&self, content: PromptContent) -> Option<PromptContent> {
+        self(content)
+    }
+}
+
+pub(crate) type SharedContentFilter = Arc<dyn ContentFilter + Send + Sync>;
+
+fn apply(filters: &[SharedContentFilter], content: PromptContent) -> Option<PromptContent> {
+    filters
+        .iter()
+        .try_fold(content, |content, filter| filter.filter(content))
+}
+
+/// Filters structured content as JSON text, so that it can't leak what the filters removed from
+/// the content. It is dropped if the filters remove it or leave it invalid JSON.
+fn filter_structured(
+    filters: &[SharedContentFilter],
+    structured: serde_json::Value,
+) -> Option<serde_json::Value> {
+    if filters.is_empty() {
+        return Some(structured);
+    }
+    let PromptContent::Text(text) = apply(filters, crate::content::text(structured.to_string()))?
+    else {
+        return None;
+    };
+    serde_json::from_str(&text.text).ok()
+}
+
+pub(crate) fn filter_tool_result(
+    filters: &[SharedContentFilter],
+    forms: ContentForms,
+    mut result: mcp_schema::CallToolResult,
+) -> mcp_schema::CallToolResult {
+    result.content = result
+        .content
+        .into_iter()
+        .filter_map(|content| apply(filters, content))
+        .map(|content| forms.downconvert(content))
+        .collect();
+    if let Some(structured) = result.extra.remove("structuredContent") {
+        if let Some(structured) = filter_structured(filters, structured) {
+            result
+                .extra
+                .insert("structuredContent".to_string(), structured);
+        }
+    }
+    result
+}
+
+pub(crate) fn filter_prompt_result(
+    filters: &[SharedContentFilter],
+    forms: ContentForms,
+    mut result: mcp_schema::GetPromptResult,
+) -> mcp_schema::GetPromptResult {
+    result.messages = result
+        .messages
+        .into_iter()
+        .filter_map(|message| {
+            Some(mcp_schema::PromptMessage {
+                role: message.role,
+                content: forms.downconvert(apply(filters, message.content)?),
+            })
+        })
+        .collect();
+    result
+}
+
+/// Replaces every match of a regular expression in text content, such as email addresses or
+/// access tokens
+pub struct RegexScrubber {
+    regex: regex::Regex,
+    replacement: String,
+}
+
+impl RegexScrubber {
+    /// # Errors
+    /// If the pattern is not a valid regular expression, this will error.
+    pub fn new(pattern: &str, replacement: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: regex::Regex::new(pattern)?,
+            replacement: replacement.into(),
+        })
+    }
+
+    /// Scrubs email addresses
+    #[must_use]
+    pub fn emails() -> Self {
+        Self::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", "[email]").unwrap()
+    }
+
+    fn scrub(&self, text: &mut String) {
+        if let std::borrow::Cow::Owned(scrubbed) =
+            self.regex.replace_all(text, self.replacement.as_str())
+        {
+            *text = scrubbed;
+        }
+    }
+}
+
+impl ContentFilter for RegexScrubber {
+    fn filter(&self, mut content: PromptContent) -> Option<PromptContent> {
+        match &mut content {
+            PromptContent::Text(text) => self.scrub(&mut text.text),
+            PromptContent::Resource(resource) => {
+                if let ResourceContents::Text(text) = &mut resource.resource {
+                    self.scrub(&mut text.text);
+                }
+            }
+            PromptContent::Image(_) => {}
+        }
+        Some(content)
+    }
+}
+
+/// Allows or denies images and embedded resources by MIME type. Patterns may end in `/*` to match
+/// a whole family of types, such as `image/*`.
+pub struct MimeTypeFilter {
+    patterns: Vec<String>,
+    allow: bool,
+}
+
+impl MimeTypeFilter {
+    /// Only content with a MIME type matching one of the patterns is kept
+    #[must_use]
+    pub fn allow(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+            allow: true,
+        }
+    }
+
+    /// Content with a MIME type matching one of the patterns is removed
+    #[must_use]
+    pub fn deny(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+            allow: false,
+        }
+    }
+
+    fn matches(&self, mime_type: &str) -> bool {
+        self.patterns.iter().any(|pattern| {
+            pattern.strip_suffix("/*").map_or_else(
+                || pattern.eq_ignore_ascii_case(mime_type),
+                |family| {
+                    mime_type
+                        .split_once('/')
+                        .is_some_and(|(kind, _)| kind.eq_ignore_ascii_case(family))
+                },
+            )
+        })
+    }
+}
+
+impl ContentFilter for MimeTypeFilter {
+    fn filter(&self, content: PromptContent) -> Option<PromptContent> {
+        let mime_type = match &content {
+            PromptContent::Text(_) => return Some(content),
+            PromptContent::Image(image) => Some(image.mime_type.as_str()),
+            PromptContent::Resource(resource) => match &resource.resource {
+                ResourceContents::Text(text) => text.mime_type.as_deref(),
+                ResourceContents::Blob(blob) => blob.mime_type.as_deref(),
+            },
+        };
+
+        // Content without a MIME type only passes a deny list
+        let matches = mime_type.is_some_and(|mime_type| self.matches(mime_type));
+        (matches == self.allow).then_some(content)
+    }
+}
+
+/// Replaces images larger than the given dimensions with a short text notice
+pub struct MaxImageDimensions {
+    width: u32,
+    height: u32,
+}
+
+impl MaxImageDimensions {
+    #[must_use]
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+}
+
+impl ContentFilter for MaxImageDimensions {
+    fn filter(&self, content: PromptContent) -> Option<PromptContent> {
+        let PromptContent::Image(image) = &content else {
+            return Some(content);
+        };
+
+        let Ok(data) = base64::engine::general_purpose::STANDARD.decode(&image.data) else {
+            return Some(content);
+        };
+
+        match image_dimensions(&data) {
+            Some((width, height)) if width > self.width || height > self.height => {
+                Some(crate::content::text(format!(
+                    "[image removed: {width}x{height} exceeds the maximum of {}x{}]",
+                    self.width, self.height
+                )))
+            }
+            _ => Some(content),
+        }
+    }
+}
+
+/// Reads the dimensions of a PNG, GIF or JPEG image from its header
+fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let be16 = |at: usize| {
+        Some(u32::from(u16::from_be_bytes(
+            data.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let le16 = |at: usize| {
+        Some(u32::from(u16::from_le_bytes(
+            data.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+
+    if data.starts_with(b"GIF8") {
+        return Some((le16(6)?, le16(8)?));
+    }
+
+    if data.starts_with(&[0xFF, 0xD8]) {
+        let mut at = 2;
+        while *data.get(at)? == 0xFF {
+            let marker = *data.get(at + 1)?;
+            let length = usize::try_from(be16(at + 2)?).ok()?;
+            // Start of frame markers, excluding DHT (C4), JPG (C8) and DAC (CC)
+            if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                return Some((be16(at + 7)?, be16(at + 5)?));
+            }
+            at += 2 + length;
+        }
+    }
+
+    None
+}
+
+/// The forms of content a client declared it can render, read from
+/// `capabilities.experimental.contentForms` of its initialize request. Anything the client did not
+/// declare is assumed to be supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct ContentForms {
+    /// Whether image content can be displayed
+    pub images: bool,
+    /// Whether embedded resources can be displayed
+    pub resources: bool,
+    /// Whether JSON text is useful as-is rather than rendered as markdown
+    pub structured: bool,
+}
+
+impl Default for ContentForms {
+    fn default() -> Self {
+        Self {
+            images: true,
+            resources: true,
+            structured: true,
+        }
+    }
+}
+
+impl ContentForms {
+    /// Reads the content forms declared by the client in its initialize request
+    #[must_use]
+    pub fn from_initialize(params: &mcp_schema::InitializeParams) -> Self {
+        Self::from_capabilities(&params.capabilities)
+    }
+
+    /// Reads the content forms declared in the capabilities of a client
+    #[must_use]
+    pub fn from_capabilities(capabilities: &mcp_schema::ClientCapabilities) -> Self {
+        serde_json::to_value(capabilities)
+            .ok()
+            .and_then(|capabilities| capabilities.pointer("/experimental/contentForms").cloned())
+            .and_then(|forms| serde_json::from_value(forms).ok())
+            .unwrap_or_default()
+    }
+
+    /// The content forms of the client whose session the current request belongs to
+    #[must_use]
+    pub fn current() -> Self {
+        crate::context::RequestContext::current()
+            .client_capabilities()
+            .map_or_else(Self::default, Self::from_capabilities)
+    }
+
+    /// Converts content the client cannot render into text
+    #[must_use]
+    pub fn downconvert(&self, content: PromptContent) -> PromptContent {
+        match content {
+            PromptContent::Image(image) if !self.images => crate::content::text(format!(
+                "[image: {}, {} bytes base64]",
+                image.mime_type,
+                image.data.len()
+            )),
+            PromptContent::Resource(resource) if !self.resources => match resource.resource {
+                ResourceContents::Text(text) => crate::content::text(text.text),
+                ResourceContents::Blob(blob) => crate::content::text(format!(
+                    "[resource {}: {} bytes base64]",
+                    blob.uri,
+                    blob.blob.len()
+                )),
+            },
+            PromptContent::Text(mut text) if !self.structured => {
+                if let Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) =
+                    serde_json::from_str(&text.text)
+                {
+                    let mut markdown = String::new();
+                    render_markdown(&value, 0, &mut markdown);
+                    text.text = markdown;
+                }
+                PromptContent::Text(text)
+            }
+            content => content,
+        }
+    }
+}
+
+fn render_markdown(value: &serde_json::Value, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object {
+                if value.is_object() || value.is_array() {
+                    out.push_str(&format!("{indent}- **{key}**:\n"));
+                    render_markdown(value, depth + 1, out);
+                } else {
+                    out.push_str(&format!("{indent}- **{key}**: {}\n", scalar(value)));
+                }
+            }
+        }
+        serde_json::Value::Array(array) => {
+            for value in array {
+                if value.is_object() || value.is_array() {
+                    out.push_str(&format!("{indent}-\n"));
+                    render_markdown(value, depth + 1, out);
+                } else {
+                    out.push_str(&format!("{indent}- {}\n", scalar(value)));
+                }
+            }
+        }
+        value => out.push_str(&format!("{indent}{}\n", scalar(value))),
+    }
+}
+
+fn scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,94 @@
+use crate::Error;
+use crate::registry::FromRef;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    static SCOPE: Scope;
+}
+
+/// Values resolved by type for a single request, such as the auth principal, locale or deadline,
+/// layered over the singletons provided to the service. Handlers receive it by taking a `Scope`
+/// parameter in place of the state.
+#[derive(Default)]
+pub struct Scope {
+    values: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl Scope {
+    /// Adds a value, replacing any earlier value of the same type
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        Arc::make_mut(&mut self.values).insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    #[must_use]
+    pub fn get<T: Clone + 'static>(&self) -> Option<T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Gets a value that the handler can't run without
+    ///
+    /// # Errors
+    /// If no value of this type was provided, this will error.
+    pub fn require<T: Clone + 'static>(&self) -> Result<T, Error> {
+        self.get().ok_or_else(|| {
+            Error::internal(format!("No {} was provided", std::any::type_name::<T>()))
+        })
+    }
+
+    /// Adds every value of another scope, replacing values of the same types
+    pub(crate) fn extend(&mut self, other: &Self) {
+        if other.values.is_empty() {
+            return;
+        }
+        let values = Arc::make_mut(&mut self.values);
+        for (type_id, value) in other.values.iter() {
+            values.insert(*type_id, value.clone());
+        }
+    }
+
+    /// The scope of the request being handled, or an empty scope outside of a request
+    #[must_use]
+    pub fn current() -> Self {
+        SCOPE.try_with(Self::share).unwrap_or_default()
+    }
+
+    /// A cheap copy sharing the same values. This isn't [`Clone`] so that `Scope` can be
+    /// extracted with [`FromRef`] from any state.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<State> FromRef<State> for Scope {
+    fn from_ref(_: &State) -> Self {
+        Self::current()
+    }
+}
+
+/// Runs a future with a value in the scope of every request it handles. This is how transport
+/// layers, such as authentication, pass values to handlers.
+pub(crate) fn provide<T: Send + Sync + 'static, F: Future>(
+    value: T,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    let mut scope = Scope::current();
+    scope.insert(value);
+    SCOPE.scope(scope, future)
+}
+
+/// Makes the scope current while the handler is created and while its future runs
+pub(crate) fn scoped<F: Future>(
+    scope: Scope,
+    f: impl FnOnce() -> F,
+) -> impl Future<Output = F::Output> {
+    let future = SCOPE.sync_scope(scope.share(), f);
+    SCOPE.scope(scope, future)
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Number of most recent calls percentiles are computed over
+const WINDOW: usize = 256;
+
+/// Latency percentiles over the most recent calls of a tool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+}
+
+/// A rolling window of call durations
+#[derive(Debug, Default)]
+pub(crate) struct LatencyWindow {
+    samples: Mutex<VecDeque<Duration>>,
+}
+
+impl LatencyWindow {
+    pub(crate) fn record(&self, duration: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back(duration);
+    }
+
+    pub(crate) fn stats(&self) -> Option<LatencyStats> {
+        let mut samples: Vec<_> = self.samples.lock().unwrap().iter().copied().collect();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+
+        let percentile = |p: usize| {
+            let index = (samples.len() * p).div_ceil(100).saturating_sub(1);
+            u64::try_from(samples[index].as_millis()).unwrap_or(u64::MAX)
+        };
+
+        Some(LatencyStats {
+            samples: samples.len(),
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            p99_ms: percentile(99),
+        })
+    }
+}
@@ -0,0 +1,174 @@
+pub mod auth;
+pub mod basic_service;
+pub mod catalog;
+pub mod completion;
+pub mod config;
+pub mod context;
+pub mod filter;
+pub mod inject;
+pub mod latency;
+pub mod logging;
+mod macros;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
+pub mod notifier;
+#[cfg(feature = "oauth")]
+pub mod oauth;
+pub mod options;
+#[cfg(feature = "otel")]
+mod otel;
+pub mod prelude;
+pub mod rate_limit;
+pub mod registry;
+pub mod resources;
+pub mod router;
+pub mod rpc;
+pub mod self_check;
+pub mod service;
+mod subscriptions;
+#[cfg(feature = "templates")]
+pub mod template_prompt;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod wire;
+
+use axum::Router;
+use axum::extract::Request;
+use axum::response::IntoResponse;
+use axum::routing::Route;
+pub use basic_service::BasicService;
+pub use mcp_core::{Error, ErrorCode, content, error};
+pub use options::{Overflow, ServerOptions};
+pub use registry::{
+    FromRef, Prompt, PromptRegistry, Resource, ResourceRegistry, Tool, ToolRegistry,
+};
+pub use router::ServiceRouter;
+pub use rpc::McpImpl;
+pub use service::{BoxService, DynService, Service};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::Layer;
+use tower::layer::util::Identity;
+
+/// # Errors
+/// An error will occur if an I/O error occurs in stdio or stdin.
+pub async fn serve_over_stdio<S: Service + Send + Sync + 'static>(
+    service: S,
+) -> std::io::Result<()> {
+    let service = Arc::new(McpImpl::new(service));
+    let result = service.clone().serve_over_stdio().await;
+    service.shutdown().await;
+    result
+}
+
+/// # Errors
+/// An error will occur if an I/O error occurs in the network.
+pub async fn serve_over_sse<S: Service + Send + Sync + 'static>(
+    listener: tokio::net::TcpListener,
+    service: S,
+) -> std::io::Result<()> {
+    serve_over_sse_with_shutdown(listener, service, std::future::pending()).await
+}
+
+/// Serves over SSE until the signal completes, then waits for open connections to finish and shuts
+/// down the service
+///
+/// # Errors
+/// An error will occur if an I/O error occurs in the network.
+pub async fn serve_over_sse_with_shutdown<S: Service + Send + Sync + 'static>(
+    listener: tokio::net::TcpListener,
+    service: S,
+    signal: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    serve_over_sse_with_layer(
+        listener,
+        service,
+        ServerOptions::default(),
+        Identity::new(),
+        signal,
+    )
+    .await
+}
+
+/// Serves over SSE with the given options
+///
+/// # Errors
+/// An error will occur if an I/O error occurs in the network.
+pub async fn serve_over_sse_with_options<S: Service + Send + Sync + 'static>(
+    listener: tokio::net::TcpListener,
+    service: S,
+    options: ServerOptions,
+) -> std::io::Result<()> {
+    serve_over_sse_with_layer(
+        listener,
+        service,
+        options,
+        Identity::new(),
+        std::future::pending(),
+    )
+    .await
+}
+
+/// Serves over SSE like [`serve_over_sse_with_shutdown`], with the given options and a tower
+/// layer such as auth, tracing, timeouts or body limits wrapped around every route. CORS and
+/// origin validation are handled outside of the layer, so preflight requests aren't affected by
+/// it.
+///
+/// # Errors
+/// An error will occur if an I/O error occurs in the network.
+pub async fn serve_over_sse_with_layer<S, L>(
+    listener: tokio::net::TcpListener,
+    service: S,
+    options: ServerOptions,
+    layer: L,
+    signal: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()>
+where
+    S: Service + Send + Sync + 'static,
+    L: Layer<Route> + Clone + Send + Sync + 'static,
+    L::Service: tower::Service<Request> + Clone + Send + Sync + 'static,
+    <L::Service as tower::Service<Request>>::Response: IntoResponse + 'static,
+    <L::Service as tower::Service<Request>>::Error: Into<Infallible> + 'static,
+    <L::Service as tower::Service<Request>>::Future: Send + 'static,
+{
+    let mut service = match options.channel_capacity_config() {
+        Some(capacity) => McpImpl::with_capacity(service, capacity),
+        None => McpImpl::new(service),
+    }
+    .keep_alive(options.keep_alive_config())
+    .on_overflow(options.overflow_config())
+    .replay_notifications(options.replay_notifications_config())
+    .error_details(options.error_details_config());
+    if let Some(limit) = options.rate_limit_config() {
+        service = service.rate_limit(limit);
+    }
+    if let Some(bytes) = options.max_message_size_config() {
+        service = service.max_message_size(bytes);
+    }
+    if let Some(limit) = options.max_subscriptions_config() {
+        service = service.max_subscriptions(limit);
+    }
+    let service = Arc::new(service);
+
+    let app = Router::new()
+        .nest("/api", service.clone().into_router())
+        .layer(layer);
+    #[cfg(feature = "metrics")]
+    let app = if options.serves_metrics() {
+        app.merge(service.clone().metrics_router())
+    } else {
+        app
+    };
+    let app = options.apply(app);
+
+    let result = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(signal)
+    .await;
+    service.shutdown().await;
+    result
+}
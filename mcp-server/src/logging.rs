@@ -0,0 +1,116 @@
+use serde::Serialize;
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+/// Log levels from the least to the most severe, as named by the spec
+const LEVELS: [&str; 8] = [
+    "debug",
+    "info",
+    "notice",
+    "warning",
+    "error",
+    "critical",
+    "alert",
+    "emergency",
+];
+
+fn severity(level: &str) -> Option<usize> {
+    LEVELS.iter().position(|known| *known == level)
+}
+
+/// The severity a `logging/setLevel` request asks for
+pub(crate) fn requested_severity(params: &mcp_schema::SetLevelParams) -> Option<usize> {
+    let params = serde_json::to_value(params).ok()?;
+    severity(params.get("level")?.as_str()?)
+}
+
+/// Whether a log message should be sent to a session that asked for messages of at least the
+/// given severity
+pub(crate) fn allows(minimum: Option<usize>, notification: &serde_json::Value) -> bool {
+    let Some(minimum) = minimum else {
+        return true;
+    };
+    notification
+        .pointer("/params/level")
+        .and_then(serde_json::Value::as_str)
+        .and_then(severity)
+        .is_none_or(|level| level >= minimum)
+}
+
+pub(crate) type LogSink = Arc<OnceLock<Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>>>;
+
+/// Sends log messages to clients as `notifications/message`. Each session only receives messages
+/// at or above the level it set with `logging/setLevel`, or every message if it set none.
+///
+/// Loggers are created with [`crate::BasicService::logger`], and messages logged before the
+/// service is served are dropped.
+#[derive(Clone)]
+pub struct Logger {
+    name: Option<String>,
+    sink: LogSink,
+}
+
+impl Logger {
+    pub(crate) const fn new(name: Option<String>, sink: LogSink) -> Self {
+        Self { name, sink }
+    }
+
+    /// Logs any serializable data, such as a string or a JSON object, at a level named as in the
+    /// spec. Messages with unknown levels are dropped.
+    pub fn log(&self, level: &str, data: impl Serialize) {
+        if severity(level).is_none() {
+            warn!("Dropping log message with unknown level '{level}'");
+            return;
+        }
+        let Some(handler) = self.sink.get() else {
+            return;
+        };
+        let notification = serde_json::to_value(data).and_then(|data| {
+            serde_json::from_value(serde_json::json!({
+                "jsonrpc": mcp_schema::JSONRPC_VERSION,
+                "method": "notifications/message",
+                "params": {
+                    "level": level,
+                    "logger": self.name,
+                    "data": data,
+                },
+            }))
+        });
+        match notification {
+            Ok(notification) => handler(notification),
+            Err(e) => warn!("Failed to create log message: {e}"),
+        }
+    }
+
+    pub fn debug(&self, data: impl Serialize) {
+        self.log("debug", data);
+    }
+
+    pub fn info(&self, data: impl Serialize) {
+        self.log("info", data);
+    }
+
+    pub fn notice(&self, data: impl Serialize) {
+        self.log("notice", data);
+    }
+
+    pub fn warning(&self, data: impl Serialize) {
+        self.log("warning", data);
+    }
+
+    pub fn error(&self, data: impl Serialize) {
+        self.log("error", data);
+    }
+
+    pub fn critical(&self, data: impl Serialize) {
+        self.log("critical", data);
+    }
+
+    pub fn alert(&self, data: impl Serialize) {
+        self.log("alert", data);
+    }
+
+    pub fn emergency(&self, data: impl Serialize) {
+        self.log("emergency", data);
+    }
+}
@@ -0,0 +1,111 @@
+/// Assembles a [`BasicService`](crate::BasicService) from a single declaration.
+///
+/// Each `tool`, `prompt` and `resource` block lists builder methods with their argument, so any
+/// method of [`ToolBuilder`](crate::registry::tool::ToolBuilder),
+/// [`PromptBuilder`](crate::registry::prompt::PromptBuilder) or
+/// [`ResourceBuilder`](crate::registry::resource::ResourceBuilder) can be used. Resources are
+/// registered with fixed uris. The macro evaluates to `Result<BasicService<_>, mcp::Error>`, which
+/// is an error if a builder fails or a name or uri is declared twice.
+///
+/// ```ignore
+/// let service = mcp::server! {
+///     name: "weather",
+///     version: "0.1.0",
+///     instructions: "Forecasts for any location",
+///     state: state,
+///     tool {
+///         name: "get_forecast",
+///         description: "Get weather forecast for a location",
+///         handler: get_forecast,
+///     },
+///     prompt {
+///         name: "forecast",
+///         handler: get_forecast_prompt,
+///     },
+///     resource {
+///         name: "history",
+///         fixed_uri: "history://temperature",
+///         source: resource,
+///     },
+/// }?;
+/// ```
+#[macro_export]
+macro_rules! server {
+    (
+        name: $name:expr,
+        version: $version:expr,
+        $(instructions: $instructions:expr,)?
+        state: $state:expr
+        $(, tool { $($tool_key:ident : $tool_value:expr),* $(,)? })*
+        $(, prompt { $($prompt_key:ident : $prompt_value:expr),* $(,)? })*
+        $(, resource { $($resource_key:ident : $resource_value:expr),* $(,)? })*
+        $(,)?
+    ) => {
+        (move || -> ::std::result::Result<_, $crate::Error> {
+            $crate::BasicService::new($state)
+                .name(::std::convert::Into::into($name))
+                .version(::std::convert::Into::into($version))
+                $(.instructions($instructions))?
+                $(.tool($crate::Tool::builder() $(.$tool_key($tool_value))* .build()?))*
+                $(.prompt($crate::Prompt::builder() $(.$prompt_key($prompt_value))* .build()?))*
+                $(.fixed_resource(
+                    $crate::Resource::builder() $(.$resource_key($resource_value))* .build()?
+                ))*
+                .unique()
+        })()
+    };
+}
+
+/// Builds a [`Tool`](crate::Tool) from an inline handler, which may capture variables such as API
+/// keys or clients. The macro evaluates to `Result<Tool<_>, mcp::Error>`.
+///
+/// Without `state`, the handler takes the service's state like any tool. With `state`, the tool
+/// keeps the value and hands each call a clone of it in place of the service's state, so the tool
+/// can be registered with any service. With `output`, the tool advertises the schema of the type
+/// as its output schema.
+///
+/// ```ignore
+/// let search = mcp::define_tool! {
+///     name: "search",
+///     description: "Searches the web",
+///     output: SearchResults,
+///     state: SearchClient::new(api_key),
+///     handler: |client: SearchClient, input: SearchInput| async move {
+///         client.search(&input.query).await
+///     },
+/// }?;
+/// ```
+#[macro_export]
+macro_rules! define_tool {
+    (
+        name: $name:expr,
+        $(description: $description:expr,)?
+        $(output: $output:ty,)?
+        state: $state:expr,
+        handler: $handler:expr $(,)?
+    ) => {{
+        let state = $state;
+        let handler = $handler;
+        $crate::Tool::builder()
+            .name($name)
+            $(.description($description))?
+            $(.output::<$output>())?
+            .handler(move |_: $crate::context::RequestContext, input| {
+                handler(::std::clone::Clone::clone(&state), input)
+            })
+            .build()
+    }};
+    (
+        name: $name:expr,
+        $(description: $description:expr,)?
+        $(output: $output:ty,)?
+        handler: $handler:expr $(,)?
+    ) => {
+        $crate::Tool::builder()
+            .name($name)
+            $(.description($description))?
+            $(.output::<$output>())?
+            .handler($handler)
+            .build()
+    };
+}
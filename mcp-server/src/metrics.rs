@@ -0,0 +1,87 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::time::Duration;
+
+/// Prometheus metrics of a server. Applications can register their own metrics in
+/// [`Metrics::registry`] to have them served alongside.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    tool_duration: HistogramVec,
+    sse_connections: IntGauge,
+    active_subscriptions: IntGauge,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        let registry = Registry::new();
+        let requests = IntCounterVec::new(
+            Opts::new(
+                "mcp_requests_total",
+                "Requests handled by method and outcome",
+            ),
+            &["method", "outcome"],
+        )
+        .expect("metric options are valid");
+        let tool_duration = HistogramVec::new(
+            HistogramOpts::new("mcp_tool_call_duration_seconds", "Duration of tool calls"),
+            &["tool"],
+        )
+        .expect("metric options are valid");
+        let sse_connections = IntGauge::new("mcp_sse_connections", "Open SSE connections")
+            .expect("metric options are valid");
+        let active_subscriptions = IntGauge::new(
+            "mcp_active_subscriptions",
+            "Resource subscriptions being followed",
+        )
+        .expect("metric options are valid");
+
+        for collector in [
+            Box::new(requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(tool_duration.clone()),
+            Box::new(sse_connections.clone()),
+            Box::new(active_subscriptions.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            requests,
+            tool_duration,
+            sse_connections,
+            active_subscriptions,
+        }
+    }
+
+    pub const fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub(crate) fn record_request(&self, method: &str, outcome: &str) {
+        self.requests.with_label_values(&[method, outcome]).inc();
+    }
+
+    pub(crate) fn record_tool_call(&self, tool: &str, duration: Duration) {
+        self.tool_duration
+            .with_label_values(&[tool])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Renders every metric in the Prometheus text format, sampling the gauges first
+    pub(crate) fn render(&self, sse_connections: usize, active_subscriptions: usize) -> String {
+        self.sse_connections
+            .set(i64::try_from(sse_connections).unwrap_or(i64::MAX));
+        self.active_subscriptions
+            .set(i64::try_from(active_subscriptions).unwrap_or(i64::MAX));
+
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {e}");
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
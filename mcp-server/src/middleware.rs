@@ -0,0 +1,34 @@
+use crate::Error;
+use crate::rpc::ClientMessage;
+use std::sync::Arc;
+
+/// Runs on every message after it is decoded from JSON-RPC and before it is handled, on every
+/// transport. Unlike HTTP layers, middleware sees the typed message, so it can authorize specific
+/// methods, rewrite parameters or record metrics per method.
+pub trait MessageMiddleware {
+    /// # Errors
+    /// If the message is rejected, this will error. Rejected requests are answered with the
+    /// error, and rejected notifications are dropped.
+    fn handle(&self, message: &mut ClientMessage) -> Result<(), Error>;
+}
+
+impl<F> MessageMiddleware for F
+where
+    F: Fn(&mut ClientMessage) -> Result<(), Error>,
+{
+    fn handle(&self, message: &mut ClientMessage) -> Result<(), Error> {
+        self(message)
+    }
+}
+
+pub(crate) type SharedMiddleware = Arc<dyn MessageMiddleware + Send + Sync>;
+
+/// Runs middleware in order until one rejects the message
+pub(crate) fn apply(
+    middleware: &[SharedMiddleware],
+    message: &mut ClientMessage,
+) -> Result<(), Error> {
+    middleware
+        .iter()
+        .try_for_each(|middleware| middleware.handle(message))
+}
@@ -0,0 +1,43 @@
+use crate::logging::LogSink;
+use tracing::warn;
+
+/// Tells clients that the tools, prompts or resources of the service changed, so they list them
+/// again, such as when a resource is created for each item a tool adds.
+///
+/// Notifiers are created with [`crate::BasicService::notifier`], which also advertises the
+/// `listChanged` capabilities, and notifications sent before the service is served are dropped.
+#[derive(Clone)]
+pub struct Notifier {
+    sink: LogSink,
+}
+
+impl Notifier {
+    pub(crate) const fn new(sink: LogSink) -> Self {
+        Self { sink }
+    }
+
+    pub fn tools_list_changed(&self) {
+        self.notify("notifications/tools/list_changed");
+    }
+
+    pub fn prompts_list_changed(&self) {
+        self.notify("notifications/prompts/list_changed");
+    }
+
+    pub fn resources_list_changed(&self) {
+        self.notify("notifications/resources/list_changed");
+    }
+
+    fn notify(&self, method: &str) {
+        let Some(handler) = self.sink.get() else {
+            return;
+        };
+        match serde_json::from_value(serde_json::json!({
+            "jsonrpc": mcp_schema::JSONRPC_VERSION,
+            "method": method,
+        })) {
+            Ok(notification) => handler(notification),
+            Err(e) => warn!("Failed to create {method} notification: {e}"),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::Error;
+use axum::extract::Request;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tower::{Layer, Service};
+
+const METADATA_PATH: &str = "/.well-known/oauth-protected-resource";
+
+/// The subject a request was authorized for. Handlers receive it through their
+/// [`crate::inject::Scope`], such as with `scope.require::<Principal>()`.
+#[derive(Clone, Debug)]
+pub struct Principal {
+    pub subject: String,
+    pub scopes: Vec<String>,
+    /// Every claim of the token, as returned by the verifier
+    pub claims: serde_json::Value,
+}
+
+type Verifier = Arc<dyn Fn(String) -> BoxFuture<'static, Result<Principal, Error>> + Send + Sync>;
+
+/// Protected resource metadata as defined by RFC 9728
+#[derive(Serialize)]
+struct ResourceMetadata {
+    resource: String,
+    authorization_servers: Vec<String>,
+    bearer_methods_supported: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scopes_supported: Vec<String>,
+}
+
+/// Authorization following the MCP specification: the server acts as an OAuth 2.1 protected
+/// resource that accepts access tokens issued by an authorization server.
+///
+/// This is a tower layer that rejects requests without a valid token with `401 Unauthorized`,
+/// pointing clients at the protected resource metadata served by [`OAuth::metadata_router`], and
+/// requests missing a required scope with `403 Forbidden`. The [`Principal`] of accepted requests
+/// is added to the scope of their handlers.
+///
+/// ```ignore
+/// let oauth = OAuth::new("https://mcp.example.com/api", "https://auth.example.com")
+///     .introspection("https://auth.example.com/introspect", "mcp", secret);
+/// let app = Router::new()
+///     .nest("/api", service.into_router().layer(oauth.clone()))
+///     .merge(oauth.metadata_router());
+/// ```
+#[derive(Clone)]
+pub struct OAuth {
+    resource: Arc<str>,
+    issuer: Arc<str>,
+    required_scopes: Arc<[String]>,
+    verifier: Option<Verifier>,
+}
+
+impl OAuth {
+    /// Protects the resource at the given uri with tokens from the given issuer
+    #[must_use]
+    pub fn new(resource: impl Into<String>, issuer: impl Into<String>) -> Self {
+        Self {
+            resource: resource.into().trim_end_matches('/').into(),
+            issuer: issuer.into().into(),
+            required_scopes: Arc::new([]),
+            verifier: None,
+        }
+    }
+
+    /// Requires every token to grant these scopes, which are also advertised in the metadata
+    #[must_use]
+    pub fn require_scopes(mut self, scopes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.required_scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Verifies tokens with RFC 7662 token introspection at the issuer. Tokens must be active,
+    /// unexpired, issued by the issuer, and, if they list an audience, intended for this resource.
+    #[must_use]
+    pub fn introspection(
+        self,
+        endpoint: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        let client = reqwest::Client::new();
+        let endpoint: Arc<str> = endpoint.into().into();
+        let client_id: Arc<str> = client_id.into().into();
+        let client_secret: Arc<str> = client_secret.into().into();
+        let issuer = self.issuer.clone();
+        let resource = self.resource.clone();
+
+        self.verifier(move |token: String| {
+            let request = client
+                .post(&*endpoint)
+                .basic_auth(&*client_id, Some(&*client_secret))
+                .form(&[("token", token)]);
+            let issuer = issuer.clone();
+            let resource = resource.clone();
+            async move {
+                let claims: serde_json::Value = request
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .map_err(|e| unauthorized(format!("Token introspection failed: {e}")))?
+                    .json()
+                    .await
+                    .map_err(|e| unauthorized(format!("Invalid introspection response: {e}")))?;
+                principal(claims, &issuer, &resource)
+            }
+        })
+    }
+
+    /// Verifies tokens with a custom verifier, such as one validating JWTs against the issuer's
+    /// keys
+    #[must_use]
+    pub fn verifier<F, Fut>(mut self, verifier: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Principal, Error>> + Send + 'static,
+    {
+        self.verifier = Some(Arc::new(move |token| Box::pin(verifier(token))));
+        self
+    }
+
+    /// The path of the protected resource metadata, which RFC 9728 places under the
+    /// `.well-known` prefix followed by the path of the resource
+    fn metadata_path(&self) -> String {
+        let path = self
+            .resource
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|index| &rest[index..]))
+            .unwrap_or_default();
+        format!("{METADATA_PATH}{path}")
+    }
+
+    fn metadata_url(&self) -> String {
+        let origin = match self.resource.split_once("://") {
+            Some((scheme, rest)) => {
+                let host = rest.split('/').next().unwrap_or_default();
+                format!("{scheme}://{host}")
+            }
+            None => String::new(),
+        };
+        format!("{origin}{}", self.metadata_path())
+    }
+
+    /// Serves the protected resource metadata that clients discover the authorization server
+    /// from. This must be merged into the application outside of the authenticated routes.
+    pub fn metadata_router<T: Clone + Send + Sync + 'static>(&self) -> Router<T> {
+        let metadata = serde_json::to_value(ResourceMetadata {
+            resource: self.resource.to_string(),
+            authorization_servers: vec![self.issuer.to_string()],
+            bearer_methods_supported: vec!["header"],
+            scopes_supported: self.required_scopes.to_vec(),
+        })
+        .unwrap_or_default();
+        Router::new().route(
+            &self.metadata_path(),
+            get(move || {
+                let metadata = metadata.clone();
+                async move { Json(metadata) }
+            }),
+        )
+    }
+
+    fn challenge(&self, status: StatusCode, error: Option<&str>) -> Response {
+        let mut challenge = format!("Bearer resource_metadata=\"{}\"", self.metadata_url());
+        if let Some(error) = error {
+            challenge.push_str(&format!(", error=\"{error}\""));
+        }
+        if !self.required_scopes.is_empty() {
+            challenge.push_str(&format!(", scope=\"{}\"", self.required_scopes.join(" ")));
+        }
+        (status, [(header::WWW_AUTHENTICATE, challenge)]).into_response()
+    }
+}
+
+fn unauthorized(message: String) -> Error {
+    Error::custom(-32001, message)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Checks the claims of an introspected token
+fn principal(claims: serde_json::Value, issuer: &str, resource: &str) -> Result<Principal, Error> {
+    #[derive(Deserialize)]
+    struct Introspection {
+        active: bool,
+        sub: Option<String>,
+        scope: Option<String>,
+        iss: Option<String>,
+        aud: Option<Audience>,
+        exp: Option<u64>,
+    }
+
+    let introspection: Introspection = serde_json::from_value(claims.clone())
+        .map_err(|e| unauthorized(format!("Invalid introspection response: {e}")))?;
+    if !introspection.active {
+        return Err(unauthorized("The token is not active".to_string()));
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if introspection.exp.is_some_and(|exp| exp <= now) {
+        return Err(unauthorized("The token has expired".to_string()));
+    }
+    if introspection
+        .iss
+        .is_some_and(|iss| iss.trim_end_matches('/') != issuer.trim_end_matches('/'))
+    {
+        return Err(unauthorized(
+            "The token was issued by another issuer".to_string(),
+        ));
+    }
+    let audiences = match introspection.aud {
+        None => Vec::new(),
+        Some(Audience::One(audience)) => vec![audience],
+        Some(Audience::Many(audiences)) => audiences,
+    };
+    if !audiences.is_empty()
+        && !audiences
+            .iter()
+            .any(|audience| audience.trim_end_matches('/') == resource)
+    {
+        return Err(unauthorized(
+            "The token is for another resource".to_string(),
+        ));
+    }
+
+    Ok(Principal {
+        subject: introspection.sub.unwrap_or_default(),
+        scopes: introspection
+            .scope
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        claims,
+    })
+}
+
+impl<S> Layer<S> for OAuth {
+    type Service = OAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OAuthService {
+            inner,
+            oauth: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OAuthService<S> {
+    inner: S,
+    oauth: OAuth,
+}
+
+impl<S> Service<Request> for OAuthService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        // The ready service must be the one that is called, so a fresh clone takes its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let oauth = self.oauth.clone();
+
+        Box::pin(async move {
+            let (Some(token), Some(verifier)) = (token, &oauth.verifier) else {
+                return Ok(oauth.challenge(StatusCode::UNAUTHORIZED, None));
+            };
+            let principal = match verifier(token).await {
+                Ok(principal) => principal,
+                Err(e) => {
+                    tracing::debug!("Rejected token: {}", e.message);
+                    return Ok(oauth.challenge(StatusCode::UNAUTHORIZED, Some("invalid_token")));
+                }
+            };
+            if !oauth
+                .required_scopes
+                .iter()
+                .all(|scope| principal.scopes.contains(scope))
+            {
+                return Ok(oauth.challenge(StatusCode::FORBIDDEN, Some("insufficient_scope")));
+            }
+
+            crate::inject::provide(principal, inner.call(request)).await
+        })
+    }
+}
@@ -0,0 +1,259 @@
+use crate::rate_limit::RateLimit;
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::middleware::{Next, from_fn_with_state};
+use axum::response::{IntoResponse, Response};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Hosts that are always allowed as origins unless [`ServerOptions::allow_localhost`] is disabled
+const LOCALHOST: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+/// What happens to an SSE connection that falls behind on notifications, such as a slow client
+/// during a burst
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Closes the connection, so the client knows it missed messages and reconnects
+    #[default]
+    Disconnect,
+    /// Drops the oldest messages and keeps the connection open
+    Skip,
+}
+
+/// Options of the SSE server.
+///
+/// By default, only browser pages served from localhost may call the server. Requests from other
+/// origins are rejected with `403 Forbidden`, which protects local servers from DNS rebinding
+/// attacks as the MCP specification requires. Requests without an `Origin` header, which
+/// non-browser clients don't send, are always allowed.
+#[derive(Clone, Debug)]
+pub struct ServerOptions {
+    allowed_origins: Vec<String>,
+    allow_any_origin: bool,
+    allow_localhost: bool,
+    rate_limit: Option<RateLimit>,
+    max_message_size: Option<usize>,
+    channel_capacity: Option<usize>,
+    keep_alive: Option<Duration>,
+    overflow: Overflow,
+    replay_notifications: usize,
+    max_subscriptions: Option<usize>,
+    error_details: bool,
+    #[cfg(feature = "metrics")]
+    metrics_route: bool,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allow_any_origin: false,
+            allow_localhost: true,
+            rate_limit: None,
+            max_message_size: None,
+            channel_capacity: None,
+            keep_alive: Some(crate::rpc::DEFAULT_KEEP_ALIVE),
+            overflow: Overflow::Disconnect,
+            replay_notifications: 0,
+            max_subscriptions: None,
+            error_details: false,
+            #[cfg(feature = "metrics")]
+            metrics_route: false,
+        }
+    }
+}
+
+impl ServerOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows browser pages from an origin such as `https://app.example.com`
+    #[must_use]
+    pub fn allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins
+            .push(origin.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Allows browser pages from every origin. This should only be used for servers that
+    /// authenticate every request.
+    #[must_use]
+    pub const fn allow_any_origin(mut self) -> Self {
+        self.allow_any_origin = true;
+        self
+    }
+
+    /// Sets whether pages served from localhost are allowed, which they are by default
+    #[must_use]
+    pub const fn allow_localhost(mut self, allowed: bool) -> Self {
+        self.allow_localhost = allowed;
+        self
+    }
+
+    /// Limits the messages each SSE session or IP address may post. See
+    /// [`crate::McpImpl::rate_limit`].
+    #[must_use]
+    pub const fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    pub(crate) const fn rate_limit_config(&self) -> Option<RateLimit> {
+        self.rate_limit
+    }
+
+    /// Sets the largest message body accepted. See [`crate::McpImpl::max_message_size`].
+    #[must_use]
+    pub const fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
+    pub(crate) const fn max_message_size_config(&self) -> Option<usize> {
+        self.max_message_size
+    }
+
+    /// Sets the number of messages buffered for each connection. See
+    /// [`crate::McpImpl::with_capacity`].
+    #[must_use]
+    pub const fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = Some(capacity);
+        self
+    }
+
+    pub(crate) const fn channel_capacity_config(&self) -> Option<usize> {
+        self.channel_capacity
+    }
+
+    /// Sets the interval of keepalive comments on idle SSE connections, or disables them with
+    /// `None`. See [`crate::McpImpl::keep_alive`].
+    #[must_use]
+    pub const fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+
+    pub(crate) const fn keep_alive_config(&self) -> Option<Duration> {
+        self.keep_alive
+    }
+
+    /// Sets what happens to SSE connections that fall behind on notifications, which is to
+    /// disconnect them by default
+    #[must_use]
+    pub const fn on_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub(crate) const fn overflow_config(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Replays notifications sent while no SSE connection was open. See
+    /// [`crate::McpImpl::replay_notifications`].
+    #[must_use]
+    pub const fn replay_notifications(mut self, capacity: usize) -> Self {
+        self.replay_notifications = capacity;
+        self
+    }
+
+    pub(crate) const fn replay_notifications_config(&self) -> usize {
+        self.replay_notifications
+    }
+
+    /// Limits the resources each session may be subscribed to. See
+    /// [`crate::McpImpl::max_subscriptions`].
+    #[must_use]
+    pub const fn max_subscriptions(mut self, limit: usize) -> Self {
+        self.max_subscriptions = Some(limit);
+        self
+    }
+
+    pub(crate) const fn max_subscriptions_config(&self) -> Option<usize> {
+        self.max_subscriptions
+    }
+
+    /// Sends the causes of errors to clients. See [`crate::McpImpl::error_details`].
+    #[must_use]
+    pub const fn error_details(mut self, enabled: bool) -> Self {
+        self.error_details = enabled;
+        self
+    }
+
+    pub(crate) const fn error_details_config(&self) -> bool {
+        self.error_details
+    }
+
+    /// Serves Prometheus metrics at `/metrics`, outside of the layer passed to
+    /// [`crate::serve_over_sse_with_layer`]
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub const fn metrics_route(mut self, enabled: bool) -> Self {
+        self.metrics_route = enabled;
+        self
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) const fn serves_metrics(&self) -> bool {
+        self.metrics_route
+    }
+
+    fn is_allowed(&self, origin: &str) -> bool {
+        if self.allow_any_origin || self.allowed_origins.iter().any(|allowed| allowed == origin) {
+            return true;
+        }
+
+        let Some(authority) = origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"))
+        else {
+            return false;
+        };
+        // Ports follow the last colon, except inside the brackets of an IPv6 address
+        let host = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => host,
+            _ => authority,
+        };
+        self.allow_localhost && LOCALHOST.contains(&host)
+    }
+
+    fn cors(&self) -> CorsLayer {
+        let options = self.clone();
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+                origin
+                    .to_str()
+                    .is_ok_and(|origin| options.is_allowed(origin))
+            }))
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers(Any)
+    }
+
+    /// Adds CORS and origin validation around a router
+    pub(crate) fn apply<T: Clone + Send + Sync + 'static>(&self, router: Router<T>) -> Router<T> {
+        router
+            .layer(from_fn_with_state(self.clone(), validate_origin))
+            .layer(self.cors())
+    }
+}
+
+async fn validate_origin(
+    State(options): State<ServerOptions>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .map(|origin| origin.to_str().unwrap_or_default());
+    match origin {
+        Some(origin) if !options.is_allowed(origin) => {
+            tracing::warn!("Rejected request from origin {origin}");
+            (StatusCode::FORBIDDEN, "Origin is not allowed").into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
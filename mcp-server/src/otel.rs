@@ -0,0 +1,69 @@
+use axum::extract::Request;
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use opentelemetry::propagation::Extractor;
+use tracing::{Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Reads trace context such as `traceparent` from the `_meta` of a request
+struct MetaExtractor<'a>(&'a serde_json::Map<String, serde_json::Value>);
+
+impl Extractor for MetaExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(serde_json::Value::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+/// Continues the trace of the host if the request carries W3C trace context in its `_meta`. The
+/// globally configured propagator is used, which should be a `TraceContextPropagator`.
+pub(crate) fn set_parent_from_meta(span: &Span, request: &mcp_schema::ClientRequest) {
+    let Ok(request) = serde_json::to_value(request) else {
+        return;
+    };
+    let Some(meta) = request
+        .pointer("/params/_meta")
+        .and_then(serde_json::Value::as_object)
+    else {
+        return;
+    };
+    if !meta.contains_key("traceparent") {
+        return;
+    }
+
+    let context = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&MetaExtractor(meta))
+    });
+    span.set_parent(context);
+}
+
+/// Runs an HTTP request in a span continuing the trace context of its headers, which request
+/// spans are children of unless the request carries its own context in `_meta`
+pub(crate) async fn propagate_http_context(request: Request, next: Next) -> Response {
+    let context = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    let span = tracing::info_span!(
+        "mcp.http",
+        http.method = %request.method(),
+        http.path = %request.uri().path(),
+    );
+    span.set_parent(context);
+    next.run(request).instrument(span).await
+}
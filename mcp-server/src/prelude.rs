@@ -0,0 +1,32 @@
+//! The supported public surface of this crate. Anything not re-exported here or documented in the
+//! crate root may change between minor releases.
+//!
+//! ```ignore
+//! use mcp::prelude::*;
+//! ```
+
+pub use crate::auth::BearerAuth;
+pub use crate::catalog::{FuzzyRanker, Ranker};
+pub use crate::completion::CompletionResult;
+pub use crate::config::ConfigService;
+pub use crate::content::{
+    Contents, IntoContents, Json, PromptMessagesBuilder, embed, embedded, image, text,
+};
+pub use crate::context::RequestContext;
+pub use crate::filter::ContentFilter;
+pub use crate::inject::Scope;
+pub use crate::logging::Logger;
+pub use crate::middleware::MessageMiddleware;
+pub use crate::notifier::Notifier;
+pub use crate::rate_limit::RateLimit;
+pub use crate::registry::resource::{Source, TemplateSource};
+pub use crate::registry::{
+    ArgumentTransformer, FromRef, Prompt, Resource, SchemaOptions, Tool, ToolAnnotations,
+};
+pub use crate::resources::memory::MemoryResource;
+pub use crate::self_check::SelfCheckReport;
+pub use crate::{
+    BasicService, BoxService, Error, Overflow, ServerOptions, Service, ServiceRouter, define_tool,
+    serve_over_sse, serve_over_sse_with_layer, serve_over_sse_with_options,
+    serve_over_sse_with_shutdown, serve_over_stdio, server,
+};
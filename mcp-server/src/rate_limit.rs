@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of tracked clients above which clients with full buckets are forgotten
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Shortest time between two passes over the tracked clients to forget those with full buckets
+const MIN_PRUNE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A token bucket limit on the messages a client may send. Clients are told how long to wait
+/// instead of their messages being queued.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    capacity: f64,
+    refill_per_second: f64,
+}
+
+impl RateLimit {
+    /// Allows bursts of up to `messages`, refilling at `messages` per `period`
+    #[must_use]
+    pub fn new(messages: u32, period: Duration) -> Self {
+        Self {
+            capacity: f64::from(messages.max(1)),
+            refill_per_second: f64::from(messages.max(1)) / period.as_secs_f64().max(f64::EPSILON),
+        }
+    }
+
+    /// How long an idle client takes to refill an empty bucket
+    fn refill_time(self) -> Duration {
+        Duration::try_from_secs_f64(self.capacity / self.refill_per_second).unwrap_or(Duration::MAX)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    clients: HashMap<String, Bucket>,
+    next_prune: Instant,
+}
+
+/// Tracks a bucket per client, keyed by SSE session or IP address.
+///
+/// Beyond [`PRUNE_THRESHOLD`] clients, those with full buckets are forgotten, as they would get a
+/// full bucket anyway. The pass over every client runs at most once per refill time, since a
+/// bucket skipped by one pass can only fill up after that, so its cost is spread over all the
+/// messages in between rather than paid by each one while many clients are active.
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    prune_interval: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            prune_interval: limit.refill_time().max(MIN_PRUNE_INTERVAL),
+            buckets: Mutex::new(Buckets {
+                clients: HashMap::new(),
+                next_prune: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the client's bucket, or returns how long until one is available
+    pub(crate) fn check(&self, client: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.clients.len() > PRUNE_THRESHOLD && now >= buckets.next_prune {
+            let limit = self.limit;
+            buckets.clients.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * limit.refill_per_second < limit.capacity
+            });
+            buckets.next_prune = now
+                .checked_add(self.prune_interval)
+                .unwrap_or(now + MIN_PRUNE_INTERVAL);
+        }
+
+        let bucket = buckets.clients.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.limit.capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * self.limit.refill_per_second).min(self.limit.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.limit.refill_per_second,
+            ))
+        }
+    }
+}
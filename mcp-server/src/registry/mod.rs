@@ -0,0 +1,260 @@
+pub mod prompt;
+pub mod resource;
+pub mod schema;
+pub mod template;
+pub mod tool;
+
+use crate::Error;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::pin::Pin;
+
+pub use prompt::{Prompt, PromptRegistry};
+pub use resource::{Resource, ResourceRegistry};
+pub use schema::SchemaOptions;
+pub use template::UriTemplate;
+pub use tool::{ArgumentTransformer, Tool, ToolAnnotations, ToolRegistry};
+
+pub type HandlerArgs = HashMap<String, serde_json::Value>;
+
+/// A stable hash of a JSON value, for clients to detect changes in listed items. This uses 64 bit
+/// FNV-1a over the serialized value, which is deterministic across processes and releases.
+pub(crate) fn content_hash(value: &serde_json::Value) -> String {
+    let hash = value
+        .to_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Inserts a key into the `_meta` object of a listed item's extra fields
+pub(crate) fn insert_meta(
+    extra: &mut HashMap<String, serde_json::Value>,
+    key: &str,
+    value: serde_json::Value,
+) {
+    let meta = extra
+        .entry("_meta".to_string())
+        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    if let Some(meta) = meta.as_object_mut() {
+        meta.insert(key.to_string(), value);
+    }
+}
+
+/// One page of a registry's entries, in a stable order
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Passed back to get the next page, or `None` if this is the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Cursors are opaque to clients, so they can't come to depend on what they contain
+fn encode_cursor(position: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(position)
+}
+
+fn decode_cursor(cursor: &str) -> Result<String, Error> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|position| String::from_utf8(position).ok())
+        .ok_or_else(|| Error::invalid_params(format!("Invalid cursor '{cursor}'")))
+}
+
+/// Pages through a map by key, with the cursor holding the last key of the previous page, so
+/// that entries registered between requests don't shift the pages
+pub(crate) fn page_map<'a, V>(
+    map: &'a BTreeMap<String, V>,
+    cursor: Option<&str>,
+    limit: usize,
+) -> Result<Page<&'a V>, Error> {
+    let after = cursor.map(decode_cursor).transpose()?;
+    let start = after.as_deref().map_or(Bound::Unbounded, Bound::Excluded);
+    let limit = limit.max(1);
+
+    let mut entries: Vec<_> = map
+        .range::<str, _>((start, Bound::Unbounded))
+        .take(limit + 1)
+        .collect();
+    let next_cursor = (entries.len() > limit).then(|| {
+        entries.truncate(limit);
+        encode_cursor(entries[limit - 1].0)
+    });
+    Ok(Page {
+        items: entries.into_iter().map(|(_, value)| value).collect(),
+        next_cursor,
+    })
+}
+
+/// Pages through a list by position
+pub(crate) fn page_slice<'a, T>(
+    items: &'a [T],
+    cursor: Option<&str>,
+    limit: usize,
+) -> Result<Page<&'a T>, Error> {
+    let start = match cursor {
+        Some(cursor) => decode_cursor(cursor)?
+            .parse()
+            .map_err(|_| Error::invalid_params(format!("Invalid cursor '{cursor}'")))?,
+        None => 0,
+    };
+    let end = start.saturating_add(limit.max(1)).min(items.len());
+    Ok(Page {
+        items: items.get(start..end).unwrap_or_default().iter().collect(),
+        next_cursor: (end < items.len()).then(|| encode_cursor(&end.to_string())),
+    })
+}
+
+#[doc(hidden)]
+pub trait HandlerFn<State, O> {
+    fn run(
+        &self,
+        state: State,
+        input: HandlerArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<O, Error>> + Send>>;
+}
+
+/// Extracts part of the application state for handlers that only need a sub-state, such as a
+/// database pool inside a larger state struct. Every cloneable state can be extracted as itself.
+pub trait FromRef<T> {
+    fn from_ref(input: &T) -> Self;
+}
+
+impl<T: Clone> FromRef<T> for T {
+    fn from_ref(input: &T) -> Self {
+        input.clone()
+    }
+}
+
+/// Implemented for async functions taking any `Sub: FromRef<State>` and the deserialized input
+#[doc(hidden)]
+pub trait AsyncFnExt<State, I, O, Sub = State> {
+    fn handler<'a>(self) -> impl HandlerFn<State, O> + Send + Sync + 'a
+    where
+        Self: 'a,
+        I: 'a,
+        Sub: 'a;
+}
+
+impl<State, Sub, I, O, Fut, F> AsyncFnExt<State, I, O, Sub> for F
+where
+    State: Send + Sync + 'static,
+    Sub: FromRef<State>,
+    I: DeserializeOwned + Send,
+    O: 'static,
+    F: Fn(Sub, I) -> Fut + Send + Sync + Sized,
+    Fut: Future<Output = Result<O, Error>> + Send + 'static,
+{
+    fn handler<'a>(self) -> impl HandlerFn<State, O> + Send + Sync + 'a
+    where
+        Self: 'a,
+        I: 'a,
+        Sub: 'a,
+    {
+        WrappedAsyncFn {
+            handler: self,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// This wrapper is used to wrap an [`AsyncFn`] and implement [`HandlerFn`]. This is needed to
+/// store the Sub and I generics
+struct WrappedAsyncFn<F, Sub, I> {
+    handler: F,
+    phantom: PhantomData<fn() -> (Sub, I)>,
+}
+
+impl<State, Sub, I, O, Fut, F> HandlerFn<State, O> for WrappedAsyncFn<F, Sub, I>
+where
+    State: Send + Sync + 'static,
+    Sub: FromRef<State>,
+    I: DeserializeOwned + Send,
+    O: 'static,
+    F: Fn(Sub, I) -> Fut + Send + Sync + Sized,
+    Fut: Future<Output = Result<O, Error>> + Send + 'static,
+{
+    fn run(
+        &self,
+        state: State,
+        args: HandlerArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<O, Error>> + Send>> {
+        let input = serde_json::from_value(serde_json::Value::Object(args.into_iter().collect()))
+            .map_err(|e| Error::invalid_params(format!("Failed to deserialize arguments: {e}")));
+
+        let result = input.map(|input| (self.handler)(Sub::from_ref(&state), input));
+
+        Box::pin(async move { result?.await })
+    }
+}
+
+/// A registry for managing available handlers
+pub(crate) struct HandlerRegistry<Handler> {
+    handlers: BTreeMap<String, Handler>,
+    duplicates: Vec<String>,
+}
+
+impl<Handler> HandlerRegistry<Handler> {
+    /// Register a new handler with the given name and handler
+    pub fn register(&mut self, name: String, handler: Handler) {
+        if let Some(name) = self.handlers.insert(name.clone(), handler).map(|_| name) {
+            self.duplicates.push(name);
+        }
+    }
+
+    /// Names that were registered more than once, replacing the earlier handler
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+
+    /// Call a handler by name with the given arguments
+    pub fn call<State, O>(
+        &self,
+        state: State,
+        name: &str,
+        args: HashMap<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<O, Error>> + use<Handler, State, O> + Send + 'static
+    where
+        State: Sync,
+        O: 'static,
+        Handler: HandlerFn<State, O>,
+    {
+        let handler = self
+            .handlers
+            .get(name)
+            .ok_or_else(|| Error::invalid_params(format!("Handler '{name}' not found")))
+            .map(|handler| handler.run(state, args));
+
+        Box::pin(async move { handler?.await })
+    }
+
+    /// Get a handler by name
+    pub fn get(&self, name: &str) -> Option<&Handler> {
+        self.handlers.get(name)
+    }
+
+    /// Iterate through all registered handlers, ordered by name
+    pub fn handlers_iter(&self) -> impl Iterator<Item = (&String, &Handler)> {
+        self.handlers.iter()
+    }
+
+    /// A page of handlers, ordered by name
+    pub fn page(&self, cursor: Option<&str>, limit: usize) -> Result<Page<&Handler>, Error> {
+        page_map(&self.handlers, cursor, limit)
+    }
+}
+
+impl<Handler> Default for HandlerRegistry<Handler> {
+    fn default() -> Self {
+        Self {
+            handlers: BTreeMap::new(),
+            duplicates: Vec::new(),
+        }
+    }
+}
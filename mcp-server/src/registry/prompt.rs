@@ -1,10 +1,13 @@
 use crate::Error;
-use crate::registry::{AsyncFnExt, HandlerArgs, HandlerFn, HandlerRegistry};
-use schemars::schema::{InstanceType, Schema, SingleOrVec};
+use crate::completion::CompletionResult;
+use crate::registry::{AsyncFnExt, HandlerArgs, HandlerFn, HandlerRegistry, Page};
+use futures::FutureExt;
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SingleOrVec};
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
+use tracing::Instrument;
 
 /// A registry for managing available prompts with shared state
 pub struct PromptRegistry<State> {
@@ -31,22 +34,63 @@ impl<State: Send + Sync + 'static> PromptRegistry<State> {
         request: mcp_schema::GetPromptParams,
     ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + use<State> + Send + 'static
     {
-        self.registry.call(
-            state,
-            &request.name,
-            request
-                .arguments
-                .unwrap_or_default()
-                .into_iter()
-                .map(|(name, value)| (name, serde_json::Value::String(value)))
-                .collect(),
-        )
-    }
-
-    /// Iterate through all registered prompts
+        let span = tracing::info_span!("mcp.prompt", prompt = %request.name);
+        let args = request
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| (name, serde_json::Value::String(value)))
+            .collect();
+        span.in_scope(|| self.registry.call(state, &request.name, args))
+            .instrument(span)
+    }
+
+    /// Suggests values for an argument of a prompt from what the user has typed so far. Arguments
+    /// without a completer have no suggestions.
+    ///
+    /// # Errors
+    /// If the prompt does not exist, this will error.
+    pub fn complete(
+        &self,
+        prompt: &str,
+        argument: &str,
+        value: String,
+    ) -> Result<Pin<Box<dyn Future<Output = CompletionResult> + Send>>, Error> {
+        let prompt = self
+            .registry
+            .get(prompt)
+            .ok_or_else(|| Error::invalid_params(format!("Prompt '{prompt}' not found")))?;
+        Ok(match prompt.completers.get(argument) {
+            Some(completer) => completer(value),
+            None => Box::pin(async { CompletionResult::new() }),
+        })
+    }
+
+    /// Whether any prompt has a completer for one of its arguments
+    pub(crate) fn has_completers(&self) -> bool {
+        self.registry
+            .handlers_iter()
+            .any(|(_, prompt)| !prompt.completers.is_empty())
+    }
+
+    /// Names of prompts that were registered more than once, replacing the earlier prompt
+    pub fn duplicate_names(&self) -> &[String] {
+        self.registry.duplicates()
+    }
+
+    /// Iterate through all registered prompts, ordered by name
     pub fn prompts_iter(&self) -> impl Iterator<Item = (&String, &Prompt<State>)> {
         self.registry.handlers_iter()
     }
+
+    /// A page of at most `limit` prompts, ordered by name, starting after the cursor returned
+    /// with the previous page
+    ///
+    /// # Errors
+    /// If the cursor is invalid, this will error.
+    pub fn page(&self, cursor: Option<&str>, limit: usize) -> Result<Page<&Prompt<State>>, Error> {
+        self.registry.page(cursor, limit)
+    }
 }
 
 impl<State> Default for PromptRegistry<State> {
@@ -57,11 +101,15 @@ impl<State> Default for PromptRegistry<State> {
     }
 }
 
+type Completer =
+    Box<dyn Fn(String) -> Pin<Box<dyn Future<Output = CompletionResult> + Send>> + Send + Sync>;
+
 pub struct Prompt<State> {
     name: String,
     description: Option<String>,
     schema: Vec<mcp_schema::PromptArgument>,
     handler: Box<dyn HandlerFn<State, Vec<mcp_schema::PromptMessage>> + Send + Sync>,
+    completers: HashMap<String, Completer>,
 }
 
 impl<State: Send + Sync + 'static> Prompt<State> {
@@ -71,6 +119,26 @@ impl<State: Send + Sync + 'static> Prompt<State> {
     }
 }
 
+impl<State> Prompt<State> {
+    /// Creates a prompt whose arguments are listed as given rather than derived from a handler's
+    /// parameters
+    #[cfg_attr(not(feature = "templates"), allow(dead_code))]
+    pub(crate) fn from_parts(
+        name: String,
+        description: Option<String>,
+        arguments: Vec<mcp_schema::PromptArgument>,
+        handler: Box<dyn HandlerFn<State, Vec<mcp_schema::PromptMessage>> + Send + Sync>,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            schema: arguments,
+            handler,
+            completers: HashMap::new(),
+        }
+    }
+}
+
 impl<State: Send + Sync + 'static> HandlerFn<State, mcp_schema::GetPromptResult> for Prompt<State> {
     fn run(
         &self,
@@ -111,6 +179,7 @@ pub struct PromptBuilder<State> {
     description: Option<String>,
     schema: Option<Vec<mcp_schema::PromptArgument>>,
     handler: Option<Box<dyn HandlerFn<State, Vec<mcp_schema::PromptMessage>> + Send + Sync>>,
+    completers: HashMap<String, Completer>,
 }
 
 impl<State: Send + Sync + 'static> PromptBuilder<State> {
@@ -131,13 +200,30 @@ impl<State: Send + Sync + 'static> PromptBuilder<State> {
         self
     }
 
+    /// Suggests values for an argument from what the user has typed so far, such as city names
+    /// starting with it, to answer `completion/complete` requests for this prompt. The completer
+    /// returns the values or a [`CompletionResult`].
+    #[must_use]
+    pub fn complete<F, Fut, R>(mut self, argument: impl Into<String>, completer: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Into<CompletionResult>,
+    {
+        self.completers.insert(
+            argument.into(),
+            Box::new(move |value| Box::pin(completer(value).map(Into::into))),
+        );
+        self
+    }
+
     /// # Panics
     /// This function will panic if the handler parameters include types that are not [`String`] and
     /// [`Option<String>`]
     #[must_use]
-    pub fn handler<I>(
+    pub fn handler<I, Sub>(
         mut self,
-        handler: impl AsyncFnExt<State, I, Vec<mcp_schema::PromptMessage>>
+        handler: impl AsyncFnExt<State, I, Vec<mcp_schema::PromptMessage>, Sub>
         + Send
         + Sync
         + Copy
@@ -145,30 +231,30 @@ impl<State: Send + Sync + 'static> PromptBuilder<State> {
     ) -> Self
     where
         I: DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        Sub: 'static,
     {
         self.schema = Some(
             schemars::schema_for!(I)
                 .schema
                 .object
                 .map_or(Vec::new(), |object| {
-                    object
-                        .properties
+                    let ObjectValidation {
+                        properties,
+                        required,
+                        ..
+                    } = *object;
+                    properties
                         .into_iter()
                         .filter_map(|(name, schema)| match schema {
                             Schema::Bool(_) => None,
                             Schema::Object(object) => {
-                                let (valid, required) = match object.instance_type {
-                                    Some(SingleOrVec::Single(x)) => {
-                                        (*x == InstanceType::String, true)
-                                    }
-                                    Some(SingleOrVec::Vec(x)) => (
-                                        matches!(
-                                            x.as_slice(),
-                                            &[InstanceType::String, InstanceType::Null]
-                                        ),
-                                        false,
+                                let valid = match &object.instance_type {
+                                    Some(SingleOrVec::Single(x)) => **x == InstanceType::String,
+                                    Some(SingleOrVec::Vec(x)) => matches!(
+                                        x.as_slice(),
+                                        &[InstanceType::String, InstanceType::Null]
                                     ),
-                                    _ => (false, false),
+                                    None => false,
                                 };
 
                                 assert!(
@@ -176,9 +262,13 @@ impl<State: Send + Sync + 'static> PromptBuilder<State> {
                                     "prompt parameter '{name}' must be String or Option<String>"
                                 );
 
+                                // Fields that are optional or have a default aren't required
+                                let required = required.contains(&name);
                                 Some(mcp_schema::PromptArgument {
+                                    description: object
+                                        .metadata
+                                        .and_then(|metadata| metadata.description),
                                     name,
-                                    description: None,
                                     required: Some(required),
                                     extra: HashMap::new(),
                                 })
@@ -199,14 +289,13 @@ impl<State: Send + Sync + 'static> PromptBuilder<State> {
         Ok(Prompt {
             name: self.name.unwrap_or_else(|| "unnamed prompt".to_string()),
             description: self.description,
-            schema: self.schema.ok_or_else(|| Error {
-                message: "missing handler input schema".to_string(),
-                code: 500,
-            })?,
-            handler: self.handler.ok_or_else(|| Error {
-                message: "missing handler".to_string(),
-                code: 500,
-            })?,
+            schema: self
+                .schema
+                .ok_or_else(|| Error::internal("missing handler input schema"))?,
+            handler: self
+                .handler
+                .ok_or_else(|| Error::internal("missing handler"))?,
+            completers: self.completers,
         })
     }
 }
@@ -218,6 +307,7 @@ impl<State> Default for PromptBuilder<State> {
             description: None,
             schema: None,
             handler: None,
+            completers: HashMap::new(),
         }
     }
 }
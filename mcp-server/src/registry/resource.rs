@@ -0,0 +1,569 @@
+use crate::Error;
+use crate::registry::template::UriTemplate;
+use crate::registry::{Page, page_map, page_slice};
+use futures::FutureExt;
+use mcp_schema::ResourceContents;
+use serde::de::DeserializeOwned;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// A registry for managing available resources with shared state
+pub struct ResourceRegistry<State> {
+    fixed_resources: BTreeMap<String, Resource<State, FixedResourceUri>>,
+    template_resources: Vec<(UriTemplate, Resource<State, TemplateResourceUri>)>,
+    duplicates: Vec<String>,
+}
+
+impl<State> ResourceRegistry<State> {
+    /// Register a new resource with a fixed uri
+    pub fn register_fixed(&mut self, resource: Resource<State, FixedResourceUri>) {
+        let uri = resource.uri.0.clone();
+        if self.fixed_resources.insert(uri.clone(), resource).is_some() {
+            self.duplicates.push(uri);
+        }
+    }
+
+    /// Fixed uris that were registered more than once, replacing the earlier resource
+    pub fn duplicate_uris(&self) -> &[String] {
+        &self.duplicates
+    }
+
+    /// Register a new resource with a template uri
+    pub fn register_template(&mut self, resource: Resource<State, TemplateResourceUri>) {
+        self.template_resources
+            .push((UriTemplate::new(resource.uri.0.clone()), resource));
+    }
+}
+
+impl<State: Send + Sync + 'static> ResourceRegistry<State> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a source from a uri. Fixed resources also match uris with a query, such as
+    /// `?version=2`, which is passed through to the source. Template resources match uris their
+    /// template could expand to, and their sources can extract the variables with
+    /// [`UriTemplate::matches`].
+    ///
+    /// # Errors
+    /// If the uri does not match any of the registered resources, this will error.
+    pub fn get_source(
+        &self,
+        uri: &str,
+    ) -> Result<Arc<dyn ErasedSource<State> + Send + Sync>, Error> {
+        self.fixed_resources
+            .get(uri)
+            .or_else(|| {
+                let (base, _) = uri.split_once('?')?;
+                self.fixed_resources.get(base)
+            })
+            .map(|resource| resource.source.clone())
+            .or_else(|| {
+                // Of overlapping templates, the most specific one wins, then the first registered
+                self.template_resources
+                    .iter()
+                    .filter(|(template, _)| template.matches(uri).is_some())
+                    .min_by_key(|(template, _)| Reverse(template.specificity()))
+                    .map(|(_, resource)| resource.source.clone())
+            })
+            .ok_or_else(|| Error::resource_not_found(format!("Resource at uri '{uri}' not found")))
+    }
+
+    /// Read a resource from a URI
+    ///
+    /// # Errors
+    /// If the uri does not match any of the registered resources or the underlying source
+    /// encounters an error, this will error.
+    pub fn read_resource(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + use<State> + Send + 'static
+    {
+        let span = tracing::info_span!("mcp.resource", uri = %uri);
+        let source = self.get_source(&uri);
+        let contents = span.in_scope(|| source.map(|source| source.read_erased(state, uri)));
+
+        async move {
+            let contents = contents?.await?;
+
+            Ok(mcp_schema::ReadResourceResult {
+                meta: None,
+                contents,
+                extra: HashMap::new(),
+            })
+        }
+        .instrument(span)
+    }
+
+    /// Waits for a change in a resource from a URI
+    ///
+    /// # Errors
+    /// If the uri does not match any of the registered resources, this will error.
+    pub fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+    ) -> Result<impl Future<Output = ()> + use<State> + Send + 'static, Error> {
+        Ok(self.get_source(&uri)?.wait_for_change_erased(state, uri))
+    }
+
+    /// Tears down the sources of every registered resource
+    pub fn teardown(&self, state: State) -> impl Future<Output = ()> + use<State> + Send + 'static
+    where
+        State: Clone,
+    {
+        let teardowns: Vec<_> = self
+            .fixed_resources
+            .values()
+            .map(|resource| &resource.source)
+            .chain(
+                self.template_resources
+                    .iter()
+                    .map(|(_, resource)| &resource.source),
+            )
+            .map(|source| source.teardown_erased(state.clone()))
+            .collect();
+        futures::future::join_all(teardowns).map(drop)
+    }
+
+    /// Iterate through all registered fixed resources, ordered by uri
+    pub fn fixed_resources_iter(&self) -> impl Iterator<Item = &Resource<State, FixedResourceUri>> {
+        self.fixed_resources.values()
+    }
+
+    /// A page of at most `limit` fixed resources, ordered by uri, starting after the cursor
+    /// returned with the previous page
+    ///
+    /// # Errors
+    /// If the cursor is invalid, this will error.
+    pub fn fixed_resources_page(
+        &self,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<&Resource<State, FixedResourceUri>>, Error> {
+        page_map(&self.fixed_resources, cursor, limit)
+    }
+
+    /// A page of at most `limit` resource templates, in the order they were registered, starting
+    /// after the cursor returned with the previous page
+    ///
+    /// # Errors
+    /// If the cursor is invalid, this will error.
+    pub fn template_resources_page(
+        &self,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<&Resource<State, TemplateResourceUri>>, Error> {
+        let page = page_slice(&self.template_resources, cursor, limit)?;
+        Ok(Page {
+            items: page
+                .items
+                .into_iter()
+                .map(|(_, resource)| resource)
+                .collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    /// The uris template resources can currently read, described like their template
+    pub fn listed_template_resources(&self, state: State) -> Vec<mcp_schema::Resource>
+    where
+        State: Clone,
+    {
+        self.template_resources
+            .iter()
+            .flat_map(|(_, resource)| {
+                resource
+                    .source
+                    .list_erased(state.clone())
+                    .into_iter()
+                    .map(|uri| mcp_schema::Resource {
+                        uri,
+                        name: resource.name.clone(),
+                        description: resource.description.clone(),
+                        mime_type: resource.mime_type.clone(),
+                        annotated: resource.annotated.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Iterate through all registered resource templates
+    pub fn template_resources_iter(
+        &self,
+    ) -> impl Iterator<Item = &Resource<State, TemplateResourceUri>> {
+        self.template_resources.iter().map(|(_, resource)| resource)
+    }
+
+    #[deprecated(since = "0.1.0", note = "renamed to `template_resources_iter`")]
+    pub fn template_resource_iter(
+        &self,
+    ) -> impl Iterator<Item = &Resource<State, TemplateResourceUri>> {
+        self.template_resources_iter()
+    }
+}
+
+impl<State> Default for ResourceRegistry<State> {
+    fn default() -> Self {
+        Self {
+            fixed_resources: BTreeMap::new(),
+            template_resources: Vec::new(),
+            duplicates: Vec::new(),
+        }
+    }
+}
+
+pub trait Source<State> {
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + 'static + Send;
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = ()> + 'static + Send;
+
+    /// Cleans up when the server shuts down, such as by deleting temporary files
+    fn teardown(&self, _state: State) -> impl Future<Output = ()> + 'static + Send {
+        async {}
+    }
+
+    /// Uris that can currently be read from a template resource, which are listed along with the
+    /// fixed resources, such as one uri for each row of a table
+    fn list(&self, _state: State) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// A source of template resources that receives the template's variables deserialized into typed
+/// parameters, such as `WeatherParams { city }` for `weather://{city}`, instead of only the uri.
+///
+/// Values are deserialized like a query string, so numbers, booleans and optional fields work as
+/// they do in [`axum::extract::Query`]. Set it with [`ResourceBuilder::typed_source`].
+pub trait TemplateSource<State> {
+    type Params: DeserializeOwned;
+
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+        params: Self::Params,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + 'static + Send;
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+        params: Self::Params,
+    ) -> impl Future<Output = ()> + 'static + Send;
+
+    /// Cleans up when the server shuts down, such as by deleting temporary files
+    fn teardown(&self, _state: State) -> impl Future<Output = ()> + 'static + Send {
+        async {}
+    }
+
+    /// Uris that can currently be read, which are listed along with the fixed resources. Tell
+    /// clients when this changes with [`crate::notifier::Notifier::resources_list_changed`].
+    fn list(&self, _state: State) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Adapts a [`TemplateSource`] to a [`Source`] by matching uris against its template
+struct Typed<S> {
+    template: UriTemplate,
+    source: S,
+}
+
+impl<S> Typed<S> {
+    fn params<P: DeserializeOwned>(&self, uri: &str) -> Result<P, Error> {
+        let variables = self.template.matches(uri).ok_or_else(|| {
+            Error::resource_not_found(format!("Resource at uri '{uri}' not found"))
+        })?;
+        serde_urlencoded::to_string(&variables)
+            .map_err(|e| e.to_string())
+            .and_then(|query| serde_urlencoded::from_str(&query).map_err(|e| e.to_string()))
+            .map_err(|e| Error::invalid_params(format!("Invalid parameters in uri '{uri}': {e}")))
+    }
+}
+
+impl<State, S: TemplateSource<State>> Source<State> for Typed<S> {
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + 'static + Send {
+        match self.params(&uri) {
+            Ok(params) => self.source.read(state, uri, params).left_future(),
+            Err(e) => std::future::ready(Err(e)).right_future(),
+        }
+    }
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = ()> + 'static + Send {
+        match self.params(&uri) {
+            Ok(params) => self
+                .source
+                .wait_for_change(state, uri, params)
+                .left_future(),
+            // A uri that can't be read never changes
+            Err(_) => std::future::pending().right_future(),
+        }
+    }
+
+    fn teardown(&self, state: State) -> impl Future<Output = ()> + 'static + Send {
+        self.source.teardown(state)
+    }
+
+    fn list(&self, state: State) -> Vec<String> {
+        self.source.list(state)
+    }
+}
+
+#[doc(hidden)]
+pub trait ErasedSource<State> {
+    fn read_erased(
+        &self,
+        state: State,
+        uri: String,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ResourceContents>, Error>> + Send>>;
+
+    fn wait_for_change_erased(
+        &self,
+        state: State,
+        uri: String,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn teardown_erased(&self, state: State) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn list_erased(&self, state: State) -> Vec<String>;
+}
+
+impl<State, T> ErasedSource<State> for T
+where
+    T: Source<State>,
+{
+    fn read_erased(
+        &self,
+        state: State,
+        uri: String,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ResourceContents>, Error>> + Send>> {
+        let fut = self.read(state, uri);
+        fut.boxed()
+    }
+
+    fn wait_for_change_erased(
+        &self,
+        state: State,
+        uri: String,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let fut = self.wait_for_change(state, uri);
+        fut.boxed()
+    }
+
+    fn teardown_erased(&self, state: State) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.teardown(state).boxed()
+    }
+
+    fn list_erased(&self, state: State) -> Vec<String> {
+        self.list(state)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct FixedResourceUri(pub String);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TemplateResourceUri(pub String);
+
+impl AsRef<str> for FixedResourceUri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for TemplateResourceUri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+type SourceFactory<State> =
+    Box<dyn FnOnce(&str) -> Arc<dyn ErasedSource<State> + Send + Sync> + Send>;
+
+pub struct Resource<State, Uri> {
+    uri: Uri,
+    name: String,
+    description: Option<String>,
+    mime_type: Option<String>,
+    annotated: mcp_schema::Annotated,
+    source: Arc<dyn ErasedSource<State> + Send + Sync>,
+}
+
+impl<State: Send + Sync + 'static, Uri> Resource<State, Uri> {
+    #[must_use]
+    pub fn builder() -> ResourceBuilder<State, Uri> {
+        ResourceBuilder::new()
+    }
+}
+
+impl<State> TryFrom<&Resource<State, FixedResourceUri>> for mcp_schema::Resource {
+    type Error = serde_json::Error;
+
+    fn try_from(resource: &Resource<State, FixedResourceUri>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            uri: resource.uri.0.clone(),
+            name: resource.name.clone(),
+            description: resource.description.clone(),
+            mime_type: resource.mime_type.clone(),
+            annotated: resource.annotated.clone(),
+        })
+    }
+}
+
+impl<State> TryFrom<&Resource<State, TemplateResourceUri>> for mcp_schema::ResourceTemplate {
+    type Error = serde_json::Error;
+
+    fn try_from(resource: &Resource<State, TemplateResourceUri>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            uri_template: resource.uri.0.clone(),
+            name: resource.name.clone(),
+            description: resource.description.clone(),
+            mime_type: resource.mime_type.clone(),
+            annotated: resource.annotated.clone(),
+        })
+    }
+}
+
+/// A builder for constructing a resource with validation and metadata
+pub struct ResourceBuilder<State, Uri> {
+    uri: Option<Uri>,
+    name: Option<String>,
+    description: Option<String>,
+    mime_type: Option<String>,
+    annotated: mcp_schema::Annotated,
+    source: Option<Arc<dyn ErasedSource<State> + Send + Sync>>,
+    /// Creates a typed source once the template is known
+    typed_source: Option<SourceFactory<State>>,
+}
+
+impl<State: Send + Sync + 'static, Uri> ResourceBuilder<State, Uri> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    #[must_use]
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    #[must_use]
+    pub fn annotations(mut self, annotations: mcp_schema::Annotations) -> Self {
+        self.annotated.annotations = Some(annotations);
+        self
+    }
+
+    #[must_use]
+    pub fn source(mut self, source: impl Source<State> + Send + Sync + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Builds a resource.
+    ///
+    /// # Errors
+    /// If the uri or source was not set, this will error.
+    pub fn build(self) -> Result<Resource<State, Uri>, Error>
+    where
+        Uri: AsRef<str>,
+    {
+        let uri = self.uri.ok_or_else(|| Error::internal("missing uri"))?;
+        let source = match self.typed_source {
+            Some(typed_source) => Some(typed_source(uri.as_ref())),
+            None => self.source,
+        };
+        Ok(Resource {
+            uri,
+            name: self.name.unwrap_or_else(|| "unnamed resource".to_string()),
+            description: self.description,
+            mime_type: self.mime_type,
+            annotated: self.annotated,
+            source: source.ok_or_else(|| Error::internal("missing source"))?,
+        })
+    }
+}
+
+impl<State> ResourceBuilder<State, FixedResourceUri> {
+    #[must_use]
+    pub fn fixed_uri(mut self, name: impl Into<String>) -> Self {
+        self.uri = Some(FixedResourceUri(name.into()));
+        self
+    }
+}
+
+impl<State> ResourceBuilder<State, TemplateResourceUri> {
+    #[must_use]
+    pub fn template_uri(mut self, name: impl Into<String>) -> Self {
+        self.uri = Some(TemplateResourceUri(name.into()));
+        self
+    }
+
+    /// Sets a source that receives the template's variables as typed parameters, replacing any
+    /// source set with [`ResourceBuilder::source`]
+    #[must_use]
+    pub fn typed_source(
+        mut self,
+        source: impl TemplateSource<State> + Send + Sync + 'static,
+    ) -> Self {
+        self.typed_source = Some(Box::new(|template| {
+            Arc::new(Typed {
+                template: UriTemplate::new(template),
+                source,
+            })
+        }));
+        self
+    }
+}
+
+impl<State, Uri> Default for ResourceBuilder<State, Uri> {
+    fn default() -> Self {
+        Self {
+            uri: None,
+            name: None,
+            description: None,
+            mime_type: None,
+            annotated: mcp_schema::Annotated {
+                annotations: None,
+                extra: HashMap::new(),
+            },
+            source: None,
+            typed_source: None,
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use serde_json::{Map, Value};
+
+/// Maximum depth `$ref`s are inlined to, which bounds the size of schemas of recursive types
+const MAX_INLINE_DEPTH: usize = 16;
+
+/// Keywords whose values map names to schemas, rather than being schemas themselves
+const SCHEMA_MAPS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+];
+
+/// Keywords whose values are instances of the schema, which are left as they are
+const INSTANCE_KEYWORDS: [&str; 4] = ["const", "default", "enum", "examples"];
+
+/// Options for post-processing the JSON schema generated for a handler input.
+///
+/// The tagging style of enum inputs follows the serde attributes of the input type
+/// (`#[serde(tag = "...")]`, `#[serde(tag = "...", content = "...")]`, `#[serde(untagged)]`).
+/// These options control how the resulting `oneOf` schema is presented to clients, since many of
+/// them only accept a single flat object schema as tool input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SchemaOptions {
+    /// Replaces `$ref`s into the schema definitions with the referenced schema
+    pub inline_refs: bool,
+    /// Merges the variants of a top level `oneOf`/`anyOf` into a single object schema. Properties
+    /// required by every variant stay required, and differing tag values are merged into one enum.
+    pub flatten_one_of: bool,
+    /// Sets `additionalProperties: false` on every object schema that doesn't specify it
+    pub deny_additional_properties: bool,
+}
+
+impl SchemaOptions {
+    /// Options that make enum inputs acceptable to the widest range of clients
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            inline_refs: true,
+            flatten_one_of: true,
+            deny_additional_properties: true,
+        }
+    }
+
+    pub(crate) fn apply(self, schema: &mut Value) {
+        if self.inline_refs || self.flatten_one_of {
+            let definitions = schema
+                .as_object_mut()
+                .and_then(|schema| schema.remove("definitions"));
+            if let Some(Value::Object(definitions)) = &definitions {
+                inline_refs(schema, definitions, 0);
+            }
+            // Definitions are only needed if some refs were too deep to inline
+            if let (Some(definitions), Some(schema)) = (definitions, schema.as_object_mut()) {
+                if schema.values().any(contains_ref) {
+                    schema.insert("definitions".to_string(), definitions);
+                }
+            }
+        }
+
+        if self.flatten_one_of {
+            if let Some(schema) = schema.as_object_mut() {
+                flatten_one_of(schema);
+            }
+        }
+
+        if self.deny_additional_properties {
+            deny_additional_properties(schema);
+        }
+    }
+}
+
+/// Resolves a local `$ref` such as `#/definitions/Name`
+pub(crate) fn resolve_ref<'a>(
+    reference: &str,
+    definitions: &'a Map<String, Value>,
+) -> Option<&'a Value> {
+    definitions.get(reference.strip_prefix("#/definitions/")?)
+}
+
+fn inline_refs(schema: &mut Value, definitions: &Map<String, Value>, depth: usize) {
+    match schema {
+        Value::Object(object) => {
+            if depth < MAX_INLINE_DEPTH {
+                let resolved = object
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|reference| resolve_ref(reference, definitions))
+                    .cloned();
+                if let Some(resolved) = resolved {
+                    object.remove("$ref");
+                    if let Value::Object(resolved) = resolved {
+                        for (key, value) in resolved {
+                            object.entry(key).or_insert(value);
+                        }
+                    }
+                    for value in object.values_mut() {
+                        inline_refs(value, definitions, depth + 1);
+                    }
+                    return;
+                }
+            }
+            for value in object.values_mut() {
+                inline_refs(value, definitions, depth);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                inline_refs(value, definitions, depth);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn contains_ref(schema: &Value) -> bool {
+    match schema {
+        Value::Object(object) => object.contains_key("$ref") || object.values().any(contains_ref),
+        Value::Array(array) => array.iter().any(contains_ref),
+        _ => false,
+    }
+}
+
+fn flatten_one_of(schema: &mut Map<String, Value>) {
+    let key = if schema.contains_key("oneOf") {
+        "oneOf"
+    } else if schema.contains_key("anyOf") {
+        "anyOf"
+    } else {
+        return;
+    };
+
+    let Some(Value::Array(variants)) = schema.get(key) else {
+        return;
+    };
+
+    // Only object variants can be merged into a single object
+    let Some(variants) = variants
+        .iter()
+        .map(|variant| {
+            let variant = variant.as_object()?;
+            let properties = match variant.get("properties") {
+                Some(Value::Object(properties)) => properties.clone(),
+                None => Map::new(),
+                Some(_) => return None,
+            };
+            let required = variant
+                .get("required")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            Some((properties, required))
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+
+    let mut properties = Map::new();
+    for (variant_properties, _) in &variants {
+        for (name, property) in variant_properties {
+            match properties.get_mut(name) {
+                None => {
+                    properties.insert(name.clone(), property.clone());
+                }
+                Some(existing) => merge_property(existing, property),
+            }
+        }
+    }
+
+    let required: Vec<Value> = variants.first().map_or_else(Vec::new, |(_, first)| {
+        first
+            .iter()
+            .filter(|name| variants.iter().all(|(_, required)| required.contains(name)))
+            .cloned()
+            .collect()
+    });
+
+    schema.remove(key);
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+}
+
+fn merge_property(existing: &mut Value, other: &Value) {
+    if existing == other {
+        return;
+    }
+
+    // Tags of different variants are enums with a single value each
+    if let (Some(Value::Array(values)), Some(Value::Array(other_values))) = (
+        existing.as_object_mut().and_then(|e| e.get_mut("enum")),
+        other.get("enum"),
+    ) {
+        for value in other_values {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        return;
+    }
+
+    let any_of = existing
+        .as_object_mut()
+        .filter(|existing| existing.len() == 1)
+        .and_then(|existing| existing.get_mut("anyOf"))
+        .and_then(Value::as_array_mut);
+    if let Some(any_of) = any_of {
+        if !any_of.contains(other) {
+            any_of.push(other.clone());
+        }
+        return;
+    }
+
+    *existing = serde_json::json!({ "anyOf": [existing.take(), other.clone()] });
+}
+
+fn deny_additional_properties(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            let is_object_schema = object.contains_key("properties")
+                || object.get("type").and_then(Value::as_str) == Some("object");
+            if is_object_schema && !object.contains_key("additionalProperties") {
+                object.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            for (keyword, value) in object.iter_mut() {
+                if SCHEMA_MAPS.contains(&keyword.as_str()) {
+                    value
+                        .as_object_mut()
+                        .into_iter()
+                        .flat_map(Map::values_mut)
+                        .for_each(deny_additional_properties);
+                } else if !INSTANCE_KEYWORDS.contains(&keyword.as_str()) {
+                    deny_additional_properties(value);
+                }
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                deny_additional_properties(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The JSON schema types a value could be given as
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The types allowed by a schema, or [`None`] if it doesn't restrict the type
+fn allowed_types(schema: &Map<String, Value>) -> Option<Vec<&str>> {
+    match schema.get("type")? {
+        Value::String(ty) => Some(vec![ty.as_str()]),
+        Value::Array(types) => Some(types.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+fn accepts(schema: &Map<String, Value>, value: &Value) -> bool {
+    allowed_types(schema).is_none_or(|types| {
+        let ty = value_type(value);
+        types.contains(&ty) || (ty == "integer" && types.contains(&"number"))
+    })
+}
+
+/// Converts a string to a scalar of one of the allowed types
+fn parse_scalar(text: &str, types: &[&str]) -> Option<Value> {
+    let text = text.trim();
+    if types.contains(&"integer") {
+        if let Ok(integer) = text.parse::<i64>() {
+            return Some(integer.into());
+        }
+        if let Ok(integer) = text.parse::<u64>() {
+            return Some(integer.into());
+        }
+    }
+    if types.contains(&"number") {
+        if let Some(number) = text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+        {
+            return Some(Value::Number(number));
+        }
+    }
+    if types.contains(&"boolean") {
+        match text {
+            "true" => return Some(Value::Bool(true)),
+            "false" => return Some(Value::Bool(false)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// An object schema, following a `$ref` at its top level
+fn resolve_object<'a>(
+    schema: &'a Value,
+    definitions: &'a Map<String, Value>,
+) -> Option<&'a Map<String, Value>> {
+    let schema = schema.as_object()?;
+    Some(
+        schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| resolve_ref(reference, definitions))
+            .and_then(Value::as_object)
+            .unwrap_or(schema),
+    )
+}
+
+/// Fixes benign type mismatches in a value guided by its schema: numbers and booleans given as
+/// strings are parsed, and a single value is wrapped where an array is expected. Anything that
+/// can't be fixed is left for deserialization to reject.
+pub(crate) fn coerce(value: &mut Value, schema: &Value, definitions: &Map<String, Value>) {
+    coerce_at(value, schema, definitions, 0);
+}
+
+fn coerce_at(value: &mut Value, schema: &Value, definitions: &Map<String, Value>, depth: usize) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    if depth > MAX_INLINE_DEPTH {
+        return;
+    }
+
+    if let Some(resolved) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| resolve_ref(reference, definitions))
+    {
+        coerce_at(value, resolved, definitions, depth + 1);
+    }
+    if let Some(Value::Array(all_of)) = schema.get("allOf") {
+        for part in all_of {
+            coerce_at(value, part, definitions, depth + 1);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = schema.get(key) {
+            // Prefer the variant the value is meant for, and only coerce to fit one otherwise
+            if let Some(variant) = select_variant(variants, value, definitions, depth + 1) {
+                coerce_at(value, variant, definitions, depth + 1);
+            } else if let Some(fitted) = variants.iter().find_map(|variant| {
+                let mut candidate = value.clone();
+                coerce_at(&mut candidate, variant, definitions, depth + 1);
+                fits(&candidate, variant, definitions, depth + 1).then_some(candidate)
+            }) {
+                *value = fitted;
+            } else if let Some(fitted) = variants.iter().find_map(|variant| {
+                let mut candidate = value.clone();
+                coerce_at(&mut candidate, variant, definitions, depth + 1);
+                accepts(resolve_object(variant, definitions)?, &candidate).then_some(candidate)
+            }) {
+                *value = fitted;
+            }
+        }
+    }
+
+    if let Some(types) = allowed_types(schema) {
+        if !accepts(schema, value) {
+            if let Some(parsed) = value.as_str().and_then(|text| parse_scalar(text, &types)) {
+                *value = parsed;
+            } else if types.contains(&"array") && !value.is_null() {
+                *value = Value::Array(vec![value.take()]);
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, property) in properties {
+                    if let Some(value) = object.get_mut(name) {
+                        coerce_at(value, property, definitions, depth + 1);
+                    }
+                }
+            }
+        }
+        Value::Array(array) => {
+            if let Some(items) = schema.get("items") {
+                for value in array {
+                    coerce_at(value, items, definitions, depth + 1);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether a value fits a schema without any violations
+fn fits(value: &Value, schema: &Value, definitions: &Map<String, Value>, depth: usize) -> bool {
+    let mut candidate = value.clone();
+    let mut violations = Vec::new();
+    repair_at(
+        &mut candidate,
+        schema,
+        definitions,
+        "",
+        &mut violations,
+        depth,
+    );
+    violations.is_empty()
+}
+
+/// Whether an object carries the `const` or `enum` tags of a variant, such as the `type` field of
+/// an internally tagged enum, along with whether it has every property the variant requires.
+/// Returns [`None`] if the variant has no tags or the object doesn't carry any of them.
+fn tag_match(value: &Value, variant: &Value, definitions: &Map<String, Value>) -> Option<bool> {
+    let object = value.as_object()?;
+    let variant = resolve_object(variant, definitions)?;
+    let properties = variant.get("properties")?.as_object()?;
+
+    let mut tagged = false;
+    for (name, property) in properties {
+        let Some(property) = resolve_object(property, definitions) else {
+            continue;
+        };
+        let allowed = match (property.get("const"), property.get("enum")) {
+            (Some(value), _) => std::slice::from_ref(value),
+            (None, Some(Value::Array(values))) => values.as_slice(),
+            _ => continue,
+        };
+        if let Some(tag) = object.get(name) {
+            if !allowed.contains(tag) {
+                return None;
+            }
+            tagged = true;
+        }
+    }
+
+    let required = variant
+        .get("required")
+        .and_then(Value::as_array)
+        .is_none_or(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .all(|name| object.contains_key(name))
+        });
+    tagged.then_some(required)
+}
+
+/// Picks the variant of a `oneOf` or `anyOf` a value is meant for: the first it fits without
+/// violations, or else the one whose tags and required properties it matches, or else the one
+/// whose tags alone it matches. Returns [`None`] if no variant fits.
+fn select_variant<'a>(
+    variants: &'a [Value],
+    value: &Value,
+    definitions: &Map<String, Value>,
+    depth: usize,
+) -> Option<&'a Value> {
+    variants
+        .iter()
+        .find(|variant| fits(value, variant, definitions, depth))
+        .or_else(|| {
+            variants
+                .iter()
+                .find(|variant| tag_match(value, variant, definitions) == Some(true))
+        })
+        .or_else(|| {
+            variants
+                .iter()
+                .find(|variant| tag_match(value, variant, definitions).is_some())
+        })
+}
+
+/// A value of the schema that is as plain as possible, used to fill in suggested arguments
+fn placeholder(schema: &Value, definitions: &Map<String, Value>, depth: usize) -> Value {
+    let Some(schema) = resolve_object(schema, definitions) else {
+        return Value::Null;
+    };
+    if depth > MAX_INLINE_DEPTH {
+        return Value::Null;
+    }
+
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(value) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|values| values.first())
+    {
+        return value.clone();
+    }
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = schema.get(key) {
+            let variant = variants
+                .iter()
+                .find(|variant| variant.get("type").and_then(Value::as_str) != Some("null"));
+            if let Some(variant) = variant {
+                return placeholder(variant, definitions, depth + 1);
+            }
+        }
+    }
+
+    let ty = allowed_types(schema)
+        .and_then(|types| types.into_iter().find(|ty| *ty != "null"))
+        .unwrap_or("null");
+    match ty {
+        "object" => {
+            let mut object = Map::new();
+            if let (Some(Value::Object(properties)), Some(Value::Array(required))) =
+                (schema.get("properties"), schema.get("required"))
+            {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if let Some(property) = properties.get(name) {
+                        object.insert(
+                            name.to_string(),
+                            placeholder(property, definitions, depth + 1),
+                        );
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        "array" => Value::Array(Vec::new()),
+        "string" => Value::String(String::new()),
+        "integer" | "number" => Value::from(0),
+        "boolean" => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
+/// What a schema expects, for describing a violation
+fn expected(schema: &Map<String, Value>) -> Value {
+    let mut expected = Map::new();
+    for key in ["type", "enum", "const"] {
+        if let Some(value) = schema.get(key) {
+            expected.insert(key.to_string(), value.clone());
+        }
+    }
+    Value::Object(expected)
+}
+
+/// Finds values that don't fit a schema and replaces them with placeholders, recording each
+/// violation with the JSON pointer it occurred at
+fn repair_at(
+    value: &mut Value,
+    schema: &Value,
+    definitions: &Map<String, Value>,
+    pointer: &str,
+    violations: &mut Vec<Value>,
+    depth: usize,
+) {
+    let Some(object_schema) = resolve_object(schema, definitions) else {
+        return;
+    };
+    if depth > MAX_INLINE_DEPTH {
+        return;
+    }
+
+    let violate = |value: &mut Value, expected: Value, violations: &mut Vec<Value>| {
+        violations.push(serde_json::json!({
+            "pointer": pointer,
+            "expected": expected,
+            "received": value.clone(),
+        }));
+        *value = placeholder(schema, definitions, depth);
+    };
+
+    let fits_enum = object_schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .is_none_or(|values| values.contains(value));
+    if !accepts(object_schema, value) || !fits_enum {
+        violate(value, expected(object_schema), violations);
+        return;
+    }
+    if let Some(Value::Array(all_of)) = object_schema.get("allOf") {
+        for part in all_of {
+            repair_at(value, part, definitions, pointer, violations, depth + 1);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = object_schema.get(key) {
+            match select_variant(variants, value, definitions, depth + 1) {
+                Some(variant) => {
+                    repair_at(value, variant, definitions, pointer, violations, depth + 1);
+                }
+                None => {
+                    let variants = variants
+                        .iter()
+                        .filter_map(|variant| resolve_object(variant, definitions))
+                        .map(expected)
+                        .collect();
+                    let mut expected = Map::new();
+                    expected.insert(key.to_string(), Value::Array(variants));
+                    violate(value, Value::Object(expected), violations);
+                    return;
+                }
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = object_schema.get("properties").and_then(Value::as_object);
+            if let Some(Value::Array(required)) = object_schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if object.contains_key(name) {
+                        continue;
+                    }
+                    let property = properties.and_then(|properties| properties.get(name));
+                    violations.push(serde_json::json!({
+                        "pointer": format!("{pointer}/{}", escape_pointer(name)),
+                        "expected": property
+                            .and_then(|property| resolve_object(property, definitions))
+                            .map_or(Value::Null, expected),
+                        "missing": true,
+                    }));
+                    let placeholder = property.map_or(Value::Null, |property| {
+                        placeholder(property, definitions, depth + 1)
+                    });
+                    object.insert(name.to_string(), placeholder);
+                }
+            }
+            if let Some(properties) = properties {
+                for (name, property) in properties {
+                    if let Some(value) = object.get_mut(name) {
+                        let pointer = format!("{pointer}/{}", escape_pointer(name));
+                        repair_at(
+                            value,
+                            property,
+                            definitions,
+                            &pointer,
+                            violations,
+                            depth + 1,
+                        );
+                    }
+                }
+            }
+        }
+        Value::Array(array) => {
+            if let Some(items) = object_schema.get("items") {
+                for (index, value) in array.iter_mut().enumerate() {
+                    let pointer = format!("{pointer}/{index}");
+                    repair_at(value, items, definitions, &pointer, violations, depth + 1);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Describes why a value doesn't fit a schema, for clients to correct their arguments in one
+/// round trip. The payload lists each violation with its JSON pointer and the expected type or
+/// enum, along with a suggested value that keeps everything that did fit. Returns [`None`] if no
+/// violation was found.
+pub(crate) fn repair(
+    value: &Value,
+    schema: &Value,
+    definitions: &Map<String, Value>,
+) -> Option<Value> {
+    let mut suggestion = value.clone();
+    let mut violations = Vec::new();
+    repair_at(&mut suggestion, schema, definitions, "", &mut violations, 0);
+
+    (!violations.is_empty()).then(|| {
+        serde_json::json!({
+            "violations": violations,
+            "suggestion": suggestion,
+        })
+    })
+}
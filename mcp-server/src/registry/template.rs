@@ -0,0 +1,234 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Characters that simple expansion percent-encodes, so they delimit its values
+const RESERVED: &str = ":/?#[]@!$&'()*+,;=";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Expression {
+        operator: Option<char>,
+        variables: Vec<String>,
+    },
+}
+
+/// An RFC 6570 uri template, such as `file:///{+path}` or `users://{id}/posts{?page,limit}`, for
+/// matching uris and extracting the values of their variables.
+///
+/// Matching inverts expansion, so every operator is supported, with prefix modifiers ignored.
+/// Where a uri could be split between variables in several ways, earlier variables take as
+/// little as possible. Values are percent-decoded, and a variable in an exploded or multi-valued
+/// expression receives the whole list, as in `a/b/c` for `{/path*}`.
+///
+/// ```ignore
+/// let template = UriTemplate::new("users://{id}/posts{?page}");
+/// let variables = template.matches("users://42/posts?page=2").unwrap();
+/// assert_eq!(variables["id"], "42");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UriTemplate {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl UriTemplate {
+    /// Parses a template. Braces that aren't closed are treated as literal text, as
+    /// [`crate::BasicService::self_check`] reports them.
+    #[must_use]
+    pub fn new(template: impl Into<String>) -> Self {
+        let template = template.into();
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template.as_str();
+
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+            literal.push_str(&rest[..start]);
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+
+            let expression = &rest[start + 1..end];
+            let operator = expression.chars().next().filter(|c| "+#./;?&".contains(*c));
+            let names = &expression[operator.map_or(0, char::len_utf8)..];
+            parts.push(Part::Expression {
+                operator,
+                variables: names
+                    .split(',')
+                    .map(|name| {
+                        let name = name.trim_end_matches('*');
+                        name.split_once(':')
+                            .map_or(name, |(name, _)| name)
+                            .to_string()
+                    })
+                    .collect(),
+            });
+            rest = &rest[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Self { template, parts }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Matches a uri against the template, returning the values of the variables it defines.
+    /// Variables that are left out of the uri, such as optional query parameters, are missing from
+    /// the map.
+    #[must_use]
+    pub fn matches(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut variables = HashMap::new();
+        match_parts(&self.parts, uri, &mut variables).then_some(variables)
+    }
+
+    /// Orders overlapping templates from the most to the least specific: the more literal text
+    /// a template has, the more specific it is, and with equal text, the fewer expressions
+    pub(crate) fn specificity(&self) -> (usize, Reverse<usize>) {
+        let (literal, expressions) =
+            self.parts
+                .iter()
+                .fold((0, 0), |(literal, expressions), part| match part {
+                    Part::Literal(text) => (literal + text.len(), expressions),
+                    Part::Expression { .. } => (literal, expressions + 1),
+                });
+        (literal, Reverse(expressions))
+    }
+}
+
+fn match_parts(parts: &[Part], uri: &str, variables: &mut HashMap<String, String>) -> bool {
+    let Some((part, rest)) = parts.split_first() else {
+        return uri.is_empty();
+    };
+
+    match part {
+        Part::Literal(literal) => uri
+            .strip_prefix(literal.as_str())
+            .is_some_and(|uri| match_parts(rest, uri, variables)),
+        Part::Expression {
+            operator,
+            variables: names,
+        } => {
+            // The shortest expansion is tried first, so that in `{name}{.ext}` the name leaves
+            // the extension to the label, backtracking while the rest of the template doesn't
+            // match what remains
+            let longest = expansion_len(*operator, uri);
+            (0..=longest)
+                .filter(|&end| uri.is_char_boundary(end))
+                .any(|end| {
+                    let mut captured = variables.clone();
+                    let matched = capture(*operator, names, &uri[..end], &mut captured)
+                        && match_parts(rest, &uri[end..], &mut captured);
+                    if matched {
+                        *variables = captured;
+                    }
+                    matched
+                })
+        }
+    }
+}
+
+/// The length of the longest prefix of the uri that an expression with the operator could have
+/// expanded to
+fn expansion_len(operator: Option<char>, uri: &str) -> usize {
+    let allowed: &str = match operator {
+        None => ",",
+        Some('+' | '#') => RESERVED,
+        Some('/') => "/,",
+        Some(';') => ";=,",
+        Some('?' | '&') => "?&=,",
+        Some(_) => ",",
+    };
+    uri.char_indices()
+        .find(|&(index, c)| {
+            let prefix = index == 0 && operator.is_some_and(|operator| operator == c);
+            !prefix && RESERVED.contains(c) && !allowed.contains(c)
+        })
+        .map_or(uri.len(), |(index, _)| index)
+}
+
+/// Assigns the variables of an expression from the text it expanded to
+fn capture(
+    operator: Option<char>,
+    names: &[String],
+    text: &str,
+    variables: &mut HashMap<String, String>,
+) -> bool {
+    // Undefined variables expand to nothing, including the operator's prefix
+    if text.is_empty() {
+        return true;
+    }
+    let text = match operator {
+        None | Some('+') => text,
+        Some(prefix) => match text.strip_prefix(prefix) {
+            Some(text) => text,
+            None => return false,
+        },
+    };
+
+    let mut assign = |name: &str, value: &str| {
+        let value = percent_decode(value);
+        match variables.get(name) {
+            // A variable used twice must have the same value both times
+            Some(existing) => *existing == value,
+            None => {
+                variables.insert(name.to_string(), value);
+                true
+            }
+        }
+    };
+
+    match operator {
+        Some(';' | '?' | '&') => {
+            let separator = if operator == Some(';') { ';' } else { '&' };
+            text.split(separator).all(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                names.iter().any(|known| known == name) && assign(name, value)
+            })
+        }
+        _ if names.len() == 1 => assign(&names[0], text),
+        _ => {
+            let separator = match operator {
+                Some('.') => '.',
+                Some('/') => '/',
+                _ => ',',
+            };
+            let values: Vec<_> = text.split(separator).collect();
+            values.len() <= names.len()
+                && names
+                    .iter()
+                    .zip(values)
+                    .all(|(name, value)| assign(name, value))
+        }
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| value.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,756 @@
+use crate::content::{Contents, IntoContents};
+use crate::context::RequestContext;
+use crate::latency::{LatencyStats, LatencyWindow};
+use crate::registry::{
+    AsyncFnExt, HandlerArgs, HandlerFn, HandlerRegistry, Page, SchemaOptions, insert_meta, schema,
+};
+use crate::{Error, ErrorCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Delay before the first retry of a failed tool call, doubled on every further retry
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A registry for managing available tools with shared state
+pub struct ToolRegistry<State> {
+    registry: HandlerRegistry<Tool<State>>,
+}
+
+impl<State> ToolRegistry<State> {
+    /// Register a new tool with the given name and handler
+    pub fn register(&mut self, tool: Tool<State>) {
+        self.registry.register(tool.name.clone(), tool);
+    }
+}
+
+impl<State: Clone + Send + Sync + 'static> ToolRegistry<State> {
+    /// Call a tool by name with the given arguments. Failures of the tool are returned as results
+    /// marked with `isError`, so the model can react to them.
+    ///
+    /// # Errors
+    /// If the tool doesn't exist or the arguments are invalid, this will error.
+    pub fn call_tool(
+        &self,
+        state: State,
+        request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + use<State> + Send + 'static
+    {
+        let span = tracing::info_span!("mcp.tool", tool = %request.name);
+        span.in_scope(|| {
+            self.registry
+                .call(state, &request.name, request.arguments.unwrap_or_default())
+        })
+        .instrument(span)
+    }
+}
+
+impl<State: Send + Sync + 'static> ToolRegistry<State> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a tool by name
+    pub fn get(&self, name: &str) -> Option<&Tool<State>> {
+        self.registry.get(name)
+    }
+
+    /// Names of tools that were registered more than once, replacing the earlier tool
+    pub fn duplicate_names(&self) -> &[String] {
+        self.registry.duplicates()
+    }
+
+    /// Iterate through all registered tools, ordered by name
+    pub fn tools_iter(&self) -> impl Iterator<Item = (&String, &Tool<State>)> {
+        self.registry.handlers_iter()
+    }
+
+    /// A page of at most `limit` tools, ordered by name, starting after the cursor returned with
+    /// the previous page
+    ///
+    /// # Errors
+    /// If the cursor is invalid, this will error.
+    pub fn page(&self, cursor: Option<&str>, limit: usize) -> Result<Page<&Tool<State>>, Error> {
+        self.registry.page(cursor, limit)
+    }
+}
+
+impl<State> Default for ToolRegistry<State> {
+    fn default() -> Self {
+        Self {
+            registry: HandlerRegistry::default(),
+        }
+    }
+}
+
+/// Rewrites the arguments of a tool call before they are deserialized and passed to the handler.
+///
+/// This is useful for normalizing paths, filling in server-side defaults of optional arguments, or
+/// resolving aliases so that handlers only ever see canonical input. Transformers receive arguments
+/// that were already validated against the input schema.
+pub trait ArgumentTransformer<State> {
+    /// # Errors
+    /// If the arguments cannot be transformed, this will error and the handler will not be called.
+    fn transform(&self, state: &State, args: HandlerArgs) -> Result<HandlerArgs, Error>;
+}
+
+impl<State, F> ArgumentTransformer<State> for F
+where
+    F: Fn(&State, HandlerArgs) -> Result<HandlerArgs, Error>,
+{
+    fn transform(&self, state: &State, args: HandlerArgs) -> Result<HandlerArgs, Error> {
+        self(state, args)
+    }
+}
+
+/// Hints describing the behavior of a tool to clients. Clients must not rely on these for security
+/// decisions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// The tool does not modify its environment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// The tool may perform destructive updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Calling the tool repeatedly with the same arguments has no additional effect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// The tool interacts with external entities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+type BoxedArgumentTransformer<State> = Box<dyn ArgumentTransformer<State> + Send + Sync>;
+
+type InitFn<State> =
+    Box<dyn Fn(State) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>> + Send + Sync>;
+type TeardownFn<State> =
+    Box<dyn Fn(State) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// A hook that prepares a tool before its first call, such as by opening connections or loading
+/// models. It runs at most once, and its failure is remembered and returned by every call.
+struct ToolInit<State> {
+    init: InitFn<State>,
+    on_first_call: bool,
+    result: tokio::sync::OnceCell<Result<(), String>>,
+}
+
+impl<State> ToolInit<State> {
+    async fn ensure(&self, state: State) -> Result<(), Error> {
+        self.result
+            .get_or_init(|| async { (self.init)(state).await.map_err(|e| e.message) })
+            .await
+            .clone()
+            .map_err(|message| Error::internal(format!("Tool failed to initialize: {message}")))
+    }
+}
+
+pub struct Tool<State> {
+    name: String,
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    annotations: Option<ToolAnnotations>,
+    docs: Option<String>,
+    dry_run: Option<HandlerArgs>,
+    schema: serde_json::Value,
+    output_schema: Option<serde_json::Value>,
+    transformers: Vec<BoxedArgumentTransformer<State>>,
+    coerce_arguments: bool,
+    max_argument_size: Option<usize>,
+    timeout: Option<Duration>,
+    retries: u32,
+    init: Option<Arc<ToolInit<State>>>,
+    teardown: Option<TeardownFn<State>>,
+    latency: Arc<LatencyWindow>,
+    handler: Arc<dyn HandlerFn<State, Contents> + Send + Sync>,
+}
+
+impl<State: Send + Sync + 'static> Tool<State> {
+    #[must_use]
+    pub fn builder() -> ToolBuilder<State> {
+        ToolBuilder::new()
+    }
+}
+
+impl<State> Tool<State> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Long-form markdown documentation of this tool
+    pub fn docs(&self) -> Option<&str> {
+        self.docs.as_deref()
+    }
+
+    fn definitions(&self) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        self.schema
+            .get("definitions")
+            .and_then(serde_json::Value::as_object)
+    }
+
+    fn coerce(&self, args: HandlerArgs) -> HandlerArgs {
+        let mut args = serde_json::Value::Object(args.into_iter().collect());
+        schema::coerce(
+            &mut args,
+            &self.schema,
+            self.definitions().unwrap_or(&serde_json::Map::new()),
+        );
+        match args {
+            serde_json::Value::Object(args) => args.into_iter().collect(),
+            _ => HashMap::new(),
+        }
+    }
+
+    /// Checks the arguments against the input schema, describing how to repair them in the
+    /// error's `data` so that the caller can correct them in one round trip
+    fn validate(&self, args: &HandlerArgs) -> Result<(), Error> {
+        let args = serde_json::Value::Object(
+            args.iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        );
+        let Some(data) = schema::repair(
+            &args,
+            &self.schema,
+            self.definitions().unwrap_or(&serde_json::Map::new()),
+        ) else {
+            return Ok(());
+        };
+
+        let first = &data["violations"][0];
+        Err(Error::invalid_params(format!(
+            "Invalid arguments at '{}': expected {}",
+            first["pointer"].as_str().unwrap_or_default(),
+            first["expected"]
+        ))
+        .with_data(data))
+    }
+
+    /// Rejects arguments whose serialized size exceeds the limit of this tool
+    fn check_size(&self, args: &HandlerArgs) -> Result<(), Error> {
+        let Some(limit) = self.max_argument_size else {
+            return Ok(());
+        };
+        let size = serde_json::to_vec(args).map_or(0, |args| args.len());
+        if size <= limit {
+            return Ok(());
+        }
+        Err(Error::invalid_request(format!(
+            "Arguments of {size} bytes exceed the limit of {limit} bytes for tool '{}'",
+            self.name
+        )))
+    }
+
+    /// Arguments this tool is called with by [`crate::BasicService::self_check`]
+    pub const fn dry_run_args(&self) -> Option<&HandlerArgs> {
+        self.dry_run.as_ref()
+    }
+
+    /// The JSON schema of the input of this tool
+    pub const fn input_schema(&self) -> &serde_json::Value {
+        &self.schema
+    }
+
+    /// Whether this tool has an init hook
+    pub const fn has_init(&self) -> bool {
+        self.init.is_some()
+    }
+
+    /// Whether this tool's init hook waits for the first call instead of running at startup
+    pub fn init_on_first_call(&self) -> bool {
+        self.init.as_ref().is_some_and(|init| init.on_first_call)
+    }
+
+    /// Runs the init hook of this tool if it hasn't run yet, returning its result
+    ///
+    /// # Errors
+    /// If the init hook failed, now or on an earlier run, this will error.
+    pub async fn warm_up(&self, state: State) -> Result<(), Error> {
+        match &self.init {
+            Some(init) => init.ensure(state).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Latency percentiles over the recent calls of this tool, if it has been called
+    pub fn latency(&self) -> Option<LatencyStats> {
+        self.latency.stats()
+    }
+
+    /// Runs the teardown hook of this tool, if it has one
+    pub async fn teardown(&self, state: State) {
+        if let Some(teardown) = &self.teardown {
+            teardown(state).await;
+        }
+    }
+
+    /// The uri this tool's documentation is exposed at, if enabled on the service
+    #[must_use]
+    pub fn docs_uri(&self) -> String {
+        format!("{TOOL_URI_PREFIX}{}/docs", self.name)
+    }
+
+    /// The uri this tool's input schema is exposed at, if lazy schemas are enabled on the service
+    #[must_use]
+    pub fn schema_uri(&self) -> String {
+        format!("{TOOL_URI_PREFIX}{}/schema", self.name)
+    }
+}
+
+/// Prefix of the uris of resources describing registered tools
+pub(crate) const TOOL_URI_PREFIX: &str = "mcp://tools/";
+
+/// Runs one attempt of a tool, failing if it takes longer than the timeout
+async fn attempt(
+    contents: impl Future<Output = Result<Contents, Error>>,
+    name: &str,
+    timeout: Option<Duration>,
+) -> Result<Contents, Error> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, contents)
+            .await
+            .map_err(|_| Error::internal(format!("Tool '{name}' timed out after {timeout:?}")))?,
+        None => contents.await,
+    }
+}
+
+/// Reports a failure of a tool to the model as its result, along with the report of its causes
+/// when error details are enabled
+fn failure(error: Error) -> mcp_schema::CallToolResult {
+    let report = error
+        .report()
+        .filter(|_| RequestContext::current().error_details());
+    let mut content = vec![crate::content::text(error.message)];
+    content.extend(report.map(crate::content::text));
+    mcp_schema::CallToolResult {
+        meta: None,
+        content,
+        is_error: Some(true),
+        extra: HashMap::new(),
+    }
+}
+
+impl<State: Clone + Send + Sync + 'static> HandlerFn<State, mcp_schema::CallToolResult>
+    for Tool<State>
+{
+    fn run(
+        &self,
+        state: State,
+        args: HandlerArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send>> {
+        if let Err(e) = self.check_size(&args) {
+            return Box::pin(async move { Err(e) });
+        }
+        let args = if self.coerce_arguments {
+            self.coerce(args)
+        } else {
+            args
+        };
+        if let Err(e) = self.validate(&args) {
+            return Box::pin(async move { Err(e) });
+        }
+        let args = self
+            .transformers
+            .iter()
+            .try_fold(args, |args, transformer| {
+                transformer.transform(&state, args)
+            });
+        let args = match args {
+            Ok(args) => args,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        let handler = self.handler.clone();
+        let init = self.init.clone();
+        let latency = self.latency.clone();
+        let name = self.name.clone();
+        let timeout = self.timeout;
+        let retries = self.retries;
+        Box::pin(async move {
+            if let Some(init) = init {
+                // The tool failed rather than the request, so the model is told like any failure
+                if let Err(e) = init.ensure(state.clone()).await {
+                    return Ok(failure(e));
+                }
+            }
+
+            let started = Instant::now();
+            let mut backoff = RETRY_BACKOFF;
+            let mut retried = 0;
+            let contents = loop {
+                let contents = handler.run(state.clone(), args.clone());
+                match attempt(contents, &name, timeout).await {
+                    // Timeouts and internal errors may pass, while other errors would repeat
+                    Err(e) if retried < retries && e.code == ErrorCode::Internal => {
+                        tracing::warn!("Retrying tool '{name}' after error: {}", e.message);
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                        retried += 1;
+                    }
+                    result => break result,
+                }
+            };
+            latency.record(started.elapsed());
+            let contents = match contents {
+                Ok(contents) => contents,
+                Err(e) if e.code.is_protocol() => return Err(e),
+                Err(e) => return Ok(failure(e)),
+            };
+
+            let mut extra = HashMap::new();
+            if let Some(structured) = contents.structured {
+                extra.insert("structuredContent".to_string(), structured);
+            }
+
+            Ok(mcp_schema::CallToolResult {
+                meta: None,
+                content: contents.content,
+                is_error: Some(false),
+                extra,
+            })
+        })
+    }
+}
+
+impl<State> TryFrom<&Tool<State>> for mcp_schema::Tool {
+    type Error = serde_json::Error;
+
+    fn try_from(tool: &Tool<State>) -> Result<Self, Self::Error> {
+        let mut extra = HashMap::new();
+        if let Some(title) = &tool.title {
+            extra.insert("title".to_string(), title.clone().into());
+        }
+        if let Some(annotations) = &tool.annotations {
+            extra.insert(
+                "annotations".to_string(),
+                serde_json::to_value(annotations)?,
+            );
+        }
+        if let Some(output_schema) = &tool.output_schema {
+            extra.insert("outputSchema".to_string(), output_schema.clone());
+        }
+        if let Some(version) = &tool.version {
+            insert_meta(&mut extra, "version", version.clone().into());
+        }
+
+        Ok(Self {
+            description: tool.description.clone(),
+            input_schema: serde_json::from_value(tool.schema.clone())?,
+            name: tool.name.clone(),
+            extra,
+        })
+    }
+}
+
+/// Converts the output of a handler into [`Contents`]
+struct IntoContentsHandler<H, O> {
+    handler: H,
+    phantom: PhantomData<fn() -> O>,
+}
+
+impl<State, H, O> HandlerFn<State, Contents> for IntoContentsHandler<H, O>
+where
+    H: HandlerFn<State, O>,
+    O: IntoContents + 'static,
+{
+    fn run(
+        &self,
+        state: State,
+        args: HandlerArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<Contents, Error>> + Send>> {
+        let output = self.handler.run(state, args);
+        Box::pin(async move { output.await?.into_contents() })
+    }
+}
+
+/// A builder for constructing a tool with validation and metadata
+pub struct ToolBuilder<State> {
+    name: Option<String>,
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    annotations: Option<ToolAnnotations>,
+    docs: Option<String>,
+    dry_run: Option<HandlerArgs>,
+    schema: Option<serde_json::Value>,
+    output_schema: Option<serde_json::Value>,
+    schema_options: SchemaOptions,
+    transformers: Vec<BoxedArgumentTransformer<State>>,
+    coerce_arguments: bool,
+    max_argument_size: Option<usize>,
+    timeout: Option<Duration>,
+    retries: u32,
+    init: Option<InitFn<State>>,
+    init_on_first_call: bool,
+    teardown: Option<TeardownFn<State>>,
+    handler: Option<Box<dyn HandlerFn<State, Contents> + Send + Sync>>,
+}
+
+impl<State: Send + Sync + 'static> ToolBuilder<State> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets a human readable title for display in clients
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the version of this tool, which is listed in its `_meta`
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    #[must_use]
+    pub fn annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Fails calls to this tool that take longer than the timeout
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries calls that time out or fail with an internal error, with exponential backoff. Since
+    /// repeating other tools could duplicate their side effects, the tool must be annotated with
+    /// `idempotent_hint`.
+    #[must_use]
+    pub const fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets a hook that prepares this tool, such as by opening connections or priming caches. It
+    /// runs once, at startup in [`crate::BasicService::warm_up`] unless [`Self::init_on_first_call`]
+    /// is set, and otherwise before the first call.
+    #[must_use]
+    pub fn init<F, Fut>(mut self, init: F) -> Self
+    where
+        F: Fn(State) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        self.init = Some(Box::new(move |state| Box::pin(init(state))));
+        self
+    }
+
+    /// Defers the init hook to the first call of this tool instead of startup
+    #[must_use]
+    pub const fn init_on_first_call(mut self, on_first_call: bool) -> Self {
+        self.init_on_first_call = on_first_call;
+        self
+    }
+
+    /// Sets a hook that releases what this tool holds, such as connections, when the server shuts
+    /// down gracefully
+    #[must_use]
+    pub fn teardown<F, Fut>(mut self, teardown: F) -> Self
+    where
+        F: Fn(State) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.teardown = Some(Box::new(move |state| Box::pin(teardown(state))));
+        self
+    }
+
+    /// Sets arguments to call this tool with during [`crate::BasicService::self_check`]. Only set
+    /// this for tools without side effects.
+    #[must_use]
+    pub fn dry_run(mut self, args: HandlerArgs) -> Self {
+        self.dry_run = Some(args);
+        self
+    }
+
+    /// Sets long-form markdown documentation, which is not part of the tool listing but can be
+    /// exposed as a resource with [`crate::BasicService::tool_docs`]
+    #[must_use]
+    pub fn docs(mut self, markdown: impl Into<String>) -> Self {
+        self.docs = Some(markdown.into());
+        self
+    }
+
+    /// Advertises the schema of the structured output of this tool, generated from `O`
+    #[must_use]
+    pub fn output<O: schemars::JsonSchema>(self) -> Self {
+        self.output_schema(serde_json::to_value(schemars::schema_for!(O)).unwrap())
+    }
+
+    /// Advertises a handwritten schema of the structured output of this tool
+    #[must_use]
+    pub fn output_schema(mut self, schema: serde_json::Value) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
+    /// Sets how the input schema generated from the handler input is post-processed, which is
+    /// mostly useful for enum inputs
+    #[must_use]
+    pub const fn schema_options(mut self, options: SchemaOptions) -> Self {
+        self.schema_options = options;
+        self
+    }
+
+    /// Adds a transformer that rewrites the arguments before they reach the handler. Transformers
+    /// run in the order they were added, after the arguments are coerced and validated against the
+    /// input schema.
+    #[must_use]
+    pub fn transform(
+        mut self,
+        transformer: impl ArgumentTransformer<State> + Send + Sync + 'static,
+    ) -> Self {
+        self.transformers.push(Box::new(transformer));
+        self
+    }
+
+    /// Fixes benign type mismatches in the arguments before they are deserialized, guided by the
+    /// input schema: numbers and booleans given as strings are parsed, and a single value is
+    /// wrapped where an array is expected. This runs before the arguments are validated and
+    /// transformed.
+    #[must_use]
+    pub const fn coerce_arguments(mut self, coerce: bool) -> Self {
+        self.coerce_arguments = coerce;
+        self
+    }
+
+    /// Limits the size of the arguments this tool accepts, measured as serialized JSON before
+    /// they are transformed. Larger arguments are rejected with an `Invalid Request` error.
+    #[must_use]
+    pub const fn max_argument_size(mut self, bytes: usize) -> Self {
+        self.max_argument_size = Some(bytes);
+        self
+    }
+
+    /// Sets the handler of this tool. The handler may return anything implementing
+    /// [`IntoContents`], such as `Vec<PromptContent>`, a `String`, or [`crate::content::Json`].
+    /// Closures may capture values such as clients, which they clone into each call's future.
+    ///
+    /// Errors are reported to the model as a result marked with `isError`, except for errors
+    /// whose [`crate::ErrorCode::is_protocol`], such as [`Error::invalid_params`], which are sent
+    /// as JSON-RPC errors.
+    #[must_use]
+    pub fn handler<I, O, Sub>(
+        mut self,
+        handler: impl AsyncFnExt<State, I, O, Sub> + Send + Sync + 'static,
+    ) -> Self
+    where
+        I: DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        O: IntoContents + 'static,
+        Sub: 'static,
+    {
+        self.schema = Some(serde_json::to_value(schemars::schema_for!(I)).unwrap());
+        self.handler = Some(Box::new(IntoContentsHandler {
+            handler: handler.handler(),
+            phantom: PhantomData,
+        }));
+        self
+    }
+
+    /// Builds a tool.
+    ///
+    /// # Errors
+    /// If the name or handler was not set, or retries were set on a tool that isn't annotated with
+    /// `idempotent_hint`, this will error.
+    pub fn build(self) -> Result<Tool<State>, Error> {
+        let mut schema = self
+            .schema
+            .ok_or_else(|| Error::internal("missing handler input schema"))?;
+        self.schema_options.apply(&mut schema);
+
+        let idempotent = self
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.idempotent_hint)
+            .unwrap_or(false);
+        if self.retries > 0 && !idempotent {
+            return Err(Error::internal(format!(
+                "Tool '{}' sets retries but isn't annotated with idempotent_hint",
+                self.name.as_deref().unwrap_or("unnamed tool")
+            )));
+        }
+
+        Ok(Tool {
+            name: self.name.unwrap_or_else(|| "unnamed tool".to_string()),
+            title: self.title,
+            version: self.version,
+            description: self.description,
+            annotations: self.annotations,
+            docs: self.docs,
+            dry_run: self.dry_run,
+            schema,
+            output_schema: self.output_schema,
+            transformers: self.transformers,
+            coerce_arguments: self.coerce_arguments,
+            max_argument_size: self.max_argument_size,
+            timeout: self.timeout,
+            retries: self.retries,
+            init: self.init.map(|init| {
+                Arc::new(ToolInit {
+                    init,
+                    on_first_call: self.init_on_first_call,
+                    result: tokio::sync::OnceCell::new(),
+                })
+            }),
+            teardown: self.teardown,
+            latency: Arc::default(),
+            handler: self
+                .handler
+                .ok_or_else(|| Error::internal("missing handler"))?
+                .into(),
+        })
+    }
+}
+
+impl<State> Default for ToolBuilder<State> {
+    fn default() -> Self {
+        Self {
+            name: None,
+            title: None,
+            version: None,
+            description: None,
+            annotations: None,
+            docs: None,
+            dry_run: None,
+            schema: None,
+            output_schema: None,
+            schema_options: SchemaOptions::default(),
+            transformers: Vec::new(),
+            coerce_arguments: false,
+            max_argument_size: None,
+            timeout: None,
+            retries: 0,
+            init: None,
+            init_on_first_call: false,
+            teardown: None,
+            handler: None,
+        }
+    }
+}
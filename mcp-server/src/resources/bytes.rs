@@ -0,0 +1,171 @@
+use crate::Error;
+use crate::registry::resource::Source;
+use base64::Engine;
+use mcp_schema::ResourceContents;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Notify;
+
+/// Largest binary resource accepted by default. Blobs are sent base64 encoded in a single
+/// message, which makes them a third larger.
+pub const DEFAULT_MAX_BLOB_SIZE: usize = 16 * 1024 * 1024;
+
+/// Size of the chunks read by [`read_blob`]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn too_large(max_size: usize) -> Error {
+    Error::internal(format!(
+        "Binary contents exceed the limit of {max_size} bytes"
+    ))
+}
+
+/// Creates base64 encoded contents of a binary resource, such as an image or a PDF
+///
+/// # Errors
+/// If the bytes are larger than [`DEFAULT_MAX_BLOB_SIZE`], this will error.
+pub fn blob(
+    uri: impl Into<String>,
+    mime_type: impl Into<String>,
+    bytes: &[u8],
+) -> Result<ResourceContents, Error> {
+    if bytes.len() > DEFAULT_MAX_BLOB_SIZE {
+        return Err(too_large(DEFAULT_MAX_BLOB_SIZE));
+    }
+    Ok(encode(uri.into(), Some(mime_type.into()), bytes))
+}
+
+/// Reads binary contents from a reader, such as a file, and encodes them like [`blob`]. Reading
+/// stops as soon as the contents exceed the maximum size, so large inputs are never buffered
+/// whole.
+///
+/// # Errors
+/// If reading fails or the contents are larger than the maximum size, this will error.
+pub async fn read_blob(
+    uri: impl Into<String>,
+    mime_type: impl Into<String>,
+    reader: impl AsyncRead + Unpin,
+    max_size: usize,
+) -> Result<ResourceContents, Error> {
+    let bytes = read_bytes(reader, max_size).await?;
+    Ok(encode(uri.into(), Some(mime_type.into()), &bytes))
+}
+
+/// Reads a reader to the end, stopping as soon as it exceeds the maximum size
+pub(crate) async fn read_bytes(
+    mut reader: impl AsyncRead + Unpin,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let read = reader
+            .read(&mut chunk)
+            .await
+            .map_err(|e| Error::internal(format!("Failed to read binary contents: {e}")))?;
+        if read == 0 {
+            break;
+        }
+        if bytes.len() + read > max_size {
+            return Err(too_large(max_size));
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+    }
+    Ok(bytes)
+}
+
+pub(crate) fn encode(uri: String, mime_type: Option<String>, bytes: &[u8]) -> ResourceContents {
+    ResourceContents::Blob(mcp_schema::BlobResourceContents {
+        uri,
+        mime_type,
+        blob: base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
+}
+
+struct BytesResourceInner {
+    mime_type: String,
+    max_size: usize,
+    /// The base64 encoding of the bytes, which is done once when they are set
+    encoded: Mutex<Option<String>>,
+    change: Notify,
+}
+
+/// A binary resource held in memory, like [`crate::resources::MemoryResource`] for images, PDFs
+/// and other data that isn't text. Until bytes are set, the resource is empty.
+#[derive(Clone)]
+pub struct BytesResource {
+    inner: Arc<BytesResourceInner>,
+}
+
+impl BytesResource {
+    /// Creates an empty resource of the given MIME type, such as `image/png`
+    #[must_use]
+    pub fn new(mime_type: impl Into<String>) -> Self {
+        Self::with_max_size(mime_type, DEFAULT_MAX_BLOB_SIZE)
+    }
+
+    /// Creates an empty resource that accepts at most `max_size` bytes
+    #[must_use]
+    pub fn with_max_size(mime_type: impl Into<String>, max_size: usize) -> Self {
+        Self {
+            inner: Arc::new(BytesResourceInner {
+                mime_type: mime_type.into(),
+                max_size,
+                encoded: Mutex::new(None),
+                change: Notify::new(),
+            }),
+        }
+    }
+
+    /// Replaces the bytes and notifies subscribers
+    ///
+    /// # Errors
+    /// If the bytes are larger than the maximum size, this will error and the previous bytes are
+    /// kept.
+    pub fn set(&self, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.len() > self.inner.max_size {
+            return Err(too_large(self.inner.max_size));
+        }
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+        *self.inner.encoded.lock().unwrap() = Some(encoded);
+        self.inner.change.notify_waiters();
+        Ok(())
+    }
+
+    /// Removes the bytes and notifies subscribers
+    pub fn clear(&self) {
+        *self.inner.encoded.lock().unwrap() = None;
+        self.inner.change.notify_waiters();
+    }
+}
+
+impl<State: Send> Source<State> for BytesResource {
+    fn read(
+        &self,
+        _: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + Send + 'static {
+        let contents = self
+            .inner
+            .encoded
+            .lock()
+            .unwrap()
+            .clone()
+            .map(|blob| {
+                ResourceContents::Blob(mcp_schema::BlobResourceContents {
+                    uri,
+                    mime_type: Some(self.inner.mime_type.clone()),
+                    blob,
+                })
+            })
+            .into_iter()
+            .collect();
+        async move { Ok(contents) }
+    }
+
+    fn wait_for_change(&self, _: State, _: String) -> impl Future<Output = ()> + Send + 'static {
+        let inner = self.inner.clone();
+        async move {
+            inner.change.notified().await;
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::Error;
+use crate::registry::resource::Source;
+use futures::future::Either;
+use mcp_schema::ResourceContents;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+struct Entry {
+    read_at: Instant,
+    contents: Vec<ResourceContents>,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<String, Entry>,
+    /// Incremented by every invalidation, so that reads started before one don't store what they
+    /// read
+    generation: u64,
+}
+
+struct CachedInner<S> {
+    source: S,
+    ttl: Duration,
+    cache: Mutex<Cache>,
+}
+
+impl<S> CachedInner<S> {
+    fn invalidate(&self, uri: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.entries.remove(uri);
+        cache.generation += 1;
+    }
+}
+
+/// Wraps a source and caches what it reads per uri for the given time to live, so that expensive
+/// backends such as databases or APIs aren't queried on every read.
+///
+/// A uri is read again once its cached contents expire, or as soon as the source reports a change
+/// while a client is subscribed to it. Failed reads aren't cached.
+pub struct CachedSource<S> {
+    inner: Arc<CachedInner<S>>,
+}
+
+impl<S> Clone for CachedSource<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S> CachedSource<S> {
+    #[must_use]
+    pub fn new(source: S, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(CachedInner {
+                source,
+                ttl,
+                cache: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Drops the cached contents of a uri, such as after writing to the backend directly
+    pub fn invalidate(&self, uri: &str) {
+        self.inner.invalidate(uri);
+    }
+
+    /// Drops every cached uri
+    pub fn clear(&self) {
+        let mut cache = self.inner.cache.lock().unwrap();
+        cache.entries.clear();
+        cache.generation += 1;
+    }
+}
+
+impl<State, S> Source<State> for CachedSource<S>
+where
+    S: Source<State> + Send + Sync + 'static,
+{
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + Send + 'static {
+        let generation = {
+            let cache = self.inner.cache.lock().unwrap();
+            if let Some(entry) = cache
+                .entries
+                .get(&uri)
+                .filter(|entry| entry.read_at.elapsed() < self.inner.ttl)
+            {
+                return Either::Left(std::future::ready(Ok(entry.contents.clone())));
+            }
+            cache.generation
+        };
+
+        let inner = self.inner.clone();
+        let read_at = Instant::now();
+        let contents = self.inner.source.read(state, uri.clone());
+        Either::Right(async move {
+            let contents = contents.await?;
+            let mut cache = inner.cache.lock().unwrap();
+            if cache.generation == generation {
+                cache.entries.insert(
+                    uri,
+                    Entry {
+                        read_at,
+                        contents: contents.clone(),
+                    },
+                );
+            }
+            Ok(contents)
+        })
+    }
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let inner = self.inner.clone();
+        let change = self.inner.source.wait_for_change(state, uri.clone());
+        async move {
+            change.await;
+            inner.invalidate(&uri);
+        }
+    }
+
+    fn teardown(&self, state: State) -> impl Future<Output = ()> + Send + 'static {
+        self.inner.source.teardown(state)
+    }
+}
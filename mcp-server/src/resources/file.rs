@@ -0,0 +1,127 @@
+use crate::Error;
+use crate::registry::resource::Source;
+use crate::resources::bytes::{DEFAULT_MAX_BLOB_SIZE, encode, read_bytes};
+use mcp_schema::ResourceContents;
+use notify::Watcher;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// A resource backed by a file, which notifies subscribers as soon as the file is written,
+/// replaced or removed, using the operating system's filesystem notifications.
+///
+/// Contents that are valid UTF-8 are read as text, and anything else as a base64 encoded blob.
+/// The file's directory is watched rather than the file itself, so that editors which save by
+/// replacing the file don't end the subscription.
+#[derive(Clone)]
+pub struct WatchedFileResource {
+    path: PathBuf,
+    mime_type: Option<String>,
+    max_size: usize,
+}
+
+impl WatchedFileResource {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mime_type: None,
+            max_size: DEFAULT_MAX_BLOB_SIZE,
+        }
+    }
+
+    /// Sets the MIME type reported with the contents, such as `text/markdown`
+    #[must_use]
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Sets the largest file that is read, [`DEFAULT_MAX_BLOB_SIZE`] by default
+    #[must_use]
+    pub const fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+async fn read_file(
+    path: PathBuf,
+    uri: String,
+    mime_type: Option<String>,
+    max_size: usize,
+) -> Result<ResourceContents, Error> {
+    let file = tokio::fs::File::open(&path).await.map_err(|e| {
+        Error::resource_not_found(format!("Failed to open {}: {e}", path.display()))
+    })?;
+    let bytes = read_bytes(file, max_size).await?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => ResourceContents::Text(mcp_schema::TextResourceContents {
+            uri,
+            mime_type,
+            text,
+        }),
+        Err(e) => encode(uri, mime_type, e.as_bytes()),
+    })
+}
+
+impl<State: Send> Source<State> for WatchedFileResource {
+    fn read(
+        &self,
+        _: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + Send + 'static {
+        let contents = read_file(
+            self.path.clone(),
+            uri,
+            self.mime_type.clone(),
+            self.max_size,
+        );
+        async move { Ok(vec![contents.await?]) }
+    }
+
+    fn wait_for_change(&self, _: State, _: String) -> impl Future<Output = ()> + Send + 'static {
+        let file_name = self.path.file_name().map(ToOwned::to_owned);
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let changed = !event.kind.is_access()
+                && event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == file_name.as_deref());
+            if changed {
+                let _ = tx.send(());
+            }
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&directory, notify::RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+
+        let path = self.path.clone();
+        async move {
+            match watcher {
+                // The watcher stops when dropped, so it is kept until the change arrives
+                Ok(_watcher) => {
+                    rx.recv().await;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to watch {} for changes: {e}", path.display());
+                    std::future::pending::<()>().await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::Error;
+use crate::registry::resource::Source;
+use mcp_schema::ResourceContents;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// A recorded version of a resource
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub version: u64,
+    pub recorded_at: SystemTime,
+    pub contents: Vec<ResourceContents>,
+}
+
+struct HistoryInner<S> {
+    source: S,
+    capacity: usize,
+    versions: Mutex<VecDeque<Version>>,
+}
+
+/// Wraps a source and records every distinct value it serves, keeping the most recent `capacity`
+/// versions. A specific version can be read by appending `?version=N` to the resource uri.
+pub struct ResourceHistory<S> {
+    inner: Arc<HistoryInner<S>>,
+}
+
+impl<S> Clone for ResourceHistory<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S> ResourceHistory<S> {
+    #[must_use]
+    pub fn new(source: S, capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(HistoryInner {
+                source,
+                capacity: capacity.max(1),
+                versions: Mutex::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// The versions that are still retained, oldest first
+    #[must_use]
+    pub fn versions(&self) -> Vec<Version> {
+        self.inner
+            .versions
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Gets a retained version
+    ///
+    /// # Errors
+    /// If the version was never recorded or has been evicted, this will error.
+    pub fn get(&self, version: u64) -> Result<Version, Error> {
+        self.inner
+            .versions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|recorded| recorded.version == version)
+            .cloned()
+            .ok_or_else(|| {
+                Error::resource_not_found(format!("Version {version} is not in the history"))
+            })
+    }
+
+    /// Renders a line diff between two versions. If `to` is not set, the latest version is used.
+    ///
+    /// # Errors
+    /// If either version is not in the history, this will error.
+    pub fn diff(&self, from: u64, to: Option<u64>) -> Result<String, Error> {
+        let to = match to {
+            Some(to) => to,
+            None => self
+                .latest()
+                .ok_or_else(|| Error::resource_not_found("The history is empty"))?,
+        };
+
+        let from = render(&self.get(from)?.contents);
+        let to = render(&self.get(to)?.contents);
+        Ok(line_diff(&from, &to))
+    }
+
+    fn latest(&self) -> Option<u64> {
+        self.inner
+            .versions
+            .lock()
+            .unwrap()
+            .back()
+            .map(|recorded| recorded.version)
+    }
+}
+
+impl<S> HistoryInner<S> {
+    /// Records the contents as a new version if they differ from the latest one
+    fn record(&self, contents: &[ResourceContents]) {
+        let Ok(value) = serde_json::to_value(contents) else {
+            return;
+        };
+
+        let mut versions = self.versions.lock().unwrap();
+        if let Some(latest) = versions.back() {
+            if serde_json::to_value(&latest.contents).is_ok_and(|latest| latest == value) {
+                return;
+            }
+        }
+
+        let version = versions.back().map_or(1, |latest| latest.version + 1);
+        if versions.len() == self.capacity {
+            versions.pop_front();
+        }
+        versions.push_back(Version {
+            version,
+            recorded_at: SystemTime::now(),
+            contents: contents.to_vec(),
+        });
+    }
+}
+
+/// Parses the `version` query parameter of a uri
+fn requested_version(uri: &str) -> Result<Option<u64>, Error> {
+    let Some((_, query)) = uri.split_once('?') else {
+        return Ok(None);
+    };
+
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("version="))
+        .map(|version| {
+            version
+                .parse()
+                .map_err(|_| Error::invalid_params(format!("Invalid resource version '{version}'")))
+        })
+        .transpose()
+}
+
+impl<State, S> Source<State> for ResourceHistory<S>
+where
+    S: Source<State> + Send + Sync + 'static,
+{
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + Send + 'static {
+        let requested = requested_version(&uri);
+        let history = self.clone();
+        let current = matches!(requested, Ok(None)).then(|| self.inner.source.read(state, uri));
+
+        async move {
+            if let Some(version) = requested? {
+                return Ok(history.get(version)?.contents);
+            }
+
+            let contents = current
+                .expect("current is read when no version is requested")
+                .await?;
+            history.inner.record(&contents);
+            Ok(contents)
+        }
+    }
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        uri: String,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        self.inner.source.wait_for_change(state, uri)
+    }
+
+    fn teardown(&self, state: State) -> impl Future<Output = ()> + Send + 'static {
+        self.inner.source.teardown(state)
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DiffParams {
+    /// The older version
+    pub from: u64,
+    /// The newer version, the latest by default
+    pub to: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct VersionSummary {
+    version: u64,
+    recorded_at: u64,
+}
+
+/// A tool handler that diffs two versions of a history, listing the retained versions alongside
+/// the diff. The history is extracted from the state with [`crate::registry::FromRef`].
+///
+/// # Errors
+/// If either version is not in the history, this will error.
+pub async fn diff<S>(history: ResourceHistory<S>, params: DiffParams) -> Result<String, Error> {
+    let versions: Vec<_> = history
+        .versions()
+        .into_iter()
+        .map(|recorded| VersionSummary {
+            version: recorded.version,
+            recorded_at: recorded
+                .recorded_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+        })
+        .collect();
+
+    Ok(format!(
+        "{}\n\nRetained versions: {}",
+        history.diff(params.from, params.to)?,
+        serde_json::to_string(&versions)?
+    ))
+}
+
+fn render(contents: &[ResourceContents]) -> String {
+    contents
+        .iter()
+        .map(|contents| match contents {
+            ResourceContents::Text(text) => text.text.clone(),
+            ResourceContents::Blob(blob) => format!("<{} bytes of base64>", blob.blob.len()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A minimal line diff based on the longest common subsequence
+fn line_diff(from: &str, to: &str) -> String {
+    let from: Vec<_> = from.lines().collect();
+    let to: Vec<_> = to.lines().collect();
+
+    let mut lengths = vec![vec![0_usize; to.len() + 1]; from.len() + 1];
+    for i in (0..from.len()).rev() {
+        for j in (0..to.len()).rev() {
+            lengths[i][j] = if from[i] == to[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() || j < to.len() {
+        if i < from.len() && j < to.len() && from[i] == to[j] {
+            let _ = writeln!(diff, "  {}", from[i]);
+            i += 1;
+            j += 1;
+        } else if j < to.len() && (i == from.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            let _ = writeln!(diff, "+ {}", to[j]);
+            j += 1;
+        } else {
+            let _ = writeln!(diff, "- {}", from[i]);
+            i += 1;
+        }
+    }
+    diff
+}
@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 use tokio::sync::Notify;
 
 #[derive(Default)]
-pub struct MemoryResourceInner {
+struct MemoryResourceInner {
     contents: Mutex<Vec<mcp_schema::ResourceContents>>,
     change: Notify,
 }
@@ -0,0 +1,17 @@
+pub mod bytes;
+pub mod cached;
+#[cfg(feature = "notify")]
+pub mod file;
+pub mod history;
+pub mod memory;
+#[cfg(feature = "postgres")]
+pub mod sql;
+
+pub use bytes::BytesResource;
+pub use cached::CachedSource;
+#[cfg(feature = "notify")]
+pub use file::WatchedFileResource;
+pub use history::ResourceHistory;
+pub use memory::MemoryResource;
+#[cfg(feature = "postgres")]
+pub use sql::SqlSource;
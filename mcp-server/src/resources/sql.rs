@@ -0,0 +1,198 @@
+use crate::Error;
+use crate::registry::UriTemplate;
+use crate::registry::resource::Source;
+use mcp_schema::ResourceContents;
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::AbortHandle;
+
+/// Notifications of a channel, forwarded by a task that is aborted once no source uses it
+struct Listener {
+    tx: broadcast::Sender<String>,
+    task: AbortHandle,
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A source that reads rows of a Postgres database as JSON, for template resources such as
+/// `db://{table}/{id}`.
+///
+/// The `table` variable selects the table, which must be allowed with [`SqlSource::table`], and
+/// the `id` variable selects the row by its key column. Without an `id`, as in `db://{table}`, the
+/// whole table is read as a JSON array.
+///
+/// With [`SqlSource::notify_channel`], subscriptions are notified through Postgres `LISTEN`: a
+/// notification on the channel whose payload is a resource's uri marks it as changed, so a
+/// trigger can run `pg_notify('mcp_changes', 'db://users/' || NEW.id)`.
+#[derive(Clone)]
+pub struct SqlSource {
+    pool: PgPool,
+    template: UriTemplate,
+    tables: HashSet<String>,
+    key_column: String,
+    channel: Option<String>,
+    listener: Arc<OnceLock<Listener>>,
+}
+
+impl SqlSource {
+    #[must_use]
+    pub fn new(pool: PgPool, template: impl Into<String>) -> Self {
+        Self {
+            pool,
+            template: UriTemplate::new(template),
+            tables: HashSet::new(),
+            key_column: "id".to_string(),
+            channel: None,
+            listener: Arc::default(),
+        }
+    }
+
+    /// Allows reading a table. Uris naming any other table aren't found.
+    #[must_use]
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.tables.insert(table.into());
+        self
+    }
+
+    /// Sets the column that the `id` variable is compared against, `id` by default
+    #[must_use]
+    pub fn key_column(mut self, column: impl Into<String>) -> Self {
+        self.key_column = column.into();
+        self
+    }
+
+    /// Listens on a Postgres notification channel for the uris of changed resources
+    #[must_use]
+    pub fn notify_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    fn query(&self, uri: &str) -> Result<(String, Option<String>), Error> {
+        let not_found = || Error::resource_not_found(format!("Resource at uri '{uri}' not found"));
+        let mut variables = self.template.matches(uri).ok_or_else(not_found)?;
+        let table = variables
+            .remove("table")
+            .filter(|table| self.tables.contains(table))
+            .ok_or_else(not_found)?;
+        let id = variables.remove("id");
+
+        let table = quote(&table);
+        let sql = if id.is_some() {
+            format!(
+                "SELECT row_to_json(t)::text FROM {table} t WHERE {}::text = $1",
+                quote(&self.key_column)
+            )
+        } else {
+            format!("SELECT coalesce(json_agg(t), '[]')::text FROM {table} t")
+        };
+        Ok((sql, id))
+    }
+
+    /// Subscribes to the notification channel, starting to listen on it if nothing has yet
+    fn changes(&self, channel: &str) -> broadcast::Receiver<String> {
+        self.listener
+            .get_or_init(|| {
+                let (tx, _) = broadcast::channel(100);
+                let task = tokio::spawn(listen(self.pool.clone(), channel.to_string(), tx.clone()));
+                Listener {
+                    tx,
+                    task: task.abort_handle(),
+                }
+            })
+            .tx
+            .subscribe()
+    }
+}
+
+/// Quotes an identifier, so that table and column names are never read as SQL
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn database_error(e: &sqlx::Error) -> Error {
+    Error::internal(format!("Database error: {e}"))
+}
+
+async fn listen(pool: PgPool, channel: String, tx: broadcast::Sender<String>) {
+    loop {
+        let listener = async {
+            let mut listener = PgListener::connect_with(&pool).await?;
+            listener.listen(&channel).await?;
+            Ok::<_, sqlx::Error>(listener)
+        };
+        let mut listener = match listener.await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!("Failed to listen on channel '{channel}': {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+
+        // Receiving reconnects after the connection is lost, so it only fails if that fails
+        while let Ok(notification) = listener.recv().await {
+            let _ = tx.send(notification.payload().to_string());
+        }
+    }
+}
+
+impl<State: Send> Source<State> for SqlSource {
+    fn read(
+        &self,
+        _: State,
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, Error>> + Send + 'static {
+        let query = self.query(&uri);
+        let pool = self.pool.clone();
+        async move {
+            let (sql, id) = query?;
+            let mut query = sqlx::query_scalar::<_, String>(&sql);
+            if let Some(id) = id {
+                query = query.bind(id);
+            }
+            let text = query
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| database_error(&e))?
+                .ok_or_else(|| {
+                    Error::resource_not_found(format!("Resource at uri '{uri}' not found"))
+                })?;
+            Ok(vec![ResourceContents::Text(
+                mcp_schema::TextResourceContents {
+                    uri,
+                    mime_type: Some("application/json".to_string()),
+                    text,
+                },
+            )])
+        }
+    }
+
+    fn wait_for_change(&self, _: State, uri: String) -> impl Future<Output = ()> + Send + 'static {
+        let mut changes = self.channel.as_deref().map(|channel| self.changes(channel));
+        async move {
+            let Some(changes) = &mut changes else {
+                return std::future::pending().await;
+            };
+            loop {
+                match changes.recv().await {
+                    Ok(changed) if changed == uri => return,
+                    Ok(_) => {}
+                    // Missed notifications could have been about this uri
+                    Err(broadcast::error::RecvError::Lagged(_)) => return,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return std::future::pending().await;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use crate::completion::Reference;
+use crate::error::ErrorCode;
+use crate::rpc::ClientMessage;
+use crate::service::DynService;
+use crate::{Error, Service};
+use futures::future::{BoxFuture, try_join_all};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Separates the prefix of a mounted service from the names of its tools and prompts
+const SEPARATOR: char = '_';
+
+/// Most pages read from one service for a listing, in case its cursors never end
+const MAX_PAGES: usize = 1000;
+
+type NotificationHandler = Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>;
+
+/// Serves several services as one. Tools and prompts of each service are listed with the prefix it
+/// was mounted under, as in `github_create_issue`, and calls are routed to the service by that
+/// prefix, preferring the longest one. Names that would be routed to another service are left out
+/// of listings. Resources keep their uris, and are read from the first service that has them.
+/// Listings include every page of each service, and are sent as a single page.
+///
+/// ```ignore
+/// let service = ServiceRouter::new()
+///     .name("tools".to_string())
+///     .mount("github", github)
+///     .mount("jira", jira);
+/// mcp::serve_over_sse(listener, service).await?;
+/// ```
+///
+/// To serve each service at its own path instead, nest their routers, as from
+/// [`crate::BasicService::into_router`], into one application.
+pub struct ServiceRouter {
+    name: String,
+    version: String,
+    instructions: Option<String>,
+    services: Vec<(String, Box<dyn DynService>)>,
+}
+
+impl Default for ServiceRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServiceRouter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            name: "unnamed".to_string(),
+            version: "0.1.0".to_string(),
+            instructions: None,
+            services: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    #[must_use]
+    pub fn version(mut self, version: String) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the instructions of the combined server. Otherwise, the instructions of every mounted
+    /// service are listed under its prefix.
+    #[must_use]
+    pub fn instructions(mut self, instructions: String) -> Self {
+        self.instructions = Some(instructions);
+        self
+    }
+
+    /// Mounts a service under a prefix. Mounting another service under the same prefix replaces
+    /// it.
+    #[must_use]
+    pub fn mount(
+        mut self,
+        prefix: impl Into<String>,
+        service: impl Service + Send + Sync + 'static,
+    ) -> Self {
+        let prefix = prefix.into();
+        self.services.retain(|(existing, _)| *existing != prefix);
+        self.services.push((prefix, Box::new(service)));
+        self
+    }
+
+    /// Finds the prefix and service owning a prefixed name, preferring the longest prefix, and
+    /// strips it
+    fn owner<'a>(&self, name: &'a str) -> Option<(&str, &dyn DynService, &'a str)> {
+        self.services
+            .iter()
+            .filter_map(|(prefix, service)| {
+                let name = name
+                    .strip_prefix(prefix.as_str())?
+                    .strip_prefix(SEPARATOR)?;
+                Some((prefix.as_str(), service.as_ref(), name))
+            })
+            .max_by_key(|(prefix, _, _)| prefix.len())
+    }
+
+    fn route<'a>(&self, name: &'a str) -> Result<(&dyn DynService, &'a str), Error> {
+        self.owner(name)
+            .map(|(_, service, name)| (service, name))
+            .ok_or_else(|| Error::invalid_params(format!("No service is mounted for '{name}'")))
+    }
+
+    /// Prefixes a name listed by the service mounted under a prefix, or returns [`None`] if the
+    /// prefixed name would be routed to another service, such as `a_b_c` listed by `a` as `b_c`
+    /// when `a_b` is also mounted
+    fn reachable(&self, prefix: &str, name: &str) -> Option<String> {
+        let prefixed = prefixed(prefix, name);
+        if self
+            .owner(&prefixed)
+            .is_some_and(|(owner, _, _)| owner == prefix)
+        {
+            return Some(prefixed);
+        }
+        warn!("Leaving out '{prefixed}', since it is routed to another mounted service");
+        None
+    }
+
+    /// Tries each service in order, skipping those that don't have the resource
+    async fn first_found<'a, T>(
+        &'a self,
+        f: impl Fn(&'a dyn DynService) -> BoxFuture<'a, Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let mut not_found = Error::resource_not_found("No service is mounted");
+        for (_, service) in &self.services {
+            match f(service.as_ref()).await {
+                Err(e) if e.code == ErrorCode::ResourceNotFound => not_found = e,
+                result => return result,
+            }
+        }
+        Err(not_found)
+    }
+}
+
+fn prefixed(prefix: &str, name: &str) -> String {
+    format!("{prefix}{SEPARATOR}{name}")
+}
+
+/// A page of a listing, which is continued by requesting its cursor
+trait Page {
+    fn take_cursor(&mut self) -> Option<String>;
+    fn append(&mut self, page: Self);
+}
+
+macro_rules! impl_page {
+    ($result:ty, $items:ident) => {
+        impl Page for $result {
+            fn take_cursor(&mut self) -> Option<String> {
+                self.next_cursor.take()
+            }
+
+            fn append(&mut self, page: Self) {
+                self.$items.extend(page.$items);
+            }
+        }
+    };
+}
+
+impl_page!(mcp_schema::ListToolsResult, tools);
+impl_page!(mcp_schema::ListPromptsResult, prompts);
+impl_page!(mcp_schema::ListResourcesResult, resources);
+impl_page!(mcp_schema::ListResourceTemplatesResult, resource_templates);
+
+/// The request for the page at a cursor, keeping the other parameters of the request
+fn at_cursor(
+    request: &mcp_schema::PaginatedParams,
+    cursor: Option<String>,
+) -> Result<mcp_schema::PaginatedParams, Error> {
+    let mut params = match serde_json::to_value(request)? {
+        serde_json::Value::Object(params) => params,
+        _ => serde_json::Map::new(),
+    };
+    match cursor {
+        Some(cursor) => params.insert("cursor".to_string(), cursor.into()),
+        None => params.remove("cursor"),
+    };
+    Ok(serde_json::from_value(serde_json::Value::Object(params))?)
+}
+
+/// Lists every page of a service. The router lists everything at once, since one cursor can't
+/// continue the listings of several services.
+async fn every_page<'a, T: Page>(
+    request: &mcp_schema::PaginatedParams,
+    list: impl Fn(mcp_schema::PaginatedParams) -> BoxFuture<'a, Result<T, Error>>,
+) -> Result<T, Error> {
+    let mut listed = list(at_cursor(request, None)?).await?;
+    let mut cursor = listed.take_cursor();
+    for _ in 1..MAX_PAGES {
+        let Some(next) = cursor else {
+            break;
+        };
+        let mut page = list(at_cursor(request, Some(next))?).await?;
+        cursor = page.take_cursor();
+        listed.append(page);
+    }
+    Ok(listed)
+}
+
+const fn empty_result() -> mcp_schema::EmptyResult {
+    mcp_schema::EmptyResult {
+        meta: None,
+        extra: HashMap::new(),
+    }
+}
+
+/// Combines the capabilities of every service, keeping the first one declared of each kind
+fn merge_capabilities(
+    merged: &mut mcp_schema::ServerCapabilities,
+    capabilities: mcp_schema::ServerCapabilities,
+) {
+    merged.experimental = merged.experimental.take().or(capabilities.experimental);
+    merged.logging = merged.logging.take().or(capabilities.logging);
+    merged.prompts = merged.prompts.take().or(capabilities.prompts);
+    merged.resources = merged.resources.take().or(capabilities.resources);
+    merged.tools = merged.tools.take().or(capabilities.tools);
+    for (key, value) in capabilities.extra {
+        merged.extra.entry(key).or_insert(value);
+    }
+}
+
+impl Service for ServiceRouter {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    ) {
+        let handler: NotificationHandler = handler.into();
+        for (_, service) in &mut self.services {
+            let handler = handler.clone();
+            service.set_notification_handler(Box::new(move |notification| handler(notification)));
+        }
+    }
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> impl Future<Output = Result<mcp_schema::InitializeResult, Error>> + Send {
+        async move {
+            let results = try_join_all(
+                self.services
+                    .iter()
+                    .map(|(_, service)| service.init(request.clone())),
+            )
+            .await?;
+
+            let mut capabilities = mcp_schema::ServerCapabilities {
+                experimental: None,
+                logging: None,
+                prompts: None,
+                resources: None,
+                tools: None,
+                extra: HashMap::new(),
+            };
+            let mut instructions = Vec::new();
+            for ((prefix, _), result) in self.services.iter().zip(results) {
+                merge_capabilities(&mut capabilities, result.capabilities);
+                if let Some(text) = result.instructions {
+                    instructions.push(format!("{prefix}: {text}"));
+                }
+            }
+
+            Ok(mcp_schema::InitializeResult {
+                capabilities,
+                instructions: self
+                    .instructions
+                    .clone()
+                    .or_else(|| (!instructions.is_empty()).then(|| instructions.join("\n\n"))),
+                meta: None,
+                protocol_version: mcp_schema::LATEST_PROTOCOL_VERSION.to_string(),
+                server_info: mcp_schema::Implementation {
+                    name: self.name.clone(),
+                    version: self.version.clone(),
+                    extra: HashMap::new(),
+                },
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn ping(
+        &self,
+        _: mcp_schema::PingParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move { Ok(empty_result()) }
+    }
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourcesResult, Error>> + Send {
+        async move {
+            let results = try_join_all(self.services.iter().map(|(_, service)| {
+                every_page(&request, |request| service.list_resources(request))
+            }))
+            .await?;
+
+            Ok(mcp_schema::ListResourcesResult {
+                meta: None,
+                next_cursor: None,
+                resources: results
+                    .into_iter()
+                    .flat_map(|result| result.resources)
+                    .collect(),
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourceTemplatesResult, Error>> + Send {
+        async move {
+            let results = try_join_all(self.services.iter().map(|(_, service)| {
+                every_page(&request, |request| service.list_resource_templates(request))
+            }))
+            .await?;
+
+            Ok(mcp_schema::ListResourceTemplatesResult {
+                meta: None,
+                next_cursor: None,
+                resource_templates: results
+                    .into_iter()
+                    .flat_map(|result| result.resource_templates)
+                    .collect(),
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + Send {
+        self.first_found(move |service| service.read_resource(request.clone()))
+    }
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.first_found(move |service| service.subscribe(request.clone()))
+    }
+
+    /// Unsubscribes from every service, since only the one that has the resource is subscribed
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move {
+            try_join_all(
+                self.services
+                    .iter()
+                    .map(|(_, service)| service.unsubscribe(request.clone())),
+            )
+            .await?;
+            Ok(empty_result())
+        }
+    }
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListPromptsResult, Error>> + Send {
+        async move {
+            let results =
+                try_join_all(self.services.iter().map(|(_, service)| {
+                    every_page(&request, |request| service.list_prompts(request))
+                }))
+                .await?;
+
+            Ok(mcp_schema::ListPromptsResult {
+                meta: None,
+                next_cursor: None,
+                prompts: self
+                    .services
+                    .iter()
+                    .zip(results)
+                    .flat_map(|((prefix, _), result)| {
+                        result.prompts.into_iter().filter_map(|mut prompt| {
+                            prompt.name = self.reachable(prefix, &prompt.name)?;
+                            Some(prompt)
+                        })
+                    })
+                    .collect(),
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn get_prompt(
+        &self,
+        mut request: mcp_schema::GetPromptParams,
+    ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + Send {
+        let service = self.route(&request.name).map(|(service, name)| {
+            let name = name.to_string();
+            (service, name)
+        });
+        async move {
+            let (service, name) = service?;
+            request.name = name;
+            service.get_prompt(request).await
+        }
+    }
+
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListToolsResult, Error>> + Send {
+        async move {
+            let results =
+                try_join_all(self.services.iter().map(|(_, service)| {
+                    every_page(&request, |request| service.list_tools(request))
+                }))
+                .await?;
+
+            Ok(mcp_schema::ListToolsResult {
+                meta: None,
+                next_cursor: None,
+                tools: self
+                    .services
+                    .iter()
+                    .zip(results)
+                    .flat_map(|((prefix, _), result)| {
+                        result.tools.into_iter().filter_map(|mut tool| {
+                            tool.name = self.reachable(prefix, &tool.name)?;
+                            Some(tool)
+                        })
+                    })
+                    .collect(),
+                extra: HashMap::new(),
+            })
+        }
+    }
+
+    fn call_tool(
+        &self,
+        mut request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send {
+        let service = self.route(&request.name).map(|(service, name)| {
+            let name = name.to_string();
+            (service, name)
+        });
+        async move {
+            let (service, name) = service?;
+            request.name = name;
+            service.call_tool(request).await
+        }
+    }
+
+    fn set_level(
+        &self,
+        request: mcp_schema::SetLevelParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        async move {
+            try_join_all(
+                self.services
+                    .iter()
+                    .map(|(_, service)| service.set_level(request.clone())),
+            )
+            .await?;
+            Ok(empty_result())
+        }
+    }
+
+    /// Completes prompt arguments in the service owning the prompt, and resource template
+    /// arguments in the first service that has the template
+    fn complete(
+        &self,
+        request: mcp_schema::CompleteParams,
+    ) -> impl Future<Output = Result<mcp_schema::CompleteResult, Error>> + Send {
+        let prompt = crate::completion::parse(&request).and_then(|completion| {
+            let Reference::Prompt(name) = completion.reference else {
+                return Ok(None);
+            };
+            let (service, name) = self.route(&name)?;
+            Ok(Some((
+                service,
+                crate::completion::rename_prompt(request.clone(), name)?,
+            )))
+        });
+        async move {
+            match prompt? {
+                Some((service, request)) => service.complete(request).await,
+                None => {
+                    self.first_found(move |service| service.complete(request.clone()))
+                        .await
+                }
+            }
+        }
+    }
+
+    fn active_subscriptions(&self) -> usize {
+        self.services
+            .iter()
+            .map(|(_, service)| service.active_subscriptions())
+            .sum()
+    }
+
+    /// Runs the middleware of every service, in the order they were mounted
+    fn intercept(&self, message: &mut ClientMessage) -> Result<(), Error> {
+        self.services
+            .iter()
+            .try_for_each(|(_, service)| service.intercept(message))
+    }
+
+    fn shutdown(&self) -> impl Future<Output = ()> + Send {
+        async move {
+            futures::future::join_all(self.services.iter().map(|(_, service)| service.shutdown()))
+                .await;
+        }
+    }
+}
@@ -0,0 +1,1438 @@
+use crate::context::RequestContext;
+use crate::logging::LogSink;
+use crate::options::Overflow;
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::subscriptions::{DEFAULT_MAX_SUBSCRIPTIONS, Subscriptions};
+use crate::wire::Wire;
+use crate::{Error, ErrorCode, Service};
+use axum::{
+    Json, Router,
+    extract::{ConnectInfo, DefaultBodyLimit, OriginalUri, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::{Next, from_fn_with_state},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post},
+};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{Arc, Mutex, Once, OnceLock},
+    time::{Duration, Instant},
+};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio_stream::StreamExt;
+use tracing::{Instrument, debug, error, info, warn};
+
+/// Number of notifications kept for clients that poll instead of holding an SSE connection
+const OUTBOX_CAPACITY: usize = 1000;
+
+/// Default largest message body accepted over HTTP
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Default number of messages buffered for each connection, see [`McpImpl::with_capacity`]
+const DEFAULT_CHANNEL_CAPACITY: usize = 100;
+
+/// Default interval of keepalive comments on idle SSE connections
+pub(crate) const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// In-flight requests are keyed by session too, since clients pick their request ids
+/// independently
+type CancelKey = (Option<String>, RequestId);
+
+type Sessions = Arc<Mutex<HashMap<String, mpsc::Sender<ServerResponse>>>>;
+
+/// The minimum severity of log messages each session asked for, with `None` for the connection
+/// of transports without sessions, such as stdio
+type LogLevels = Arc<Mutex<HashMap<Option<String>, usize>>>;
+
+/// The capabilities each session's client declared when it initialized
+type Clients = Arc<Mutex<HashMap<Option<String>, Arc<mcp_schema::ClientCapabilities>>>>;
+
+type RequestHook = Arc<dyn Fn(&mut mcp_schema::ClientRequest) + Send + Sync>;
+type ResponseHook =
+    Arc<dyn Fn(&mcp_schema::ClientRequest, &ServerResponse, Duration) + Send + Sync>;
+
+pub struct McpImpl<S> {
+    /// Messages for every connection, with the outbox number of notifications
+    tx: broadcast::Sender<(Option<u64>, ServerResponse)>,
+    cancel: Mutex<HashMap<CancelKey, oneshot::Sender<()>>>,
+    outbox: Arc<Mutex<Outbox>>,
+    sessions: Sessions,
+    log_levels: LogLevels,
+    clients: Clients,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    on_request: Vec<RequestHook>,
+    on_response: Vec<ResponseHook>,
+    rate_limiter: Option<RateLimiter>,
+    max_message_size: usize,
+    error_details: bool,
+    capacity: usize,
+    keep_alive: Option<Duration>,
+    overflow: Overflow,
+    #[cfg(feature = "metrics")]
+    metrics: crate::metrics::Metrics,
+    service: S,
+}
+
+/// Identifies the SSE session a message was posted for. The endpoint event of each SSE
+/// connection tells the client which `sessionId` to post with.
+#[derive(Default, Deserialize)]
+pub struct SessionQuery {
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
+}
+
+/// Removes a session when its SSE stream is dropped
+struct SessionGuard {
+    sessions: Sessions,
+    log_levels: LogLevels,
+    clients: Clients,
+    id: String,
+    /// Ends the session's resource subscriptions
+    on_close: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.sessions.lock().unwrap().remove(&self.id);
+        self.log_levels
+            .lock()
+            .unwrap()
+            .remove(&Some(self.id.clone()));
+        self.clients.lock().unwrap().remove(&Some(self.id.clone()));
+        if let Some(on_close) = self.on_close.take() {
+            on_close();
+        }
+    }
+}
+
+/// The most recent notifications, numbered in the order they were sent
+#[derive(Default)]
+struct Outbox {
+    next: u64,
+    notifications: VecDeque<(u64, mcp_schema::ServerNotification)>,
+    /// Numbers of notifications sent while no connection was open, oldest first
+    undelivered: VecDeque<u64>,
+    /// Number of undelivered notifications kept for the next connection, or zero to disable
+    /// replaying
+    replay: usize,
+}
+
+impl Outbox {
+    fn push(&mut self, notification: mcp_schema::ServerNotification) -> u64 {
+        if self.notifications.len() == OUTBOX_CAPACITY {
+            self.notifications.pop_front();
+        }
+        let number = self.next;
+        self.notifications.push_back((number, notification));
+        self.next += 1;
+        number
+    }
+
+    fn undelivered(&mut self, number: u64) {
+        if self.replay == 0 {
+            return;
+        }
+        if self.undelivered.len() == self.replay {
+            self.undelivered.pop_front();
+        }
+        self.undelivered.push_back(number);
+    }
+
+    /// Takes the notifications a new connection missed: those after the last event the client
+    /// received if it is reconnecting, or else those no connection received
+    fn replay(&mut self, last_event_id: Option<u64>) -> Vec<(u64, mcp_schema::ServerNotification)> {
+        if self.replay == 0 {
+            return Vec::new();
+        }
+        let undelivered = std::mem::take(&mut self.undelivered);
+        self.notifications
+            .iter()
+            .filter(|(number, _)| match last_event_id {
+                Some(last) => *number > last,
+                None => undelivered.contains(number),
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PollQuery {
+    /// The cursor returned by the previous poll. Without it, polling starts from now.
+    cursor: Option<u64>,
+    /// The SSE session polling, which must still be open
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PollResponse {
+    notifications: Vec<mcp_schema::ServerNotification>,
+    /// Pass this to the next poll to receive the notifications sent after this one
+    cursor: u64,
+    /// Whether notifications since the given cursor were dropped because the client polled too
+    /// rarely
+    missed: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ClientMessage {
+    Request(mcp_schema::ClientRequest),
+    Notification(mcp_schema::ClientNotification),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum ServerResponse {
+    Response(mcp_schema::JSONRPCResponse<mcp_schema::ServerResult>),
+    Notification(mcp_schema::ServerNotification),
+    Error(mcp_schema::JSONRPCError),
+    None,
+}
+
+#[derive(Debug, Clone)]
+struct RequestId(mcp_schema::RequestId);
+
+impl PartialEq for RequestId {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (mcp_schema::RequestId::String(x), mcp_schema::RequestId::String(y)) => x == y,
+            (mcp_schema::RequestId::Number(x), mcp_schema::RequestId::Number(y)) => x == y,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RequestId {}
+
+impl Hash for RequestId {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        match &self.0 {
+            mcp_schema::RequestId::String(x) => x.hash(state),
+            mcp_schema::RequestId::Number(x) => x.hash(state),
+        }
+    }
+}
+
+impl<S: Service + Send + Sync + 'static> McpImpl<S> {
+    #[must_use]
+    pub fn new(service: S) -> Self {
+        Self::with_capacity(service, DEFAULT_CHANNEL_CAPACITY)
+    }
+
+    /// Creates the server with room for the given number of messages per connection, which is
+    /// 100 by default. Notifications are buffered until every connection has sent them, and
+    /// responses until the session they belong to has. A session whose buffer is full loses
+    /// further responses, while connections that fall behind on notifications are handled as
+    /// set with [`Self::on_overflow`].
+    ///
+    /// # Panics
+    /// If the capacity is zero, this will panic.
+    #[must_use]
+    pub fn with_capacity(mut service: S, capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+
+        let tx_clone = tx.clone();
+        let outbox = Arc::new(Mutex::new(Outbox::default()));
+        let outbox_clone = outbox.clone();
+        service.set_notification_handler(Box::new(move |notification| {
+            // The outbox stays locked while broadcasting, so a connection that replays it and then
+            // subscribes receives every notification exactly once
+            let mut outbox = outbox_clone.lock().unwrap();
+            let number = outbox.push(notification.clone());
+            if let Err(e) =
+                tx_clone.send((Some(number), ServerResponse::Notification(notification)))
+            {
+                outbox.undelivered(number);
+                warn!("Failed to broadcast response: {}", e);
+            } else {
+                debug!("Successfully broadcast response");
+            }
+        }));
+        Self {
+            tx,
+            cancel: Mutex::new(HashMap::new()),
+            outbox,
+            sessions: Arc::default(),
+            log_levels: Arc::default(),
+            clients: Arc::default(),
+            subscriptions: Arc::new(Mutex::new(Subscriptions::new(DEFAULT_MAX_SUBSCRIPTIONS))),
+            on_request: Vec::new(),
+            on_response: Vec::new(),
+            rate_limiter: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            error_details: false,
+            capacity,
+            keep_alive: Some(DEFAULT_KEEP_ALIVE),
+            overflow: Overflow::default(),
+            #[cfg(feature = "metrics")]
+            metrics: crate::metrics::Metrics::new(),
+            service,
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub const fn metrics(&self) -> &crate::metrics::Metrics {
+        &self.metrics
+    }
+
+    /// Serves the metrics in the Prometheus text format at `/metrics`
+    #[cfg(feature = "metrics")]
+    pub fn metrics_router<T>(self: Arc<Self>) -> Router<T> {
+        Router::new()
+            .route(
+                "/metrics",
+                get(|State(state): State<Arc<Self>>| async move {
+                    state
+                        .metrics
+                        .render(state.session_count(), state.service.active_subscriptions())
+                }),
+            )
+            .with_state(self)
+    }
+
+    /// Adds a hook that runs on every request before it is handled, after message middleware. It
+    /// may rewrite the request.
+    #[must_use]
+    pub fn on_request(
+        mut self,
+        hook: impl Fn(&mut mcp_schema::ClientRequest) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_request.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a hook that runs on the response to every request along with the time it took to
+    /// handle, such as for audit logs or custom metrics. Cancelled requests have a response of
+    /// [`ServerResponse::None`].
+    #[must_use]
+    pub fn on_response(
+        mut self,
+        hook: impl Fn(&mcp_schema::ClientRequest, &ServerResponse, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_response.push(Arc::new(hook));
+        self
+    }
+
+    /// Limits the messages each client may post over HTTP. Clients are identified by their SSE
+    /// session, or by their IP address, which requires serving with
+    /// `into_make_service_with_connect_info::<SocketAddr>()`. Messages without a session are not
+    /// limited without connect info. Messages over the limit are answered with an error whose
+    /// data has the `retryAfterMs` to wait.
+    #[must_use]
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(limit));
+        self
+    }
+
+    /// Sets the largest message body accepted over HTTP, which is 4 MiB by default. Larger
+    /// messages are rejected with an `Invalid Request` error without being parsed. Byte stream
+    /// transports also apply it to frames, closing the stream when one is larger.
+    #[must_use]
+    pub const fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+
+    /// Sets the interval of keepalive comments sent on idle SSE connections, which stop proxies
+    /// from closing them, or disables them with `None`. This is 15 seconds by default.
+    #[must_use]
+    pub const fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+
+    /// Keeps up to the given number of notifications that were sent while no SSE connection was
+    /// open, such as resource updates before a client connects, and sends them to the next
+    /// connection. Reconnecting clients that send `Last-Event-ID` also receive the notifications
+    /// they missed, as long as they are among the last 1000. This is disabled by default.
+    #[must_use]
+    pub fn replay_notifications(self, capacity: usize) -> Self {
+        self.outbox.lock().unwrap().replay = capacity;
+        self
+    }
+
+    /// Sets the most resources each session may be subscribed to, which is 100 by default.
+    /// Subscriptions over the limit are rejected, and a session's subscriptions end when it
+    /// closes.
+    #[must_use]
+    pub fn max_subscriptions(self, limit: usize) -> Self {
+        self.subscriptions.lock().unwrap().set_limit(limit);
+        self
+    }
+
+    /// Sends the causes of errors converted from `eyre::Report` to clients, as a `chain` of
+    /// messages in the error data, along with the `report` rendered by the eyre handler, which
+    /// includes any backtrace or span trace it captured. Tool failures get the report as extra
+    /// content instead. This is disabled by default, since the causes may reveal details of the
+    /// server.
+    #[must_use]
+    pub const fn error_details(mut self, enabled: bool) -> Self {
+        self.error_details = enabled;
+        self
+    }
+
+    /// Sets what happens to SSE connections that fall behind on notifications
+    #[must_use]
+    pub const fn on_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Rejects message bodies over the size limit while reading them, before they are parsed
+    async fn limit_size(State(state): State<Arc<Self>>, request: Request, next: Next) -> Response {
+        let limit = state.max_message_size;
+        let (parts, body) = request.into_parts();
+        match axum::body::to_bytes(body, limit).await {
+            Ok(body) => next.run(Request::from_parts(parts, body.into())).await,
+            Err(e) => {
+                warn!("Rejected message body: {e}");
+                // The id can't be known without parsing the message
+                Json(serde_json::json!({
+                    "jsonrpc": mcp_schema::JSONRPC_VERSION,
+                    "id": null,
+                    "error": {
+                        "code": ErrorCode::InvalidRequest.code(),
+                        "message": format!("Message exceeds the limit of {limit} bytes"),
+                    },
+                }))
+                .into_response()
+            }
+        }
+    }
+
+    /// Rejects messages over the rate limit before they are decoded and handled
+    async fn limit_rate(State(state): State<Arc<Self>>, request: Request, next: Next) -> Response {
+        let Some(limiter) = &state.rate_limiter else {
+            return next.run(request).await;
+        };
+
+        let session_id = Query::<SessionQuery>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(query)| query.session_id);
+        let client = session_id.clone().or_else(|| {
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip().to_string())
+        });
+        // Sharing one bucket between every unidentified client would let one of them starve the
+        // rest, so they aren't limited at all
+        let Some(client) = client else {
+            static WARNED: Once = Once::new();
+            WARNED.call_once(|| {
+                warn!(
+                    "Not rate limiting messages without a session, serve with connect info to \
+                     limit them by IP address"
+                );
+            });
+            return next.run(request).await;
+        };
+        let Err(retry_after) = limiter.check(&client) else {
+            return next.run(request).await;
+        };
+
+        warn!("Rate limited client {client} for {retry_after:?}");
+        let body = axum::body::to_bytes(request.into_body(), state.max_message_size)
+            .await
+            .unwrap_or_default();
+        let id = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|message| serde_json::from_value(message.get("id")?.clone()).ok());
+        // Notifications have no id to answer with
+        let Some(id) = id else {
+            return Json(ServerResponse::None).into_response();
+        };
+
+        let retry_after_ms = u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX);
+        let response = error_response(
+            id,
+            Error::custom(
+                -32000,
+                format!("Rate limit exceeded, retry after {retry_after_ms}ms"),
+            )
+            .with_data(serde_json::json!({ "retryAfterMs": retry_after_ms })),
+        );
+        state.respond(session_id.as_deref(), response.clone());
+        (
+            [(
+                header::RETRY_AFTER,
+                retry_after.as_secs().max(1).to_string(),
+            )],
+            Json(response),
+        )
+            .into_response()
+    }
+
+    fn run_response_hooks(
+        &self,
+        request: &mcp_schema::ClientRequest,
+        response: &ServerResponse,
+        started: Instant,
+    ) {
+        let elapsed = started.elapsed();
+        for hook in &self.on_response {
+            hook(request, response, elapsed);
+        }
+    }
+
+    /// Number of open SSE sessions
+    #[doc(hidden)]
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Creates a session with 128 random bits as its id, which other clients can't guess
+    fn open_session(&self) -> (SessionGuard, mpsc::Receiver<ServerResponse>) {
+        let mut random = [0; 16];
+        getrandom::fill(&mut random).expect("the system random number generator failed");
+        let id = format!("{:032x}", u128::from_be_bytes(random));
+
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        self.sessions.lock().unwrap().insert(id.clone(), sender);
+        let guard = SessionGuard {
+            sessions: self.sessions.clone(),
+            log_levels: self.log_levels.clone(),
+            clients: self.clients.clone(),
+            id,
+            on_close: None,
+        };
+        (guard, receiver)
+    }
+
+    /// Whether a broadcast message should be sent to a session, which is decided by the level of
+    /// log messages the session asked for and the resources it is subscribed to
+    fn delivers(&self, session_id: Option<&str>, message: &ServerResponse) -> bool {
+        let ServerResponse::Notification(notification) = message else {
+            return true;
+        };
+        let Ok(notification) = serde_json::to_value(notification) else {
+            return true;
+        };
+        match notification
+            .get("method")
+            .and_then(serde_json::Value::as_str)
+        {
+            Some("notifications/message") => {
+                let minimum = self
+                    .log_levels
+                    .lock()
+                    .unwrap()
+                    .get(&session_id.map(str::to_string))
+                    .copied();
+                crate::logging::allows(minimum, &notification)
+            }
+            Some("notifications/resources/updated") => notification
+                .pointer("/params/uri")
+                .and_then(serde_json::Value::as_str)
+                .is_none_or(|uri| {
+                    self.subscriptions
+                        .lock()
+                        .unwrap()
+                        .is_subscribed(session_id, uri)
+                }),
+            _ => true,
+        }
+    }
+
+    /// Creates the context handlers of a request can see, and the sender that cancels it
+    fn context(
+        &self,
+        session_id: Option<String>,
+        request: &mcp_schema::ClientRequest,
+    ) -> (watch::Sender<bool>, RequestContext) {
+        let (sender, cancelled) = watch::channel(false);
+        let progress_token = serde_json::to_value(request)
+            .ok()
+            .and_then(|request| request.pointer("/params/_meta/progressToken").cloned());
+        let context = RequestContext::new(crate::context::Inner {
+            peer: crate::inject::Scope::current()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address),
+            client_capabilities: self.clients.lock().unwrap().get(&session_id).cloned(),
+            sink: self.session_sink(session_id.clone()),
+            session_id,
+            request_id: serde_json::to_value(request_id(request)).ok(),
+            progress_token,
+            cancelled,
+            error_details: self.error_details,
+        });
+        (sender, context)
+    }
+
+    /// Sends notifications about a request only to the session that made it, or to every
+    /// connection for requests without a session, such as over stdio. Log messages below the
+    /// level the session set are dropped.
+    fn session_sink(&self, session_id: Option<String>) -> LogSink {
+        let sessions = self.sessions.clone();
+        let log_levels = self.log_levels.clone();
+        let tx = self.tx.clone();
+        let send = move |notification: mcp_schema::ServerNotification| {
+            let minimum = log_levels.lock().unwrap().get(&session_id).copied();
+            let allowed = serde_json::to_value(&notification)
+                .is_ok_and(|notification| crate::logging::allows(minimum, &notification));
+            if !allowed {
+                return;
+            }
+            let response = ServerResponse::Notification(notification);
+            match &session_id {
+                Some(id) => {
+                    if let Some(session) = sessions.lock().unwrap().get(id) {
+                        let _ = session.try_send(response);
+                    }
+                }
+                None => {
+                    let _ = tx.send((None, response));
+                }
+            }
+        };
+        Arc::new(OnceLock::from(
+            Arc::new(send) as Arc<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>
+        ))
+    }
+
+    /// Handles a request, asking the service to watch a resource only when its first session
+    /// subscribes and to stop when its last session unsubscribes
+    async fn dispatch(
+        &self,
+        session_id: Option<String>,
+        request: mcp_schema::ClientRequest,
+    ) -> Result<mcp_schema::JSONRPCResponse<mcp_schema::ServerResult>, Error> {
+        match request {
+            mcp_schema::ClientRequest::Subscribe {
+                json_rpc,
+                id,
+                params,
+            } => {
+                let uri = params.uri.clone();
+                let first = self
+                    .subscriptions
+                    .lock()
+                    .unwrap()
+                    .subscribe(session_id.clone(), &uri)?;
+                if !first {
+                    return empty_response(json_rpc, id);
+                }
+                let response = handle_request(
+                    &self.service,
+                    mcp_schema::ClientRequest::Subscribe {
+                        json_rpc,
+                        id,
+                        params,
+                    },
+                )
+                .await;
+                if response.is_err() {
+                    self.subscriptions
+                        .lock()
+                        .unwrap()
+                        .unsubscribe(&session_id, &uri);
+                }
+                response
+            }
+            mcp_schema::ClientRequest::Unsubscribe {
+                json_rpc,
+                id,
+                params,
+            } => {
+                let last = self
+                    .subscriptions
+                    .lock()
+                    .unwrap()
+                    .unsubscribe(&session_id, &params.uri);
+                if !last {
+                    return empty_response(json_rpc, id);
+                }
+                handle_request(
+                    &self.service,
+                    mcp_schema::ClientRequest::Unsubscribe {
+                        json_rpc,
+                        id,
+                        params,
+                    },
+                )
+                .await
+            }
+            request => handle_request(&self.service, request).await,
+        }
+    }
+
+    /// Ends the subscriptions of a closed session, unsubscribing the service from resources no
+    /// other session is subscribed to
+    async fn close_subscriptions(&self, session_id: Option<String>) {
+        let unwatched = self.subscriptions.lock().unwrap().close(&session_id);
+        for uri in unwatched {
+            let params = serde_json::from_value(serde_json::json!({ "uri": uri }));
+            let result = match params {
+                Ok(params) => self.service.unsubscribe(params).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                warn!("Failed to unsubscribe from {uri}: {}", e.message);
+            }
+        }
+    }
+
+    /// Sends a response to the session that issued the request. Responses to requests made
+    /// without a session are only returned from the request that produced them, so this drops
+    /// them.
+    fn respond(&self, session_id: Option<&str>, response: ServerResponse) {
+        let Some(session_id) = session_id else {
+            return;
+        };
+
+        let session = self.sessions.lock().unwrap().get(session_id).cloned();
+        match session {
+            Some(session) => {
+                if let Err(e) = session.try_send(response) {
+                    warn!("Failed to send response to session {session_id}: {e}");
+                }
+            }
+            None => warn!("Dropping response for unknown session {session_id}"),
+        }
+    }
+
+    pub const fn service(&self) -> &S {
+        &self.service
+    }
+
+    /// Shuts down the service
+    pub async fn shutdown(&self) {
+        info!("Shutting down");
+        self.service.shutdown().await;
+    }
+
+    /// Number of requests that are currently in flight and can be cancelled
+    #[doc(hidden)]
+    pub fn pending_requests(&self) -> usize {
+        self.cancel.lock().unwrap().len()
+    }
+
+    /// Number of live receivers of the outgoing message channel
+    #[doc(hidden)]
+    pub fn receiver_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// The MCP endpoints as a router, for nesting into an existing application with its own
+    /// middleware, such as with `app.nest("/mcp", mcp.into_router())`. Clients connect to
+    /// `events` for SSE, post to `message` and may poll `poll` for notifications.
+    pub fn into_router<T>(self: Arc<Self>) -> Router<T> {
+        let router = Router::new()
+            .route(
+                "/message",
+                post(Self::message_handler)
+                    // The size limit is enforced with a JSON-RPC error instead
+                    .layer(DefaultBodyLimit::disable())
+                    .layer(from_fn_with_state(self.clone(), Self::limit_size))
+                    .layer(from_fn_with_state(self.clone(), Self::limit_rate))
+                    .layer(axum::middleware::from_fn(provide_peer)),
+            )
+            .route("/events", get(Self::sse_handler))
+            .route("/poll", get(Self::poll_handler));
+        #[cfg(feature = "otel")]
+        let router = router.layer(axum::middleware::from_fn(
+            crate::otel::propagate_http_context,
+        ));
+        router.with_state(self)
+    }
+
+    /// # Errors
+    /// An error will occur if an I/O error occurs in stdio or stdin.
+    pub async fn serve_over_stdio(self: Arc<Self>) -> std::io::Result<()> {
+        self.serve_over(tokio::io::stdin(), tokio::io::stdout())
+            .await
+    }
+
+    /// Serves over a byte stream, starting in JSON and switching to the
+    /// [`crate::wire::Encoding`] and [`crate::wire::Compression`] negotiated during initialization.
+    /// This returns once the input has ended and every request read from it has been answered.
+    ///
+    /// # Errors
+    /// An error will occur if an I/O error occurs in the input or output.
+    pub async fn serve_over(
+        self: Arc<Self>,
+        input: impl AsyncRead + Unpin,
+        mut output: impl AsyncWrite + Unpin,
+    ) -> std::io::Result<()> {
+        let wire = Arc::new(Mutex::new(Wire::default()));
+        let mut frames = Box::pin(crate::wire::read_frames(
+            input,
+            wire.clone(),
+            self.max_message_size,
+        ));
+        let mut rx = self.tx.subscribe();
+        // Responses are returned by each request's handler rather than broadcast
+        let (responder, mut responses) = mpsc::unbounded_channel();
+        // Dropped at the end of the input, so the responses end with the last handler
+        let mut responder = Some(responder);
+        let mut read_enabled = true;
+        // The id of an initialize request asking to switch wire formats, and the format to switch
+        // to. Reading pauses until the response is written, since the next frame may already be
+        // in the new format.
+        let mut negotiating: Option<(serde_json::Value, Wire)> = None;
+
+        loop {
+            tokio::select! {
+                frame = frames.next(), if read_enabled && negotiating.is_none() => {
+                    let Some(frame) = frame else {
+                        // The end of the input was reached
+                        read_enabled = false;
+                        responder = None;
+                        continue;
+                    };
+                    let (frame_wire, frame) = frame?;
+
+                    match frame_wire.decode(&frame) {
+                        Ok(msg) => {
+                            if let ClientMessage::Request(mcp_schema::ClientRequest::Initialize {
+                                id, params, ..
+                            }) = &msg
+                            {
+                                let requested = Wire::negotiate(params);
+                                if requested != frame_wire {
+                                    negotiating = serde_json::to_value(id)
+                                        .ok()
+                                        .map(|id| (id, requested));
+                                }
+                            }
+                            let handling = Self::message_handler(
+                                State(self.clone()),
+                                Query(SessionQuery::default()),
+                                Json(msg),
+                            );
+                            let responder = responder.clone();
+                            tokio::spawn(async move {
+                                let Json(response) = handling.await;
+                                if !matches!(response, ServerResponse::None) {
+                                    if let Some(responder) = responder {
+                                        let _ = responder.send(response);
+                                    }
+                                }
+                            });
+                        },
+                        Err(e) => {
+                            warn!("Error deserializing message: {}", e);
+                        }
+                    }
+                },
+                msg = responses.recv() => {
+                    let Some(msg) = msg else {
+                        return Ok(());
+                    };
+                    write_message(&mut output, &wire, &mut negotiating, &msg).await?;
+                },
+                msg = rx.recv() => {
+                    match msg {
+                        Ok((_, msg)) if !self.delivers(None, &msg) => {}
+                        Ok((_, msg)) => {
+                            write_message(&mut output, &wire, &mut negotiating, &msg).await?;
+                        },
+                        Err(e) => {
+                            warn!("Error receiving message: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Opens an SSE session. Responses to requests posted with this session's id are only sent
+    /// here, while notifications are sent to every session.
+    #[allow(clippy::unused_async)]
+    pub async fn sse_handler(
+        State(state): State<Arc<Self>>,
+        OriginalUri(uri): OriginalUri,
+        headers: HeaderMap,
+    ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let last_event_id = headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok());
+        let (rx, replay) = {
+            let mut outbox = state.outbox.lock().unwrap();
+            (state.tx.subscribe(), outbox.replay(last_event_id))
+        };
+        let (mut session, responses) = state.open_session();
+        let closing = Arc::downgrade(&state);
+        let closed = Some(session.id.clone());
+        session.on_close = Some(Box::new(move || {
+            let Some(state) = closing.upgrade() else {
+                return;
+            };
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move { state.close_subscriptions(closed).await });
+            }
+        }));
+        info!("New SSE connection established with session {}", session.id);
+
+        // Send initial endpoint event as required by MCP spec. The message route is a sibling of
+        // this one, wherever the router was nested.
+        let base = uri.path().strip_suffix("events").unwrap_or("/api/");
+        let endpoint_url = format!("{base}message?sessionId={}", session.id);
+        debug!("Sending initial endpoint URL: {}", endpoint_url);
+
+        let initial =
+            stream::once(async move { Ok(Event::default().event("endpoint").data(endpoint_url)) });
+        let replay = stream::iter(replay).filter_map(|(number, notification)| {
+            notification_event(Some(number), &ServerResponse::Notification(notification)).map(Ok)
+        });
+
+        let overflow = state.overflow;
+        let session_id = session.id.clone();
+        let levels = state.clone();
+        let broadcast = stream::unfold(rx, move |mut rx| {
+            let state = levels.clone();
+            let session_id = session_id.clone();
+            async move {
+                loop {
+                    match rx.recv().await {
+                        Ok((_, msg)) if !state.delivers(Some(&session_id), &msg) => {}
+                        Ok((number, msg)) => {
+                            debug!("Broadcasting message: {:?}", msg);
+                            let event = notification_event(number, &msg)?;
+                            return Some((Ok(event), rx));
+                        }
+                        Err(broadcast::error::RecvError::Lagged(missed))
+                            if overflow == Overflow::Skip =>
+                        {
+                            warn!("SSE connection fell behind and skipped {missed} messages");
+                        }
+                        Err(e) => {
+                            warn!("Error receiving message: {}", e);
+                            return None;
+                        }
+                    }
+                }
+            }
+        });
+
+        let responses = stream::unfold(
+            (responses, session),
+            |(mut responses, session)| async move {
+                let msg = responses.recv().await?;
+                debug!("Sending message to session {}: {:?}", session.id, msg);
+                let event = Event::default().event("message").json_data(msg).ok()?;
+                Some((Ok(event), (responses, session)))
+            },
+        );
+
+        // The connection closes when the notifications end, such as when it fell behind, even
+        // though the session's responses could continue
+        let broadcast = broadcast.map(Some).chain(stream::once(async { None }));
+        let messages = stream::select(broadcast, responses.map(Some))
+            .take_while(Option::is_some)
+            .filter_map(std::convert::identity);
+        let sse = Sse::new(initial.chain(replay).chain(messages));
+        match state.keep_alive {
+            Some(interval) => sse.keep_alive(KeepAlive::new().interval(interval)),
+            None => sse,
+        }
+    }
+
+    /// Returns the notifications sent since the cursor, for clients that can't hold an SSE
+    /// connection open. Responses to requests are returned by [`Self::message_handler`] directly.
+    /// Polls naming a `sessionId` that isn't open are rejected with 404 Not Found.
+    #[allow(clippy::unused_async)]
+    pub async fn poll_handler(
+        State(state): State<Arc<Self>>,
+        Query(query): Query<PollQuery>,
+    ) -> Result<Json<PollResponse>, (StatusCode, &'static str)> {
+        if let Some(session_id) = &query.session_id {
+            if !state.sessions.lock().unwrap().contains_key(session_id) {
+                return Err((StatusCode::NOT_FOUND, "Unknown session"));
+            }
+        }
+
+        let outbox = state.outbox.lock().unwrap();
+        let cursor = query.cursor.unwrap_or(outbox.next).min(outbox.next);
+        let oldest = outbox
+            .notifications
+            .front()
+            .map_or(outbox.next, |(number, _)| *number);
+
+        Ok(Json(PollResponse {
+            notifications: outbox
+                .notifications
+                .iter()
+                .filter(|(number, _)| *number >= cursor)
+                .map(|(_, notification)| notification.clone())
+                .collect(),
+            cursor: outbox.next,
+            missed: cursor < oldest,
+        }))
+    }
+
+    pub async fn message_handler(
+        State(state): State<Arc<Self>>,
+        Query(session): Query<SessionQuery>,
+        Json(mut message): Json<ClientMessage>,
+    ) -> Json<ServerResponse> {
+        debug!("Message details: {:?}", message);
+        let session_id = session.session_id;
+
+        if let Err(error) = state.service.intercept(&mut message) {
+            warn!("Message was rejected by middleware: {}", error.message);
+            let ClientMessage::Request(request) = message else {
+                return Json(ServerResponse::None);
+            };
+            let response = error_response(request_id(&request).clone(), error);
+            state.respond(session_id.as_deref(), response.clone());
+            return Json(response);
+        }
+
+        match message {
+            ClientMessage::Request(mut request) => {
+                for hook in &state.on_request {
+                    hook(&mut request);
+                }
+
+                if let mcp_schema::ClientRequest::Initialize { params, .. } = &request {
+                    state
+                        .clients
+                        .lock()
+                        .unwrap()
+                        .insert(session_id.clone(), Arc::new(params.capabilities.clone()));
+                }
+
+                if let mcp_schema::ClientRequest::SetLevel { params, .. } = &request {
+                    if let Some(severity) = crate::logging::requested_severity(params) {
+                        state
+                            .log_levels
+                            .lock()
+                            .unwrap()
+                            .insert(session_id.clone(), severity);
+                    }
+                }
+
+                let id = (session_id.clone(), RequestId(request_id(&request).clone()));
+                let (cancel_sender, cancel_receiver) = oneshot::channel();
+                state
+                    .cancel
+                    .lock()
+                    .unwrap()
+                    .insert(id.clone(), cancel_sender);
+
+                // Removes the cancellation entry even if this future is dropped before the request
+                // finishes, such as when an HTTP client disconnects mid-request.
+                let _guard = CancelGuard {
+                    cancel: &state.cancel,
+                    id: id.clone(),
+                };
+
+                let started = Instant::now();
+                // Response hooks are given the request, which handling consumes
+                let original = (!state.on_response.is_empty()).then(|| request.clone());
+                #[cfg(feature = "metrics")]
+                let (method, tool) = (
+                    method_name(&request),
+                    match &request {
+                        mcp_schema::ClientRequest::CallTool { params, .. } => {
+                            Some(params.name.clone())
+                        }
+                        _ => None,
+                    },
+                );
+
+                let span = tracing::info_span!(
+                    "mcp.request",
+                    rpc.method = method_name(&request),
+                    rpc.id = ?request_id(&request),
+                );
+                #[cfg(feature = "otel")]
+                crate::otel::set_parent_from_meta(&span, &request);
+
+                let (cancelled, context) = state.context(session_id.clone(), &request);
+                let handling = context.provide(state.dispatch(session_id.clone(), request));
+                let response = tokio::select! {
+                    response = handling.instrument(span) => response,
+                    _ = cancel_receiver => {
+                        cancelled.send_replace(true);
+                        #[cfg(feature = "metrics")]
+                        state.metrics.record_request(method, "cancelled");
+                        if let Some(request) = &original {
+                            state.run_response_hooks(request, &ServerResponse::None, started);
+                        }
+                        return Json(ServerResponse::None);
+                    }
+                };
+
+                #[cfg(feature = "metrics")]
+                {
+                    let outcome = if response.is_ok() { "ok" } else { "error" };
+                    state.metrics.record_request(method, outcome);
+                    if let Some(tool) = tool {
+                        state.metrics.record_tool_call(&tool, started.elapsed());
+                    }
+                }
+
+                let response = match response {
+                    Ok(response) => ServerResponse::Response(response),
+                    Err(error) if state.error_details => {
+                        error_response(id.1.0, error.with_details())
+                    }
+                    Err(error) => error_response(id.1.0, error),
+                };
+                if let Some(request) = &original {
+                    state.run_response_hooks(request, &response, started);
+                }
+
+                state.respond(session_id.as_deref(), response.clone());
+
+                Json(response)
+            }
+            ClientMessage::Notification(notification) => {
+                if let mcp_schema::ClientNotification::Cancelled { params, .. } = notification {
+                    let id = (session_id, RequestId(params.request_id));
+                    if let Some(reason) = params.reason {
+                        warn!("client cancelled client request {id:?} with reason: {reason}");
+                    } else {
+                        warn!("client cancelled client request {id:?} with no reason provided");
+                    }
+                    let sender = state.cancel.lock().unwrap().remove(&id);
+                    if let Some(sender) = sender {
+                        if sender.send(()).is_err() {
+                            error!("cancellation receiver was dropped");
+                        }
+                    } else {
+                        // This may occur if the request finished on the server side and the
+                        // result has not yet been sent to the client. Therefore, this isn't treated as an error.
+                        warn!(
+                            "client attempted to cancel client request {id:?} but it is not in progress - this is likely harmless"
+                        );
+                    }
+                }
+                Json(ServerResponse::None)
+            }
+        }
+    }
+}
+
+/// An SSE event of a broadcast message, with the outbox number of notifications as the event id
+/// so that reconnecting clients can resume after it
+fn notification_event(number: Option<u64>, msg: &ServerResponse) -> Option<Event> {
+    let event = Event::default().event("message").json_data(msg).ok()?;
+    Some(match number {
+        Some(number) => event.id(number.to_string()),
+        None => event,
+    })
+}
+
+fn error_response(id: mcp_schema::RequestId, error: Error) -> ServerResponse {
+    ServerResponse::Error(mcp_schema::JSONRPCError {
+        json_rpc: mcp_schema::JSONRPC_VERSION.to_string(),
+        id,
+        error: mcp_schema::RPCErrorDetail {
+            code: error.code.code(),
+            message: error.message,
+            data: error.data,
+        },
+    })
+}
+
+struct CancelGuard<'a> {
+    cancel: &'a Mutex<HashMap<CancelKey, oneshot::Sender<()>>>,
+    id: CancelKey,
+}
+
+impl Drop for CancelGuard<'_> {
+    fn drop(&mut self) {
+        self.cancel.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// The JSON-RPC method of a request
+const fn method_name(request: &mcp_schema::ClientRequest) -> &'static str {
+    match request {
+        mcp_schema::ClientRequest::Initialize { .. } => "initialize",
+        mcp_schema::ClientRequest::Ping { .. } => "ping",
+        mcp_schema::ClientRequest::ListResources { .. } => "resources/list",
+        mcp_schema::ClientRequest::ListResourceTemplates { .. } => "resources/templates/list",
+        mcp_schema::ClientRequest::ReadResource { .. } => "resources/read",
+        mcp_schema::ClientRequest::Subscribe { .. } => "resources/subscribe",
+        mcp_schema::ClientRequest::Unsubscribe { .. } => "resources/unsubscribe",
+        mcp_schema::ClientRequest::ListPrompts { .. } => "prompts/list",
+        mcp_schema::ClientRequest::GetPrompt { .. } => "prompts/get",
+        mcp_schema::ClientRequest::ListTools { .. } => "tools/list",
+        mcp_schema::ClientRequest::CallTool { .. } => "tools/call",
+        mcp_schema::ClientRequest::SetLevel { .. } => "logging/setLevel",
+        mcp_schema::ClientRequest::Complete { .. } => "completion/complete",
+    }
+}
+
+const fn request_id(request: &mcp_schema::ClientRequest) -> &mcp_schema::RequestId {
+    match request {
+        mcp_schema::ClientRequest::Initialize { id, .. }
+        | mcp_schema::ClientRequest::Ping { id, .. }
+        | mcp_schema::ClientRequest::ListResources { id, .. }
+        | mcp_schema::ClientRequest::ListResourceTemplates { id, .. }
+        | mcp_schema::ClientRequest::ReadResource { id, .. }
+        | mcp_schema::ClientRequest::Subscribe { id, .. }
+        | mcp_schema::ClientRequest::Unsubscribe { id, .. }
+        | mcp_schema::ClientRequest::ListPrompts { id, .. }
+        | mcp_schema::ClientRequest::GetPrompt { id, .. }
+        | mcp_schema::ClientRequest::ListTools { id, .. }
+        | mcp_schema::ClientRequest::CallTool { id, .. }
+        | mcp_schema::ClientRequest::SetLevel { id, .. }
+        | mcp_schema::ClientRequest::Complete { id, .. } => id,
+    }
+}
+
+/// Makes the address of the client available to handlers
+async fn provide_peer(request: Request, next: Next) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .cloned();
+    match peer {
+        Some(peer) => crate::inject::provide(peer, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+/// Writes a message to a byte stream in its current wire format, switching to the negotiated
+/// format once the successful response to the initialize request asking for it is written
+async fn write_message(
+    output: &mut (impl AsyncWrite + Unpin),
+    wire: &Mutex<Wire>,
+    negotiating: &mut Option<(serde_json::Value, Wire)>,
+    msg: &ServerResponse,
+) -> std::io::Result<()> {
+    let mut msg = match serde_json::to_value(msg) {
+        Ok(msg) => msg,
+        Err(e) => {
+            warn!("Error serializing message: {}", e);
+            return Ok(());
+        }
+    };
+
+    let switch = negotiating
+        .take_if(|(id, _)| msg.get("id") == Some(id))
+        .filter(|_| msg.get("result").is_some())
+        .map(|(_, next)| next);
+    if let Some(next) = switch {
+        next.confirm(&mut msg);
+    }
+
+    let current = *wire.lock().unwrap();
+    match current.encode(&msg) {
+        Ok(bytes) => output.write_all(&bytes).await?,
+        Err(e) => warn!("Error serializing message: {}", e),
+    }
+
+    if let Some(next) = switch {
+        info!(
+            "Switching to {} encoding with {} compression",
+            next.encoding.name(),
+            next.compression.name()
+        );
+        *wire.lock().unwrap() = next;
+    }
+    Ok(())
+}
+
+fn empty_response(
+    json_rpc: String,
+    id: mcp_schema::RequestId,
+) -> Result<mcp_schema::JSONRPCResponse<mcp_schema::ServerResult>, Error> {
+    Ok(mcp_schema::JSONRPCResponse {
+        json_rpc: checked_version(json_rpc)?,
+        id,
+        result: mcp_schema::ServerResult::Empty(mcp_schema::EmptyResult {
+            meta: None,
+            extra: HashMap::new(),
+        }),
+    })
+}
+
+fn checked_version(json_rpc: String) -> Result<String, Error> {
+    let expected = mcp_schema::JSONRPC_VERSION;
+    if json_rpc == expected {
+        Ok(json_rpc)
+    } else {
+        Err(Error::invalid_request(format!(
+            "Client is using JSON RPC version {json_rpc}, but server only supports version {expected}"
+        )))
+    }
+}
+
+#[expect(clippy::too_many_lines)]
+async fn handle_request(
+    service: &(impl Service + Send + Sync),
+    request: mcp_schema::ClientRequest,
+) -> Result<mcp_schema::JSONRPCResponse<mcp_schema::ServerResult>, Error> {
+    let response = match request {
+        mcp_schema::ClientRequest::Initialize {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .init(params)
+                .await
+                .map(mcp_schema::ServerResult::Initialize)?,
+        },
+        mcp_schema::ClientRequest::Ping {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .ping(params)
+                .await
+                .map(mcp_schema::ServerResult::Empty)?,
+        },
+        mcp_schema::ClientRequest::ListResources {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .list_resources(params)
+                .await
+                .map(mcp_schema::ServerResult::ListResources)?,
+        },
+        mcp_schema::ClientRequest::ListResourceTemplates {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .list_resource_templates(params)
+                .await
+                .map(mcp_schema::ServerResult::ListResourceTemplates)?,
+        },
+        mcp_schema::ClientRequest::ReadResource {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .read_resource(params)
+                .await
+                .map(mcp_schema::ServerResult::ReadResource)?,
+        },
+        mcp_schema::ClientRequest::Subscribe {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .subscribe(params)
+                .await
+                .map(mcp_schema::ServerResult::Empty)?,
+        },
+        mcp_schema::ClientRequest::Unsubscribe {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .unsubscribe(params)
+                .await
+                .map(mcp_schema::ServerResult::Empty)?,
+        },
+        mcp_schema::ClientRequest::ListPrompts {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .list_prompts(params)
+                .await
+                .map(mcp_schema::ServerResult::ListPrompts)?,
+        },
+        mcp_schema::ClientRequest::GetPrompt {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .get_prompt(params)
+                .await
+                .map(mcp_schema::ServerResult::GetPrompt)?,
+        },
+        mcp_schema::ClientRequest::ListTools {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .list_tools(params)
+                .await
+                .map(mcp_schema::ServerResult::ListTools)?,
+        },
+        mcp_schema::ClientRequest::CallTool {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .call_tool(params)
+                .await
+                .map(mcp_schema::ServerResult::CallTool)?,
+        },
+        mcp_schema::ClientRequest::SetLevel {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .set_level(params)
+                .await
+                .map(mcp_schema::ServerResult::Empty)?,
+        },
+        mcp_schema::ClientRequest::Complete {
+            json_rpc,
+            id,
+            params,
+        } => mcp_schema::JSONRPCResponse {
+            json_rpc: checked_version(json_rpc)?,
+            id,
+            result: service
+                .complete(params)
+                .await
+                .map(mcp_schema::ServerResult::Complete)?,
+        },
+    };
+
+    Ok(response)
+}
@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter};
+
+/// A problem found by [`crate::BasicService::self_check`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelfCheckProblem {
+    /// What the problem is about, such as `tool 'get_forecast'`
+    pub subject: String,
+    pub message: String,
+}
+
+/// The result of [`crate::BasicService::self_check`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SelfCheckReport {
+    pub checked: usize,
+    pub problems: Vec<SelfCheckProblem>,
+}
+
+impl SelfCheckReport {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub(crate) fn check(&mut self, subject: impl Display, result: Result<(), String>) {
+        self.checked += 1;
+        if let Err(message) = result {
+            self.problems.push(SelfCheckProblem {
+                subject: subject.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+impl Display for SelfCheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_ok() {
+            return write!(f, "{} checks passed", self.checked);
+        }
+
+        writeln!(
+            f,
+            "{} of {} checks failed:",
+            self.problems.len(),
+            self.checked
+        )?;
+        for problem in &self.problems {
+            writeln!(f, "  {}: {}", problem.subject, problem.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that every expression in a uri template is closed and names at least one variable
+pub(crate) fn check_uri_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err(format!(
+                "unmatched '}}' at byte {}",
+                template.len() - rest.len() + start
+            ));
+        }
+
+        let expression = &rest[start + 1..];
+        let end = expression
+            .find(['{', '}'])
+            .filter(|&end| expression[end..].starts_with('}'))
+            .ok_or_else(|| "unclosed '{' in template".to_string())?;
+
+        let variables = expression[..end].trim_start_matches(['+', '#', '.', '/', ';', '?', '&']);
+        if variables
+            .split(',')
+            .any(|variable| variable.trim_end_matches('*').is_empty())
+        {
+            return Err(format!(
+                "empty variable name in '{{{}}}'",
+                &expression[..end]
+            ));
+        }
+
+        rest = &expression[end + 1..];
+    }
+    Ok(())
+}
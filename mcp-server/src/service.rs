@@ -0,0 +1,428 @@
+use crate::Error;
+use crate::rpc::ClientMessage;
+use futures::future::BoxFuture;
+
+pub trait Service {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    );
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> impl Future<Output = Result<mcp_schema::InitializeResult, Error>> + Send;
+
+    fn ping(
+        &self,
+        _: mcp_schema::PingParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send;
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourcesResult, Error>> + Send;
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourceTemplatesResult, Error>> + Send;
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + Send;
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send;
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send;
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListPromptsResult, Error>> + Send;
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + Send;
+
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListToolsResult, Error>> + Send;
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send;
+
+    fn set_level(
+        &self,
+        request: mcp_schema::SetLevelParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send;
+
+    /// Suggests values for an argument of a prompt or resource template. Without completions, no
+    /// values are suggested.
+    fn complete(
+        &self,
+        _request: mcp_schema::CompleteParams,
+    ) -> impl Future<Output = Result<mcp_schema::CompleteResult, Error>> + Send {
+        async { crate::completion::CompletionResult::new().build() }
+    }
+
+    /// Number of resource subscriptions the service is following, for metrics
+    fn active_subscriptions(&self) -> usize {
+        0
+    }
+
+    /// Inspects or rewrites every message after it is decoded and before it is handled
+    ///
+    /// # Errors
+    /// If the message is rejected, this will error. Rejected requests are answered with the
+    /// error, and rejected notifications are dropped.
+    fn intercept(&self, _message: &mut ClientMessage) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Releases everything the service holds, such as connections and subscription tasks. This is
+    /// called once when the server shuts down gracefully.
+    fn shutdown(&self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+/// An object safe version of [`Service`], with boxed futures, so services of different types can
+/// be stored together or chosen at runtime. Every [`Service`] is a `DynService`, and
+/// [`BoxService`] turns one back into a [`Service`].
+pub trait DynService: Send + Sync {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    );
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::InitializeResult, Error>>;
+
+    fn ping(
+        &self,
+        request: mcp_schema::PingParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>>;
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListResourcesResult, Error>>;
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListResourceTemplatesResult, Error>>;
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ReadResourceResult, Error>>;
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>>;
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>>;
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListPromptsResult, Error>>;
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::GetPromptResult, Error>>;
+
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListToolsResult, Error>>;
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::CallToolResult, Error>>;
+
+    fn set_level(
+        &self,
+        request: mcp_schema::SetLevelParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>>;
+
+    fn complete(
+        &self,
+        request: mcp_schema::CompleteParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::CompleteResult, Error>>;
+
+    fn active_subscriptions(&self) -> usize;
+
+    fn intercept(&self, message: &mut ClientMessage) -> Result<(), Error>;
+
+    fn shutdown(&self) -> BoxFuture<'_, ()>;
+}
+
+impl<S: Service + Send + Sync> DynService for S {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    ) {
+        Service::set_notification_handler(self, handler);
+    }
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::InitializeResult, Error>> {
+        Box::pin(Service::init(self, request))
+    }
+
+    fn ping(
+        &self,
+        request: mcp_schema::PingParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>> {
+        Box::pin(Service::ping(self, request))
+    }
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListResourcesResult, Error>> {
+        Box::pin(Service::list_resources(self, request))
+    }
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListResourceTemplatesResult, Error>> {
+        Box::pin(Service::list_resource_templates(self, request))
+    }
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ReadResourceResult, Error>> {
+        Box::pin(Service::read_resource(self, request))
+    }
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>> {
+        Box::pin(Service::subscribe(self, request))
+    }
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>> {
+        Box::pin(Service::unsubscribe(self, request))
+    }
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListPromptsResult, Error>> {
+        Box::pin(Service::list_prompts(self, request))
+    }
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::GetPromptResult, Error>> {
+        Box::pin(Service::get_prompt(self, request))
+    }
+
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::ListToolsResult, Error>> {
+        Box::pin(Service::list_tools(self, request))
+    }
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::CallToolResult, Error>> {
+        Box::pin(Service::call_tool(self, request))
+    }
+
+    fn set_level(
+        &self,
+        request: mcp_schema::SetLevelParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::EmptyResult, Error>> {
+        Box::pin(Service::set_level(self, request))
+    }
+
+    fn complete(
+        &self,
+        request: mcp_schema::CompleteParams,
+    ) -> BoxFuture<'_, Result<mcp_schema::CompleteResult, Error>> {
+        Box::pin(Service::complete(self, request))
+    }
+
+    fn active_subscriptions(&self) -> usize {
+        Service::active_subscriptions(self)
+    }
+
+    fn intercept(&self, message: &mut ClientMessage) -> Result<(), Error> {
+        Service::intercept(self, message)
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        Box::pin(Service::shutdown(self))
+    }
+}
+
+/// A boxed [`DynService`], so services chosen at runtime can be served like any other
+///
+/// ```ignore
+/// let service = if config.read_only {
+///     BoxService::new(read_only)
+/// } else {
+///     BoxService::new(full)
+/// };
+/// ```
+pub struct BoxService(Box<dyn DynService>);
+
+impl BoxService {
+    #[must_use]
+    pub fn new(service: impl Service + Send + Sync + 'static) -> Self {
+        Self(Box::new(service))
+    }
+}
+
+impl From<Box<dyn DynService>> for BoxService {
+    fn from(service: Box<dyn DynService>) -> Self {
+        Self(service)
+    }
+}
+
+impl Service for BoxService {
+    fn set_notification_handler(
+        &mut self,
+        handler: Box<dyn Fn(mcp_schema::ServerNotification) + Send + Sync>,
+    ) {
+        self.0.set_notification_handler(handler);
+    }
+
+    fn init(
+        &self,
+        request: mcp_schema::InitializeParams,
+    ) -> impl Future<Output = Result<mcp_schema::InitializeResult, Error>> + Send {
+        self.0.init(request)
+    }
+
+    fn ping(
+        &self,
+        request: mcp_schema::PingParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.0.ping(request)
+    }
+
+    fn list_resources(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourcesResult, Error>> + Send {
+        self.0.list_resources(request)
+    }
+
+    fn list_resource_templates(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListResourceTemplatesResult, Error>> + Send {
+        self.0.list_resource_templates(request)
+    }
+
+    fn read_resource(
+        &self,
+        request: mcp_schema::ReadResourceParams,
+    ) -> impl Future<Output = Result<mcp_schema::ReadResourceResult, Error>> + Send {
+        self.0.read_resource(request)
+    }
+
+    fn subscribe(
+        &self,
+        request: mcp_schema::SubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.0.subscribe(request)
+    }
+
+    fn unsubscribe(
+        &self,
+        request: mcp_schema::UnsubscribeParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.0.unsubscribe(request)
+    }
+
+    fn list_prompts(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListPromptsResult, Error>> + Send {
+        self.0.list_prompts(request)
+    }
+
+    fn get_prompt(
+        &self,
+        request: mcp_schema::GetPromptParams,
+    ) -> impl Future<Output = Result<mcp_schema::GetPromptResult, Error>> + Send {
+        self.0.get_prompt(request)
+    }
+
+    fn list_tools(
+        &self,
+        request: mcp_schema::PaginatedParams,
+    ) -> impl Future<Output = Result<mcp_schema::ListToolsResult, Error>> + Send {
+        self.0.list_tools(request)
+    }
+
+    fn call_tool(
+        &self,
+        request: mcp_schema::CallToolParams,
+    ) -> impl Future<Output = Result<mcp_schema::CallToolResult, Error>> + Send {
+        self.0.call_tool(request)
+    }
+
+    fn set_level(
+        &self,
+        request: mcp_schema::SetLevelParams,
+    ) -> impl Future<Output = Result<mcp_schema::EmptyResult, Error>> + Send {
+        self.0.set_level(request)
+    }
+
+    fn complete(
+        &self,
+        request: mcp_schema::CompleteParams,
+    ) -> impl Future<Output = Result<mcp_schema::CompleteResult, Error>> + Send {
+        self.0.complete(request)
+    }
+
+    fn active_subscriptions(&self) -> usize {
+        self.0.active_subscriptions()
+    }
+
+    fn intercept(&self, message: &mut ClientMessage) -> Result<(), Error> {
+        self.0.intercept(message)
+    }
+
+    fn shutdown(&self) -> impl Future<Output = ()> + Send {
+        self.0.shutdown()
+    }
+}
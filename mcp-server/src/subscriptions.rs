@@ -0,0 +1,81 @@
+use crate::Error;
+use std::collections::{HashMap, HashSet};
+
+/// Most resources a session may be subscribed to by default
+pub(crate) const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+
+/// The sessions subscribed to each resource, with `None` for the connection of transports without
+/// sessions, such as stdio. The service is only asked to watch a resource once, when its first
+/// session subscribes, and to stop when its last session unsubscribes or closes.
+pub(crate) struct Subscriptions {
+    sessions: HashMap<String, HashSet<Option<String>>>,
+    limit: usize,
+}
+
+impl Subscriptions {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            limit,
+        }
+    }
+
+    pub(crate) const fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    fn count(&self, session: &Option<String>) -> usize {
+        self.sessions
+            .values()
+            .filter(|sessions| sessions.contains(session))
+            .count()
+    }
+
+    /// Records a subscription, returning whether it is the first to the resource
+    pub(crate) fn subscribe(&mut self, session: Option<String>, uri: &str) -> Result<bool, Error> {
+        if self.is_subscribed(session.as_deref(), uri) {
+            return Ok(false);
+        }
+        if self.count(&session) >= self.limit {
+            return Err(Error::invalid_request(format!(
+                "Too many subscriptions, the limit is {} per session",
+                self.limit
+            )));
+        }
+        let sessions = self.sessions.entry(uri.to_string()).or_default();
+        sessions.insert(session);
+        Ok(sessions.len() == 1)
+    }
+
+    /// Removes a subscription, returning whether it was the last to the resource
+    pub(crate) fn unsubscribe(&mut self, session: &Option<String>, uri: &str) -> bool {
+        let Some(sessions) = self.sessions.get_mut(uri) else {
+            return false;
+        };
+        if !sessions.remove(session) || !sessions.is_empty() {
+            return false;
+        }
+        self.sessions.remove(uri);
+        true
+    }
+
+    /// Removes every subscription of a closed session, returning the resources no session is
+    /// subscribed to anymore
+    pub(crate) fn close(&mut self, session: &Option<String>) -> Vec<String> {
+        let mut unwatched = Vec::new();
+        self.sessions.retain(|uri, sessions| {
+            if sessions.remove(session) && sessions.is_empty() {
+                unwatched.push(uri.clone());
+                return false;
+            }
+            true
+        });
+        unwatched
+    }
+
+    pub(crate) fn is_subscribed(&self, session: Option<&str>, uri: &str) -> bool {
+        self.sessions
+            .get(uri)
+            .is_some_and(|sessions| sessions.contains(&session.map(str::to_string)))
+    }
+}
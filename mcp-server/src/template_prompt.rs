@@ -0,0 +1,157 @@
+use crate::Error;
+use crate::registry::prompt::Prompt;
+use crate::registry::{HandlerArgs, HandlerFn};
+use mcp_schema::Role;
+use minijinja::Environment;
+use std::collections::HashMap;
+use std::pin::Pin;
+
+fn template_error(e: &minijinja::Error) -> Error {
+    Error::internal(format!("Invalid prompt template: {e:#}"))
+}
+
+/// A prompt rendered from [minijinja](https://docs.rs/minijinja) templates, one per message, so
+/// simple prompts need no handler.
+///
+/// Arguments are available in the templates by name. Arguments that weren't passed are undefined,
+/// so `{% if language %}` tests for them, and messages that render to nothing but whitespace are
+/// left out.
+///
+/// ```ignore
+/// let prompt = TemplatePrompt::new("review")
+///     .description("Review code")
+///     .argument("code", "The code to review", true)
+///     .argument("language", "The language of the code", false)
+///     .user("Review this{% if language %} {{ language }}{% endif %} code:\n\n{{ code }}")
+///     .build()?;
+/// ```
+pub struct TemplatePrompt {
+    name: String,
+    description: Option<String>,
+    arguments: Vec<mcp_schema::PromptArgument>,
+    messages: Vec<(Role, String)>,
+}
+
+impl TemplatePrompt {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            arguments: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Declares an argument, which is listed to clients in the order declared
+    #[must_use]
+    pub fn argument(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        required: bool,
+    ) -> Self {
+        self.arguments.push(mcp_schema::PromptArgument {
+            name: name.into(),
+            description: Some(description.into()),
+            required: Some(required),
+            extra: HashMap::new(),
+        });
+        self
+    }
+
+    /// Adds a message from the user, rendered from the template
+    #[must_use]
+    pub fn user(self, template: impl Into<String>) -> Self {
+        self.message(Role::User, template)
+    }
+
+    /// Adds a message from the assistant, rendered from the template
+    #[must_use]
+    pub fn assistant(self, template: impl Into<String>) -> Self {
+        self.message(Role::Assistant, template)
+    }
+
+    #[must_use]
+    pub fn message(mut self, role: Role, template: impl Into<String>) -> Self {
+        self.messages.push((role, template.into()));
+        self
+    }
+
+    /// Builds a prompt that can be registered like any other.
+    ///
+    /// # Errors
+    /// If a template has a syntax error, this will error.
+    pub fn build<State>(self) -> Result<Prompt<State>, Error> {
+        for (_, template) in &self.messages {
+            Environment::new()
+                .template_from_str(template)
+                .map_err(|e| template_error(&e))?;
+        }
+
+        let required = self
+            .arguments
+            .iter()
+            .filter(|argument| argument.required == Some(true))
+            .map(|argument| argument.name.clone())
+            .collect();
+        Ok(Prompt::from_parts(
+            self.name,
+            self.description,
+            self.arguments,
+            Box::new(Renderer {
+                env: Environment::new(),
+                required,
+                messages: self.messages,
+            }),
+        ))
+    }
+}
+
+struct Renderer {
+    env: Environment<'static>,
+    required: Vec<String>,
+    messages: Vec<(Role, String)>,
+}
+
+impl Renderer {
+    fn render(&self, args: &HandlerArgs) -> Result<Vec<mcp_schema::PromptMessage>, Error> {
+        if let Some(missing) = self.required.iter().find(|name| !args.contains_key(*name)) {
+            return Err(Error::invalid_params(format!(
+                "Missing required argument '{missing}'"
+            )));
+        }
+
+        let context = minijinja::Value::from_serialize(args);
+        let mut messages = Vec::with_capacity(self.messages.len());
+        for (role, template) in &self.messages {
+            let text = self
+                .env
+                .render_str(template, &context)
+                .map_err(|e| template_error(&e))?;
+            if !text.trim().is_empty() {
+                messages.push(mcp_schema::PromptMessage {
+                    role: role.clone(),
+                    content: crate::content::text(text),
+                });
+            }
+        }
+        Ok(messages)
+    }
+}
+
+impl<State> HandlerFn<State, Vec<mcp_schema::PromptMessage>> for Renderer {
+    fn run(
+        &self,
+        _state: State,
+        args: HandlerArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<mcp_schema::PromptMessage>, Error>> + Send>> {
+        Box::pin(std::future::ready(self.render(&args)))
+    }
+}
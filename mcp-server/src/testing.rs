@@ -0,0 +1,472 @@
+use crate::{Error, ErrorCode, McpImpl, Service};
+use futures::Stream;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Room for messages in flight in each direction of the in-memory transport
+const BUFFER_SIZE: usize = 64 * 1024;
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<serde_json::Value>>>>;
+
+/// The senders of the open subscriptions to each resource, by subscription id
+type Subscriptions = Arc<
+    Mutex<HashMap<String, HashMap<u64, mpsc::UnboundedSender<mcp_schema::ResourceUpdatedParams>>>>,
+>;
+
+/// What the writer task sends to the server, with where to report once it has been written
+type Queued = (Outgoing, Option<oneshot::Sender<std::io::Result<()>>>);
+
+/// What the client sends to the server
+enum Outgoing {
+    Message(Vec<u8>),
+    /// Closes the input of the server
+    Close,
+}
+
+/// Serves the service in memory and returns a client connected to it, which has already
+/// initialized. This makes end-to-end tests of tools, prompts and resources take a few lines.
+///
+/// ```ignore
+/// #[tokio::test]
+/// async fn adds_numbers() {
+///     let mut client = mcp::testing::harness(service()).await.unwrap();
+///     let result = client.call_tool("add", json!({ "a": 1, "b": 2 })).await.unwrap();
+///     assert_eq!(result.is_error, Some(false));
+/// }
+/// ```
+///
+/// # Errors
+/// If the service fails to initialize, this will error.
+pub async fn harness<S: Service + Send + Sync + 'static>(service: S) -> Result<McpClient, Error> {
+    let (client_writer, server_reader) = tokio::io::duplex(BUFFER_SIZE);
+    let (server_writer, client_reader) = tokio::io::duplex(BUFFER_SIZE);
+
+    let server = Arc::new(McpImpl::new(service));
+    let server = tokio::spawn(async move {
+        if let Err(e) = server
+            .clone()
+            .serve_over(server_reader, server_writer)
+            .await
+        {
+            warn!("Test server stopped: {e}");
+        }
+        // Like stdio, the server shuts down once its input ends
+        server.shutdown().await;
+    });
+
+    let pending = Pending::default();
+    let subscriptions = Subscriptions::default();
+    let (notification_sender, notifications) = mpsc::unbounded_channel();
+    let reader = tokio::spawn(read_messages(
+        client_reader,
+        pending.clone(),
+        subscriptions.clone(),
+        notification_sender,
+    ));
+    let (outgoing, queued) = mpsc::unbounded_channel();
+    let writer = tokio::spawn(write_messages(client_writer, queued));
+
+    let mut client = McpClient {
+        outgoing,
+        pending,
+        subscriptions,
+        notifications,
+        next_id: Arc::new(AtomicU64::new(1)),
+        tasks: [server, reader, writer],
+        initialize_result: None,
+    };
+    let initialize_result = client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": mcp_schema::LATEST_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": {
+                    "name": "mcp-testing",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+        )
+        .await?;
+    client.initialize_result = Some(initialize_result);
+    client
+        .notify("notifications/initialized", json!({}))
+        .await?;
+    Ok(client)
+}
+
+/// Passes responses to the requests waiting for them and queues notifications, passing resource
+/// updates to their subscriptions as well
+async fn read_messages(
+    reader: DuplexStream,
+    pending: Pending,
+    subscriptions: Subscriptions,
+    notifications: mpsc::UnboundedSender<mcp_schema::ServerNotification>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Test client received invalid JSON: {e}");
+                continue;
+            }
+        };
+
+        let id = message.get("id").and_then(serde_json::Value::as_u64);
+        if let Some(id) = id.filter(|_| message.get("method").is_none()) {
+            if let Some(sender) = pending.lock().unwrap().remove(&id) {
+                let _ = sender.send(message);
+            }
+            continue;
+        }
+        match serde_json::from_value(message) {
+            Ok(notification) => {
+                if let mcp_schema::ServerNotification::ResourceUpdated { params, .. } =
+                    &notification
+                {
+                    let subscriptions = subscriptions.lock().unwrap();
+                    for sender in subscriptions
+                        .get(&params.uri)
+                        .into_iter()
+                        .flat_map(HashMap::values)
+                    {
+                        let _ = sender.send(mcp_schema::ResourceUpdatedParams {
+                            uri: params.uri.clone(),
+                            extra: params.extra.clone(),
+                        });
+                    }
+                }
+                let _ = notifications.send(notification);
+            }
+            Err(e) => warn!("Test client received an unknown message: {e}"),
+        }
+    }
+}
+
+/// Writes what is queued to the server in order, so messages queued without waiting, like those
+/// of dropped subscriptions, don't overtake each other
+async fn write_messages(mut writer: DuplexStream, mut queued: mpsc::UnboundedReceiver<Queued>) {
+    while let Some((outgoing, written)) = queued.recv().await {
+        let result = match outgoing {
+            Outgoing::Message(bytes) => writer.write_all(&bytes).await,
+            Outgoing::Close => writer.shutdown().await,
+        };
+        if let Some(written) = written {
+            let _ = written.send(result);
+        }
+    }
+}
+
+/// A request as it is sent to the server
+fn request_bytes(id: u64, method: &str, params: serde_json::Value) -> Result<Vec<u8>, Error> {
+    message_bytes(&json!({
+        "jsonrpc": mcp_schema::JSONRPC_VERSION,
+        "id": id,
+        "method": method,
+        "params": params,
+    }))
+}
+
+/// A message as it is sent to the server, one per line
+fn message_bytes(message: &serde_json::Value) -> Result<Vec<u8>, Error> {
+    let mut bytes = serde_json::to_vec(message)?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+/// Removes a subscription, returning whether it was the last one to its resource
+fn remove_subscription(subscriptions: &Subscriptions, uri: &str, id: u64) -> bool {
+    let mut subscriptions = subscriptions.lock().unwrap();
+    let Some(senders) = subscriptions.get_mut(uri) else {
+        return false;
+    };
+    senders.remove(&id);
+    let last = senders.is_empty();
+    if last {
+        subscriptions.remove(uri);
+    }
+    last
+}
+
+/// A client of a service served in memory by [`harness`]. Errors the server responds with are
+/// returned with their JSON-RPC code.
+pub struct McpClient {
+    outgoing: mpsc::UnboundedSender<Queued>,
+    pending: Pending,
+    subscriptions: Subscriptions,
+    notifications: mpsc::UnboundedReceiver<mcp_schema::ServerNotification>,
+    next_id: Arc<AtomicU64>,
+    tasks: [JoinHandle<()>; 3],
+    initialize_result: Option<mcp_schema::InitializeResult>,
+}
+
+impl McpClient {
+    /// What the server responded to `initialize` with, such as its capabilities and instructions
+    pub const fn initialize_result(&self) -> &mcp_schema::InitializeResult {
+        self.initialize_result
+            .as_ref()
+            .expect("the harness initializes before returning the client")
+    }
+
+    /// Queues something for the server and waits until it has been written
+    async fn write(&self, outgoing: Outgoing) -> std::io::Result<()> {
+        let (written, result) = oneshot::channel();
+        let gone = || std::io::Error::from(std::io::ErrorKind::BrokenPipe);
+        self.outgoing
+            .send((outgoing, Some(written)))
+            .map_err(|_| gone())?;
+        result.await.map_err(|_| gone())?
+    }
+
+    async fn send(&self, bytes: Vec<u8>) -> Result<(), Error> {
+        self.write(Outgoing::Message(bytes))
+            .await
+            .map_err(|e| Error::internal(format!("Failed to send to the test server: {e}")))
+    }
+
+    /// Sends a request with any method and returns its result
+    ///
+    /// # Errors
+    /// If the server responds with an error, or the result doesn't deserialize, this will error.
+    pub async fn request<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+        self.send(request_bytes(id, method, params)?).await?;
+
+        let mut response = receiver
+            .await
+            .map_err(|_| Error::internal("The test server closed the connection"))?;
+        if let Some(mut error) = response.get_mut("error").map(serde_json::Value::take) {
+            let code = error["code"]
+                .as_i64()
+                .and_then(|code| i32::try_from(code).ok())
+                .unwrap_or_default();
+            let message = error["message"].as_str().unwrap_or_default().to_string();
+            let data = error["data"].take();
+            let error = Error::new(ErrorCode::from_code(code), message);
+            return Err(if data.is_null() {
+                error
+            } else {
+                error.with_data(data)
+            });
+        }
+        Ok(serde_json::from_value(response["result"].take())?)
+    }
+
+    /// Sends a notification with any method
+    ///
+    /// # Errors
+    /// If the server is gone, this will error.
+    pub async fn notify(&mut self, method: &str, params: serde_json::Value) -> Result<(), Error> {
+        self.send(message_bytes(&json!({
+            "jsonrpc": mcp_schema::JSONRPC_VERSION,
+            "method": method,
+            "params": params,
+        }))?)
+        .await
+    }
+
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn list_tools(&mut self) -> Result<mcp_schema::ListToolsResult, Error> {
+        self.request("tools/list", json!({})).await
+    }
+
+    /// Calls a tool with arguments given as a JSON object
+    ///
+    /// # Errors
+    /// If the server responds with an error, this will error. Failures of the tool itself are
+    /// results marked with `is_error`.
+    pub async fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<mcp_schema::CallToolResult, Error> {
+        self.request(
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        )
+        .await
+    }
+
+    /// Calls a tool with typed input and deserializes its structured output, such as the value a
+    /// handler returned as [`Json`](crate::content::Json)
+    ///
+    /// # Errors
+    /// If the server responds with an error, the tool fails, or its structured output doesn't
+    /// deserialize, this will error.
+    pub async fn call<O: DeserializeOwned>(
+        &mut self,
+        name: &str,
+        input: impl Serialize,
+    ) -> Result<O, Error> {
+        let mut result: serde_json::Value = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": serde_json::to_value(input)? }),
+            )
+            .await?;
+        if result["isError"] == true {
+            let message = result["content"][0]["text"].as_str().unwrap_or_default();
+            return Err(Error::internal(format!("Tool '{name}' failed: {message}")));
+        }
+        Ok(serde_json::from_value(result["structuredContent"].take())?)
+    }
+
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn list_prompts(&mut self) -> Result<mcp_schema::ListPromptsResult, Error> {
+        self.request("prompts/list", json!({})).await
+    }
+
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn get_prompt(
+        &mut self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<mcp_schema::GetPromptResult, Error> {
+        self.request(
+            "prompts/get",
+            json!({ "name": name, "arguments": arguments }),
+        )
+        .await
+    }
+
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn list_resources(&mut self) -> Result<mcp_schema::ListResourcesResult, Error> {
+        self.request("resources/list", json!({})).await
+    }
+
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn read_resource(
+        &mut self,
+        uri: &str,
+    ) -> Result<mcp_schema::ReadResourceResult, Error> {
+        self.request("resources/read", json!({ "uri": uri })).await
+    }
+
+    /// Subscribes to a resource and returns a stream of its updates. The updates still arrive as
+    /// notifications too. Dropping the stream unsubscribes from the resource once no other
+    /// subscription to it is left.
+    ///
+    /// # Errors
+    /// If the server responds with an error, this will error.
+    pub async fn subscribe(&mut self, uri: &str) -> Result<Subscription, Error> {
+        // Registered before subscribing, so updates sent right after the response aren't missed
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, updates) = mpsc::unbounded_channel();
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .entry(uri.to_string())
+            .or_default()
+            .insert(id, sender);
+
+        let result = self
+            .request::<serde_json::Value>("resources/subscribe", json!({ "uri": uri }))
+            .await;
+        if let Err(e) = result {
+            remove_subscription(&self.subscriptions, uri, id);
+            return Err(e);
+        }
+        Ok(Subscription {
+            id,
+            uri: uri.to_string(),
+            updates,
+            subscriptions: self.subscriptions.clone(),
+            outgoing: self.outgoing.clone(),
+            next_id: self.next_id.clone(),
+        })
+    }
+
+    /// Closes the input of the server and waits for it to shut down, which tears down the tools
+    /// and resources of the service
+    ///
+    /// # Errors
+    /// If the server panicked, this will error.
+    pub async fn close(mut self) -> Result<(), Error> {
+        self.write(Outgoing::Close)
+            .await
+            .map_err(|e| Error::internal(format!("Failed to close the test server: {e}")))?;
+        (&mut self.tasks[0])
+            .await
+            .map_err(|e| Error::internal(format!("The test server failed: {e}")))
+    }
+
+    /// Waits for the next notification from the server, such as a resource update or log
+    /// message, for up to the timeout
+    pub async fn next_notification(
+        &mut self,
+        timeout: Duration,
+    ) -> Option<mcp_schema::ServerNotification> {
+        tokio::time::timeout(timeout, self.notifications.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Notifications that have already arrived, without waiting for more
+    pub fn drain_notifications(&mut self) -> Vec<mcp_schema::ServerNotification> {
+        std::iter::from_fn(|| self.notifications.try_recv().ok()).collect()
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// The updates of a resource subscribed to with [`McpClient::subscribe`]
+#[must_use = "dropping a subscription unsubscribes from the resource"]
+pub struct Subscription {
+    id: u64,
+    uri: String,
+    updates: mpsc::UnboundedReceiver<mcp_schema::ResourceUpdatedParams>,
+    subscriptions: Subscriptions,
+    outgoing: mpsc::UnboundedSender<Queued>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Stream for Subscription {
+    type Item = mcp_schema::ResourceUpdatedParams;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_recv(cx)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if !remove_subscription(&self.subscriptions, &self.uri, self.id) {
+            return;
+        }
+        // Nothing waits for the response, which the client ignores
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(bytes) = request_bytes(id, "resources/unsubscribe", json!({ "uri": self.uri })) {
+            let _ = self.outgoing.send((Outgoing::Message(bytes), None));
+        }
+    }
+}
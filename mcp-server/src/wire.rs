@@ -0,0 +1,282 @@
+use crate::rpc::ClientMessage;
+use futures::stream::{self, Stream};
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+
+/// Set in the length prefix of frames whose body is compressed
+#[cfg(feature = "zstd")]
+const COMPRESSED_FLAG: u32 = 1 << 31;
+
+/// Bodies at least this large are compressed when compression was negotiated
+#[cfg(feature = "zstd")]
+const COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// How messages are encoded on byte stream transports like stdio. Transports start in JSON and
+/// switch after initialization if the client lists a supported encoding in
+/// `capabilities.experimental.encodings`. The server confirms the switch with
+/// `capabilities.experimental.encoding` in its initialize response, which is still sent as JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// Newline delimited JSON, as required by the MCP specification
+    #[default]
+    Json,
+    /// MessagePack, with every message prefixed by its length as a big endian `u32`
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+/// Compression of large frames, negotiated like [`Encoding`] through
+/// `capabilities.experimental.compression` and confirmed in the initialize response. It only
+/// applies to length prefixed encodings, where the highest bit of the prefix marks a compressed
+/// body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Zstandard, applied to bodies of 16 KiB or more
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Encoding {
+    /// Encodings the server can switch to, in order of preference
+    const NEGOTIABLE: &[Self] = &[
+        #[cfg(feature = "msgpack")]
+        Self::MessagePack,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => "msgpack",
+        }
+    }
+
+    const fn is_length_prefixed(self) -> bool {
+        !matches!(self, Self::Json)
+    }
+}
+
+impl Compression {
+    /// Compressions the server can switch to, in order of preference
+    const NEGOTIABLE: &[Self] = &[
+        #[cfg(feature = "zstd")]
+        Self::Zstd,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+/// The encoding and compression used by a transport
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Wire {
+    pub encoding: Encoding,
+    pub compression: Compression,
+}
+
+/// Lists the strings at a pointer into the initialize parameters
+fn requested(params: &serde_json::Value, pointer: &str) -> Vec<String> {
+    params
+        .pointer(pointer)
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+        .map(str::to_string)
+        .collect()
+}
+
+impl Wire {
+    /// Picks the encoding and compression to use after initialization from the ones requested by
+    /// the client
+    pub(crate) fn negotiate(params: &mcp_schema::InitializeParams) -> Self {
+        let params = serde_json::to_value(params).unwrap_or_default();
+
+        let encodings = requested(&params, "/capabilities/experimental/encodings");
+        let encoding = Encoding::NEGOTIABLE
+            .iter()
+            .copied()
+            .find(|encoding| encodings.iter().any(|name| name == encoding.name()))
+            .unwrap_or_default();
+
+        let compressions = requested(&params, "/capabilities/experimental/compression");
+        let compression = Compression::NEGOTIABLE
+            .iter()
+            .copied()
+            .filter(|_| encoding.is_length_prefixed())
+            .find(|compression| compressions.iter().any(|name| name == compression.name()))
+            .unwrap_or_default();
+
+        Self {
+            encoding,
+            compression,
+        }
+    }
+
+    /// Adds `capabilities.experimental.encoding` and `compression` to an initialize response
+    pub(crate) fn confirm(self, response: &mut serde_json::Value) {
+        let Some(capabilities) = response
+            .pointer_mut("/result/capabilities")
+            .and_then(serde_json::Value::as_object_mut)
+        else {
+            return;
+        };
+
+        let experimental = capabilities
+            .entry("experimental")
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if !experimental.is_object() {
+            *experimental = serde_json::Value::Object(serde_json::Map::new());
+        }
+        if let Some(experimental) = experimental.as_object_mut() {
+            experimental.insert("encoding".to_string(), self.encoding.name().into());
+            if self.compression != Compression::None {
+                experimental.insert("compression".to_string(), self.compression.name().into());
+            }
+        }
+    }
+
+    pub(crate) fn encode(self, message: &serde_json::Value) -> Result<Vec<u8>, String> {
+        match self.encoding {
+            Encoding::Json => {
+                let mut bytes = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+                bytes.push(b'\n');
+                Ok(bytes)
+            }
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => {
+                let body = rmp_serde::to_vec_named(message).map_err(|e| e.to_string())?;
+                self.frame(body)
+            }
+        }
+    }
+
+    /// Prefixes a body with its length, compressing it first if it is large
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(not(feature = "zstd"), allow(clippy::unused_self))]
+    fn frame(self, body: Vec<u8>) -> Result<Vec<u8>, String> {
+        #[cfg(feature = "zstd")]
+        let (body, flag) =
+            if self.compression == Compression::Zstd && body.len() >= COMPRESSION_THRESHOLD {
+                let body = zstd::encode_all(&body[..], 0).map_err(|e| e.to_string())?;
+                (body, COMPRESSED_FLAG)
+            } else {
+                (body, 0)
+            };
+        #[cfg(not(feature = "zstd"))]
+        let flag = 0;
+
+        let len = u32::try_from(body.len())
+            .ok()
+            .filter(|len| len & (1 << 31) == 0)
+            .ok_or_else(|| format!("message of {} bytes is too large", body.len()))?;
+        Ok([&(len | flag).to_be_bytes()[..], &body].concat())
+    }
+
+    pub(crate) fn decode(self, frame: &[u8]) -> Result<ClientMessage, String> {
+        match self.encoding {
+            Encoding::Json => serde_json::from_slice(frame).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => rmp_serde::from_slice(frame).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Reads one frame, decompressing it if needed, or [`None`] at the end of the input. Frames
+    /// larger than the limit are rejected before their body is read or decompressed.
+    async fn read_frame(
+        self,
+        input: &mut BufReader<impl AsyncRead + Unpin>,
+        limit: usize,
+    ) -> io::Result<Option<Vec<u8>>> {
+        match self.encoding {
+            Encoding::Json => {
+                let mut line = Vec::new();
+                let bound = u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1);
+                if (&mut *input)
+                    .take(bound)
+                    .read_until(b'\n', &mut line)
+                    .await?
+                    == 0
+                {
+                    return Ok(None);
+                }
+                if line.last() != Some(&b'\n') && line.len() > limit {
+                    return Err(too_large(line.len(), limit));
+                }
+                let len = line.trim_ascii_end().len();
+                line.truncate(len);
+                Ok(Some(line))
+            }
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => {
+                let prefix = match input.read_u32().await {
+                    Ok(prefix) => prefix,
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+                let len = (prefix & !(1 << 31)) as usize;
+                if len > limit {
+                    return Err(too_large(len, limit));
+                }
+                let mut frame = vec![0; len];
+                input.read_exact(&mut frame).await?;
+
+                #[cfg(feature = "zstd")]
+                if prefix & COMPRESSED_FLAG != 0 {
+                    return decompress(&frame, limit).map(Some);
+                }
+                Ok(Some(frame))
+            }
+        }
+    }
+}
+
+fn too_large(len: usize, limit: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("frame of {len} bytes exceeds the limit of {limit} bytes"),
+    )
+}
+
+/// Decompresses a frame body, stopping as soon as it grows past the limit
+#[cfg(feature = "zstd")]
+fn decompress(frame: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let bound = u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1);
+    let mut body = Vec::new();
+    zstd::stream::read::Decoder::new(frame)?
+        .take(bound)
+        .read_to_end(&mut body)?;
+    if body.len() > limit {
+        return Err(too_large(body.len(), limit));
+    }
+    Ok(body)
+}
+
+/// Reads frames with whatever wire format is current when each read starts. Reads only start
+/// when the stream is polled, so the caller can switch formats between frames.
+pub(crate) fn read_frames<R: AsyncRead + Unpin>(
+    input: R,
+    wire: Arc<Mutex<Wire>>,
+    limit: usize,
+) -> impl Stream<Item = io::Result<(Wire, Vec<u8>)>> {
+    stream::unfold(
+        (BufReader::new(input), wire),
+        |(mut input, wire)| async move {
+            let current = *wire.lock().unwrap();
+            let frame = current.read_frame(&mut input, limit).await.transpose()?;
+            Some((frame.map(|frame| (current, frame)), (input, wire)))
+        },
+    )
+}
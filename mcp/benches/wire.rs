@@ -0,0 +1,99 @@
+//! Throughput of requests served over a byte stream in each wire format. Run with
+//! `cargo bench --bench wire --features msgpack` to include MessagePack.
+
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+
+const REQUESTS: u32 = 10_000;
+
+fn encode(message: &serde_json::Value, framed: bool) -> Vec<u8> {
+    if framed {
+        return frame(message);
+    }
+    let mut line = serde_json::to_vec(message).unwrap();
+    line.push(b'\n');
+    line
+}
+
+#[cfg(feature = "msgpack")]
+fn frame(message: &serde_json::Value) -> Vec<u8> {
+    let body = rmp_serde::to_vec_named(message).unwrap();
+    let len = u32::try_from(body.len()).unwrap();
+    [&len.to_be_bytes()[..], &body].concat()
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn frame(_: &serde_json::Value) -> Vec<u8> {
+    unreachable!("framed encodings need the msgpack feature")
+}
+
+async fn skip_response(reader: &mut BufReader<impl AsyncRead + Unpin>, framed: bool) {
+    if framed {
+        let len = reader.read_u32().await.unwrap() & !(1 << 31);
+        let mut body = vec![0; len as usize];
+        reader.read_exact(&mut body).await.unwrap();
+    } else {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+    }
+}
+
+async fn run(name: &str, capabilities: serde_json::Value, payload: usize) {
+    let (mut writer, server_reader) = tokio::io::duplex(1024 * 1024);
+    let (server_writer, reader) = tokio::io::duplex(1024 * 1024);
+    let mut reader = BufReader::new(reader);
+    let mcp = Arc::new(McpImpl::new(BasicService::new(())));
+    let served = tokio::spawn(mcp.serve_over(server_reader, server_writer));
+
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": mcp_schema::LATEST_PROTOCOL_VERSION,
+            "capabilities": capabilities,
+            "clientInfo": { "name": "bench", "version": "1.0.0" },
+        },
+    });
+    writer.write_all(&encode(&initialize, false)).await.unwrap();
+    let mut response = String::new();
+    reader.read_line(&mut response).await.unwrap();
+    let framed = response.contains("\"encoding\":\"msgpack\"");
+
+    let padding = "x".repeat(payload);
+    let started = Instant::now();
+    for id in 1..=REQUESTS {
+        let ping = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "ping",
+            "params": { "_meta": { "padding": padding } },
+        });
+        writer.write_all(&encode(&ping, framed)).await.unwrap();
+        skip_response(&mut reader, framed).await;
+    }
+    let elapsed = started.elapsed();
+    served.abort();
+
+    println!(
+        "{name:<24} {payload:>6} byte payload: {:>8.1} µs/request",
+        elapsed.as_secs_f64() * 1e6 / f64::from(REQUESTS)
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    for payload in [0, 1024, 32 * 1024] {
+        run("json", json!({}), payload).await;
+        #[cfg(feature = "msgpack")]
+        run(
+            "msgpack",
+            json!({ "experimental": { "encodings": ["msgpack"] } }),
+            payload,
+        )
+        .await;
+    }
+}
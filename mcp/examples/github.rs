@@ -0,0 +1,253 @@
+//! Exposes GitHub issues and pull requests over MCP.
+//!
+//! Set `GITHUB_TOKEN` to a personal access token to raise rate limits and enable commenting.
+
+use mcp::Resource;
+use mcp::content::Json;
+use mcp::registry::resource::TemplateSource;
+use mcp_schema::ResourceContents;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tracing_subscriber::{fmt, prelude::*};
+
+const API: &str = "https://api.github.com";
+const ISSUE_TEMPLATE: &str = "github://{owner}/{repo}/issues/{number}";
+
+#[derive(Clone)]
+struct GitHub {
+    client: reqwest::Client,
+    token: Option<String>,
+}
+
+impl GitHub {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{API}{path}"))
+            .header("User-Agent", "mcp-github-example")
+            .header("Accept", "application/vnd.github+json");
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<serde_json::Value, mcp::Error> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| mcp::Error::internal(format!("GitHub request failed: {e}")))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            mcp::Error::internal(format!("GitHub returned an invalid response: {e}"))
+        })?;
+
+        if status.is_success() {
+            Ok(body)
+        } else {
+            Err(mcp::Error::internal(format!(
+                "GitHub returned {status}: {}",
+                body["message"].as_str().unwrap_or("unknown error")
+            ))
+            .with_data(serde_json::json!({ "status": status.as_u16() })))
+        }
+    }
+}
+
+/// Reads issues and pull requests at `github://{owner}/{repo}/issues/{number}`
+struct Issues;
+
+#[derive(Deserialize)]
+struct IssueParams {
+    owner: String,
+    repo: String,
+    number: u64,
+}
+
+impl TemplateSource<GitHub> for Issues {
+    type Params = IssueParams;
+
+    fn read(
+        &self,
+        github: GitHub,
+        uri: String,
+        IssueParams {
+            owner,
+            repo,
+            number,
+        }: IssueParams,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, mcp::Error>> + Send + 'static {
+        async move {
+            let path = format!("/repos/{owner}/{repo}/issues/{number}");
+            let issue = github
+                .send(github.request(reqwest::Method::GET, &path))
+                .await?;
+
+            Ok(vec![ResourceContents::Text(
+                mcp_schema::TextResourceContents {
+                    uri,
+                    mime_type: Some("text/markdown".to_string()),
+                    text: format!(
+                        "# {}\n\n{} opened by @{}\n\n{}",
+                        issue["title"].as_str().unwrap_or_default(),
+                        issue["state"].as_str().unwrap_or_default(),
+                        issue["user"]["login"].as_str().unwrap_or_default(),
+                        issue["body"].as_str().unwrap_or_default(),
+                    ),
+                },
+            )])
+        }
+    }
+
+    fn wait_for_change(
+        &self,
+        _: GitHub,
+        _: String,
+        _: IssueParams,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        // GitHub doesn't push changes, so subscriptions never fire
+        futures::future::pending()
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct SearchParams {
+    /// A GitHub search query, such as `repo:rust-lang/rust is:open label:C-bug`
+    query: String,
+    /// Maximum number of results, at most 100
+    limit: Option<u8>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CommentParams {
+    owner: String,
+    repo: String,
+    /// The issue or pull request number
+    number: u64,
+    /// The markdown body of the comment
+    body: String,
+}
+
+async fn search(
+    github: GitHub,
+    params: SearchParams,
+) -> Result<Json<serde_json::Value>, mcp::Error> {
+    let limit = params.limit.unwrap_or(10).min(100);
+    let results = github
+        .send(
+            github
+                .request(reqwest::Method::GET, "/search/issues")
+                .query(&[("q", params.query), ("per_page", limit.to_string())]),
+        )
+        .await?;
+
+    let items = results["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            serde_json::json!({
+                "title": item["title"],
+                "state": item["state"],
+                "url": item["html_url"],
+                "is_pull_request": item.get("pull_request").is_some(),
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::Value::Array(items)))
+}
+
+async fn comment(github: GitHub, params: CommentParams) -> Result<String, mcp::Error> {
+    let CommentParams {
+        owner,
+        repo,
+        number,
+        body,
+    } = params;
+
+    let comment = github
+        .send(
+            github
+                .request(
+                    reqwest::Method::POST,
+                    &format!("/repos/{owner}/{repo}/issues/{number}/comments"),
+                )
+                .json(&serde_json::json!({ "body": body })),
+        )
+        .await?;
+
+    Ok(format!(
+        "Commented at {}",
+        comment["html_url"].as_str().unwrap_or_default()
+    ))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(tracing_subscriber::filter::LevelFilter::INFO)
+        .init();
+
+    let github = GitHub {
+        client: reqwest::Client::new(),
+        token: std::env::var("GITHUB_TOKEN").ok(),
+    };
+
+    let service = mcp::server! {
+        name: "github",
+        version: "0.1.0",
+        instructions: "Search, read and comment on GitHub issues and pull requests. \
+            Read an issue with the github://{owner}/{repo}/issues/{number} resource template.",
+        state: github,
+        tool {
+            name: "search_issues",
+            description: "Search issues and pull requests",
+            annotations: mcp::registry::ToolAnnotations {
+                read_only_hint: Some(true),
+                open_world_hint: Some(true),
+                ..Default::default()
+            },
+            handler: search,
+        },
+        tool {
+            name: "comment",
+            description: "Comment on an issue or pull request",
+            annotations: mcp::registry::ToolAnnotations {
+                destructive_hint: Some(false),
+                open_world_hint: Some(true),
+                ..Default::default()
+            },
+            handler: comment,
+        },
+    }?
+    .template_resource(
+        Resource::builder()
+            .name("issue")
+            .template_uri(ISSUE_TEMPLATE)
+            .description("A GitHub issue or pull request")
+            .mime_type("text/markdown")
+            .typed_source(Issues)
+            .build()?,
+    );
+
+    let report = service.self_check().await;
+    if !report.is_ok() {
+        eyre::bail!("self check failed: {report}");
+    }
+    // With `--check`, only validate the service, such as in CI
+    if std::env::args().any(|arg| arg == "--check") {
+        eprintln!("{report}");
+        return Ok(());
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+    mcp::serve_over_sse(listener, service).await?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+mod service;
+
+use tracing_subscriber::{fmt, prelude::*};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    const USE_STDIO: bool = true;
+
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(tracing_subscriber::filter::LevelFilter::DEBUG)
+        .init();
+
+    let service = service::service()?;
+
+    let report = service.self_check().await;
+    if !report.is_ok() {
+        eyre::bail!("self check failed: {report}");
+    }
+    // With `--check`, only validate the service, such as in CI
+    if std::env::args().any(|arg| arg == "--check") {
+        eprintln!("{report}");
+        return Ok(());
+    }
+
+    if USE_STDIO {
+        mcp::serve_over_stdio(service).await?;
+    } else {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+        mcp::serve_over_sse(listener, service).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,316 @@
+//! The todo service, shared by the example binary and `tests/todo.rs`.
+
+#![allow(clippy::unused_async)]
+
+use mcp::content::Json;
+use mcp::notifier::Notifier;
+use mcp::registry::resource::TemplateSource;
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, Resource};
+use mcp_schema::ResourceContents;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::Notify;
+
+pub const TODOS_URI: &str = "todo://all";
+const TODO_TEMPLATE: &str = "todo://{id}";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Todo {
+    pub id: u64,
+    pub title: String,
+    pub done: bool,
+}
+
+#[derive(Default)]
+struct Todos {
+    next_id: u64,
+    todos: Vec<Todo>,
+}
+
+impl Todos {
+    fn get(&self, id: u64) -> Option<&Todo> {
+        self.todos.iter().find(|todo| todo.id == id)
+    }
+}
+
+#[derive(Clone)]
+pub struct State {
+    todos: Arc<Mutex<Todos>>,
+    resource: MemoryResource,
+    change: Arc<Notify>,
+    /// Set once the service is built, since the notifier comes from the service
+    notifier: Arc<OnceLock<Notifier>>,
+}
+
+impl State {
+    /// Applies a change to the todo list and notifies subscribers of the todo resources
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut Todos) -> Result<T, mcp::Error>,
+    ) -> Result<T, mcp::Error> {
+        let mut todos = self.todos.lock().unwrap();
+        let count = todos.todos.len();
+        let result = f(&mut *todos)?;
+
+        self.resource
+            .set([ResourceContents::Text(mcp_schema::TextResourceContents {
+                uri: TODOS_URI.to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&todos.todos)?,
+            })]);
+        self.change.notify_waiters();
+        // Each todo is a resource, so adding or removing one changes the list of resources
+        if todos.todos.len() != count {
+            if let Some(notifier) = self.notifier.get() {
+                notifier.resources_list_changed();
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn get(&self, id: u64) -> Option<Todo> {
+        self.todos.lock().unwrap().get(id).cloned()
+    }
+}
+
+fn not_found(id: u64) -> mcp::Error {
+    mcp::Error::invalid_params(format!("todo {id} does not exist"))
+}
+
+/// Reads each todo at `todo://{id}`
+struct TodoById;
+
+#[derive(Deserialize)]
+struct TodoParams {
+    id: u64,
+}
+
+impl TemplateSource<State> for TodoById {
+    type Params = TodoParams;
+
+    fn read(
+        &self,
+        state: State,
+        uri: String,
+        TodoParams { id }: TodoParams,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, mcp::Error>> + Send + 'static {
+        let todo = state.get(id);
+        async move {
+            let todo = todo.ok_or_else(|| {
+                mcp::Error::resource_not_found(format!("Resource at uri '{uri}' not found"))
+            })?;
+            Ok(vec![ResourceContents::Text(
+                mcp_schema::TextResourceContents {
+                    uri,
+                    mime_type: Some("application/json".to_string()),
+                    text: serde_json::to_string_pretty(&todo)?,
+                },
+            )])
+        }
+    }
+
+    fn wait_for_change(
+        &self,
+        state: State,
+        _: String,
+        TodoParams { id }: TodoParams,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let before = state.get(id);
+        async move {
+            // Other todos changing doesn't change this one
+            loop {
+                state.change.notified().await;
+                if state.get(id) != before {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn list(&self, state: State) -> Vec<String> {
+        let todos = state.todos.lock().unwrap();
+        todos
+            .todos
+            .iter()
+            .map(|todo| format!("todo://{}", todo.id))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct AddParams {
+    /// What needs to be done
+    title: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct IdParams {
+    id: u64,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct ListParams {
+    /// Also list todos that are done
+    include_done: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct PlanParams {
+    focus: Option<String>,
+}
+
+async fn add(state: State, params: AddParams) -> Result<Json<Todo>, mcp::Error> {
+    state.update(|todos| {
+        let todo = Todo {
+            id: todos.next_id,
+            title: params.title,
+            done: false,
+        };
+        todos.next_id += 1;
+        todos.todos.push(todo.clone());
+        Ok(Json(todo))
+    })
+}
+
+async fn complete(state: State, params: IdParams) -> Result<Json<Todo>, mcp::Error> {
+    state.update(|todos| {
+        let todo = todos
+            .todos
+            .iter_mut()
+            .find(|todo| todo.id == params.id)
+            .ok_or_else(|| not_found(params.id))?;
+        todo.done = true;
+        Ok(Json(todo.clone()))
+    })
+}
+
+async fn remove(state: State, params: IdParams) -> Result<String, mcp::Error> {
+    state.update(|todos| {
+        let index = todos
+            .todos
+            .iter()
+            .position(|todo| todo.id == params.id)
+            .ok_or_else(|| not_found(params.id))?;
+        let todo = todos.todos.remove(index);
+        Ok(format!("Removed '{}'", todo.title))
+    })
+}
+
+async fn list(state: State, params: ListParams) -> Result<Json<Vec<Todo>>, mcp::Error> {
+    let include_done = params.include_done.unwrap_or(false);
+    let todos = state.todos.lock().unwrap();
+    Ok(Json(
+        todos
+            .todos
+            .iter()
+            .filter(|todo| include_done || !todo.done)
+            .cloned()
+            .collect(),
+    ))
+}
+
+async fn plan(
+    state: State,
+    params: PlanParams,
+) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    let open: Vec<_> = state
+        .todos
+        .lock()
+        .unwrap()
+        .todos
+        .iter()
+        .filter(|todo| !todo.done)
+        .map(|todo| format!("- [{}] {}", todo.id, todo.title))
+        .collect();
+
+    let focus = params
+        .focus
+        .map(|focus| format!(" Prioritize anything related to {focus}."))
+        .unwrap_or_default();
+
+    Ok(vec![mcp_schema::PromptMessage {
+        role: mcp_schema::Role::User,
+        content: mcp_schema::PromptContent::Text(mcp_schema::TextContent {
+            kind: "text".to_string(),
+            text: format!(
+                "Here are my open todos:\n{}\n\nPlan my day around them.{focus}",
+                open.join("\n")
+            ),
+            annotated: mcp_schema::Annotated {
+                annotations: None,
+                extra: HashMap::new(),
+            },
+        }),
+    }])
+}
+
+/// Builds the todo service with an empty list
+///
+/// # Errors
+/// If a tool, prompt or resource is invalid, this will error.
+pub fn service() -> Result<BasicService<State>, mcp::Error> {
+    let resource = MemoryResource::new();
+    let state = State {
+        todos: Arc::default(),
+        resource: resource.clone(),
+        change: Arc::default(),
+        notifier: Arc::default(),
+    };
+    // Publish the empty list so the resource can be read before the first change
+    state.update(|_| Ok(()))?;
+
+    let service = mcp::server! {
+        name: "todo",
+        version: "0.1.0",
+        instructions: "A todo list. Subscribe to todo://all or todo://{id} to follow changes.",
+        state: state.clone(),
+        tool {
+            name: "add_todo",
+            description: "Add a todo",
+            handler: add,
+        },
+        tool {
+            name: "complete_todo",
+            description: "Mark a todo as done",
+            handler: complete,
+        },
+        tool {
+            name: "remove_todo",
+            description: "Remove a todo",
+            handler: remove,
+        },
+        tool {
+            name: "list_todos",
+            description: "List todos",
+            handler: list,
+        },
+        prompt {
+            name: "plan_day",
+            description: "Plan the day around the open todos",
+            handler: plan,
+        },
+        resource {
+            name: "todos",
+            fixed_uri: TODOS_URI,
+            description: "Every todo as JSON",
+            mime_type: "application/json",
+            source: resource,
+        },
+    }?
+    .template_resource(
+        Resource::builder()
+            .name("todo")
+            .template_uri(TODO_TEMPLATE)
+            .description("A todo as JSON")
+            .mime_type("application/json")
+            .typed_source(TodoById)
+            .build()?,
+    );
+    let _ = state.notifier.set(service.notifier());
+
+    Ok(service)
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::unused_async)]
 
 use futures::future::pending;
-use mcp::Resource;
+use mcp::content::PromptMessagesBuilder;
 use mcp::resources::MemoryResource;
 use mcp_schema::ResourceContents;
 use rand::Rng;
@@ -85,22 +85,13 @@ async fn get_forecast_prompt(
     _state: Arc<std::sync::Mutex<State>>,
     params: ForecastPromptParams,
 ) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
-    Ok(vec![mcp_schema::PromptMessage {
-        role: mcp_schema::Role::Assistant,
-        content: mcp_schema::PromptContent::Text(mcp_schema::TextContent {
-            kind: "text".to_string(),
-            text: if let Some(city) = params.city {
-                format!("You are a meteorologist with access to weather forecasts from {city}.")
-            } else {
-                "You are a meteorologist with access to weather forecasts from any location"
-                    .to_string()
-            },
-            annotated: mcp_schema::Annotated {
-                annotations: None,
-                extra: HashMap::new(),
-            },
-        }),
-    }])
+    Ok(PromptMessagesBuilder::new()
+        .assistant(if let Some(city) = params.city {
+            format!("You are a meteorologist with access to weather forecasts from {city}.")
+        } else {
+            "You are a meteorologist with access to weather forecasts from any location".to_string()
+        })
+        .build())
 }
 
 #[tokio::main]
@@ -119,37 +110,43 @@ async fn main() -> eyre::Result<()> {
         history: Vec::new(),
     }));
 
-    let forecast_tool = mcp::Tool::builder()
-        .name("get_forecast")
-        .description("Get weather forecast for a location")
-        .handler(get_forecast)
-        .build()?;
-
-    let do_nothing_tool = mcp::Tool::builder()
-        .name("do_nothing")
-        .description("Do absolutely nothing")
-        .handler(do_nothing)
-        .build()?;
-
-    let forecast_prompt = mcp::Prompt::builder()
-        .name("forecast")
-        .description("Get the forecaster prompt")
-        .handler(get_forecast_prompt)
-        .build()?;
-
-    let resource = Resource::builder()
-        .name("history")
-        .fixed_uri("history://temperature")
-        .description("Temperature history")
-        .source(resource)
-        .build()?;
-
-    let service = mcp::BasicService::new()
-        .tool(forecast_tool)
-        .tool(do_nothing_tool)
-        .prompt(forecast_prompt)
-        .fixed_resource(resource)
-        .state(state);
+    let service = mcp::server! {
+        name: "weather",
+        version: "0.1.0",
+        instructions: "Weather forecasts and a history of forecasted temperatures",
+        state: state,
+        tool {
+            name: "get_forecast",
+            description: "Get weather forecast for a location",
+            handler: get_forecast,
+        },
+        tool {
+            name: "do_nothing",
+            description: "Do absolutely nothing",
+            handler: do_nothing,
+        },
+        prompt {
+            name: "forecast",
+            description: "Get the forecaster prompt",
+            handler: get_forecast_prompt,
+        },
+        resource {
+            name: "history",
+            fixed_uri: "history://temperature",
+            description: "Temperature history",
+            source: resource,
+        },
+    }?;
+
+    let report = service.self_check().await;
+    if !report.is_ok() {
+        eyre::bail!("self check failed: {report}");
+    }
+    // With `--check`, only validate the service, such as in CI
+    if std::env::args().any(|arg| arg == "--check") {
+        eprintln!("{report}");
+        return Ok(());
+    }
 
     if USE_STDIO {
         mcp::serve_over_stdio(service).await?;
@@ -1,42 +1,35 @@
-pub mod basic_service;
-pub mod error;
-pub mod registry;
-pub mod resources;
-pub mod rpc;
-pub mod service;
+//! A framework for Model Context Protocol servers.
+//!
+//! This crate gathers the workspace's crates behind one dependency:
+//!
+//! - `mcp-core`: errors and the content of tool results and prompts
+//! - `mcp-server`: services, registries and the stdio and SSE transports
+//! - `mcp-macros`: procedural macros, whose generated code refers to paths of this crate
+//!
+//! Everything is re-exported at the same paths, so `mcp::Tool`, `mcp::content::text` and
+//! `mcp::server!` work the same whichever crate defines them.
 
-use axum::Router;
-use axum::routing::{get, post};
-pub use basic_service::BasicService;
-pub use error::Error;
-pub use registry::{Prompt, PromptRegistry, Resource, ResourceRegistry, Tool, ToolRegistry};
-pub use rpc::McpImpl;
-pub use service::Service;
-use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+pub use mcp_core::{Error, ErrorCode, content, error};
+pub use mcp_macros::*;
+pub use mcp_server::*;
 
-/// # Errors
-/// An error will occur if an I/O error occurs in stdio or stdin.
-pub async fn serve_over_stdio<S: Service + Send + Sync + 'static>(
-    service: S,
-) -> std::io::Result<()> {
-    let service = Arc::new(McpImpl::new(service));
-    service.serve_over_stdio().await
+/// Crates the code generated by the macros refers to, so users don't need to depend on them
+#[doc(hidden)]
+pub mod __private {
+    pub use mcp_schema;
+    pub use schemars;
+    pub use serde;
+    pub use serde_json;
 }
 
-/// # Errors
-/// An error will occur if an I/O error occurs in the network.
-pub async fn serve_over_sse<S: Service + Send + Sync + 'static>(
-    listener: tokio::net::TcpListener,
-    service: S,
-) -> std::io::Result<()> {
-    let service = Arc::new(McpImpl::new(service));
+pub mod prelude {
+    //! The supported public surface of this crate. Anything not re-exported here or documented
+    //! in the crate root may change between minor releases.
+    //!
+    //! ```ignore
+    //! use mcp::prelude::*;
+    //! ```
 
-    let app = Router::new()
-        .route("/api/message", post(McpImpl::message_handler))
-        .route("/api/events", get(McpImpl::sse_handler))
-        .layer(CorsLayer::permissive())
-        .with_state(service);
-
-    axum::serve(listener, app).await
+    pub use mcp_macros::{IntoContents, tool};
+    pub use mcp_server::prelude::*;
 }
@@ -1,3 +0,0 @@
-pub mod memory;
-
-pub use memory::MemoryResource;
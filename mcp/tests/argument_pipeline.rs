@@ -0,0 +1,74 @@
+//! The order in which tool arguments are coerced, validated and transformed.
+
+use mcp::registry::HandlerArgs;
+use mcp::{ErrorCode, Tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Deserialize, JsonSchema)]
+struct RepeatParams {
+    text: String,
+    times: usize,
+}
+
+async fn repeat((): (), params: RepeatParams) -> Result<String, mcp::Error> {
+    Ok(params.text.repeat(params.times))
+}
+
+/// A registry whose transformer doubles `times`, counting how often it ran
+fn registry(transformed: Arc<AtomicUsize>) -> ToolRegistry<()> {
+    let double = move |_: &(), mut args: HandlerArgs| -> Result<HandlerArgs, mcp::Error> {
+        transformed.fetch_add(1, Ordering::Relaxed);
+        let times = args["times"]
+            .as_u64()
+            .ok_or_else(|| mcp::Error::internal("times was not coerced"))?;
+        args.insert("times".to_string(), json!(times * 2));
+        Ok(args)
+    };
+    let mut registry = ToolRegistry::new();
+    registry.register(
+        Tool::builder()
+            .name("repeat")
+            .handler(repeat)
+            .coerce_arguments(true)
+            .transform(double)
+            .build()
+            .unwrap(),
+    );
+    registry
+}
+
+fn call(arguments: serde_json::Value) -> mcp_schema::CallToolParams {
+    serde_json::from_value(json!({ "name": "repeat", "arguments": arguments })).unwrap()
+}
+
+#[tokio::test]
+async fn transforms_coerced_arguments() {
+    let transformed = Arc::new(AtomicUsize::new(0));
+    let result = registry(transformed.clone())
+        .call_tool((), call(json!({ "text": "ab", "times": "2" })))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "abababab"
+    );
+    assert_eq!(transformed.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn validates_before_transforming() {
+    let transformed = Arc::new(AtomicUsize::new(0));
+    let error = registry(transformed.clone())
+        .call_tool((), call(json!({ "text": "ab", "times": "many" })))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.data.unwrap()["violations"][0]["pointer"], "/times");
+    assert_eq!(transformed.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,146 @@
+//! Checking arguments given as a variant of a `oneOf` or `anyOf` other than the first.
+
+use mcp::{ErrorCode, Tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Shape {
+    Circle { radius: i64 },
+    Square { side: i64 },
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum Owner {
+    User { user: String },
+    Team { team: String, members: i64 },
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct DrawParams {
+    shape: Shape,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct AssignParams {
+    owner: Owner,
+}
+
+async fn draw((): (), params: DrawParams) -> Result<String, mcp::Error> {
+    Ok(match params.shape {
+        Shape::Circle { radius } => format!("circle of radius {radius}"),
+        Shape::Square { side } => format!("square of side {side}"),
+    })
+}
+
+async fn assign((): (), params: AssignParams) -> Result<String, mcp::Error> {
+    Ok(match params.owner {
+        Owner::User { user } => user,
+        Owner::Team { team, members } => format!("{team} ({members})"),
+    })
+}
+
+fn registry() -> ToolRegistry<()> {
+    let mut registry = ToolRegistry::new();
+    registry.register(Tool::builder().name("draw").handler(draw).build().unwrap());
+    registry.register(
+        Tool::builder()
+            .name("assign")
+            .handler(assign)
+            .coerce_arguments(true)
+            .build()
+            .unwrap(),
+    );
+    registry
+}
+
+fn call(name: &str, arguments: serde_json::Value) -> mcp_schema::CallToolParams {
+    serde_json::from_value(json!({ "name": name, "arguments": arguments })).unwrap()
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[tokio::test]
+async fn accepts_a_later_tagged_variant() {
+    let result = registry()
+        .call_tool(
+            (),
+            call("draw", json!({ "shape": { "kind": "square", "side": 2 } })),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "square of side 2");
+}
+
+#[tokio::test]
+async fn repairs_against_the_variant_of_the_tag() {
+    let error = registry()
+        .call_tool(
+            (),
+            call(
+                "draw",
+                json!({ "shape": { "kind": "square", "side": "wide" } }),
+            ),
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    let data = error.data.unwrap();
+    assert_eq!(data["violations"].as_array().unwrap().len(), 1);
+    assert_eq!(data["violations"][0]["pointer"], "/shape/side");
+    assert_eq!(
+        data["suggestion"]["shape"],
+        json!({ "kind": "square", "side": 0 })
+    );
+}
+
+#[tokio::test]
+async fn accepts_a_later_untagged_variant() {
+    let result = registry()
+        .call_tool(
+            (),
+            call(
+                "assign",
+                json!({ "owner": { "team": "core", "members": 3 } }),
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "core (3)");
+}
+
+#[tokio::test]
+async fn coerces_into_a_later_untagged_variant() {
+    let result = registry()
+        .call_tool(
+            (),
+            call(
+                "assign",
+                json!({ "owner": { "team": "core", "members": "3" } }),
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(text(&result), "core (3)");
+}
+
+#[tokio::test]
+async fn rejects_values_that_fit_no_variant() {
+    let error = registry()
+        .call_tool((), call("assign", json!({ "owner": { "group": "core" } })))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    let data = error.data.unwrap();
+    assert_eq!(data["violations"][0]["pointer"], "/owner");
+}
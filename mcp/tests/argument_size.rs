@@ -0,0 +1,54 @@
+//! Rejecting tool arguments larger than the tool's `max_argument_size`.
+
+use mcp::{BasicService, ErrorCode, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Deserialize, JsonSchema)]
+struct Note {
+    text: String,
+}
+
+/// Counts the calls that reached the handler
+async fn save(calls: Arc<AtomicUsize>, note: Note) -> Result<String, mcp::Error> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    Ok(format!("Saved {} bytes", note.text.len()))
+}
+
+fn service(calls: Arc<AtomicUsize>) -> BasicService<Arc<AtomicUsize>> {
+    BasicService::new(calls).tool(
+        Tool::builder()
+            .name("save")
+            .handler(save)
+            .max_argument_size(64)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn rejects_oversized_arguments_before_the_handler_runs() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut client = mcp::testing::harness(service(calls.clone())).await.unwrap();
+
+    let error = client
+        .call_tool("save", json!({ "text": "x".repeat(100) }))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert_eq!(
+        error.message,
+        "Arguments of 111 bytes exceed the limit of 64 bytes for tool 'save'"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let result = client
+        .call_tool("save", json!({ "text": "short" }))
+        .await
+        .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,79 @@
+//! Rejecting requests without a valid bearer token.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use mcp::auth::BearerAuth;
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Arc::new(McpImpl::new(BasicService::new(())))
+        .into_router()
+        .layer(BearerAuth::token("secret"))
+}
+
+fn authorized(
+    request: axum::http::request::Builder,
+    authorization: Option<&str>,
+) -> axum::http::request::Builder {
+    match authorization {
+        Some(authorization) => request.header(header::AUTHORIZATION, authorization),
+        None => request,
+    }
+}
+
+async fn post_message(authorization: Option<&str>) -> StatusCode {
+    let request = authorized(Request::post("/message"), authorization)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }).to_string(),
+        ))
+        .unwrap();
+    app().oneshot(request).await.unwrap().status()
+}
+
+async fn open_events(authorization: Option<&str>) -> StatusCode {
+    let request = authorized(Request::get("/events"), authorization)
+        .body(Body::empty())
+        .unwrap();
+    app().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn rejects_messages_without_a_valid_token() {
+    assert_eq!(post_message(None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        post_message(Some("Bearer guess")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        post_message(Some("Basic secret")).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn accepts_messages_with_the_token() {
+    assert_eq!(post_message(Some("Bearer secret")).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_event_streams_without_a_valid_token() {
+    let request = Request::get("/events").body(Body::empty()).unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+    assert_eq!(
+        open_events(Some("Bearer guess")).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn accepts_event_streams_with_the_token() {
+    assert_eq!(open_events(Some("Bearer secret")).await, StatusCode::OK);
+}
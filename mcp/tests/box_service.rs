@@ -0,0 +1,69 @@
+//! Serving services of different types chosen at runtime through `BoxService`.
+
+use mcp::{BasicService, BoxService, DynService, ServiceRouter, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn whoami(name: &'static str, _: Empty) -> Result<String, mcp::Error> {
+    Ok(name.to_string())
+}
+
+fn basic(name: &'static str) -> BasicService<&'static str> {
+    BasicService::new(name).tool(
+        Tool::builder()
+            .name("whoami")
+            .handler(whoami)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[tokio::test]
+async fn serves_boxed_services_of_different_types() {
+    let services: Vec<Box<dyn DynService>> = vec![
+        Box::new(basic("basic")),
+        Box::new(ServiceRouter::new().mount("routed", basic("router"))),
+    ];
+    let mut services = services.into_iter().map(BoxService::from);
+
+    let mut client = mcp::testing::harness(services.next().unwrap())
+        .await
+        .unwrap();
+    let result = client.call_tool("whoami", json!({})).await.unwrap();
+    assert_eq!(text(&result), "basic");
+
+    let mut client = mcp::testing::harness(services.next().unwrap())
+        .await
+        .unwrap();
+    let tools = client.list_tools().await.unwrap();
+    let name = tools.tools[0].name.clone();
+    let result = client.call_tool(&name, json!({})).await.unwrap();
+    assert_eq!(text(&result), "router");
+}
+
+#[tokio::test]
+async fn forwards_notifications_of_boxed_services() {
+    let service = basic("basic");
+    let logger = service.logger("boxed");
+    let mut client = mcp::testing::harness(BoxService::new(service))
+        .await
+        .unwrap();
+
+    logger.info("hello");
+    let notification = client
+        .next_notification(Duration::from_secs(1))
+        .await
+        .unwrap();
+    let notification = serde_json::to_value(notification).unwrap();
+    assert_eq!(notification["method"], "notifications/message");
+    assert_eq!(notification["params"]["data"], "hello");
+}
@@ -0,0 +1,61 @@
+//! Reading binary resources held by a `BytesResource` over the in-memory harness.
+
+use base64::Engine;
+use mcp::resources::BytesResource;
+use mcp::{BasicService, Resource};
+use mcp_schema::ResourceContents;
+
+const URI: &str = "memory://logo.png";
+
+/// Some bytes that aren't valid UTF-8, as in an actual PNG
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff];
+
+fn service(bytes: BytesResource) -> BasicService<()> {
+    BasicService::new(()).fixed_resource(
+        Resource::builder()
+            .name("logo")
+            .fixed_uri(URI)
+            .source(bytes)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn round_trips_the_blob_and_mime_type() {
+    let bytes = BytesResource::new("image/png");
+    bytes.set(PNG).unwrap();
+    let mut client = mcp::testing::harness(service(bytes)).await.unwrap();
+
+    let result = client.read_resource(URI).await.unwrap();
+    let [ResourceContents::Blob(contents)] = &result.contents[..] else {
+        panic!("expected one blob contents");
+    };
+    assert_eq!(contents.uri, URI);
+    assert_eq!(contents.mime_type.as_deref(), Some("image/png"));
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(&contents.blob)
+        .unwrap();
+    assert_eq!(decoded, PNG);
+}
+
+#[tokio::test]
+async fn reads_nothing_until_bytes_are_set() {
+    let bytes = BytesResource::new("image/png");
+    let mut client = mcp::testing::harness(service(bytes.clone())).await.unwrap();
+
+    let result = client.read_resource(URI).await.unwrap();
+    assert!(result.contents.is_empty());
+
+    bytes.set(PNG).unwrap();
+    bytes.clear();
+    let result = client.read_resource(URI).await.unwrap();
+    assert!(result.contents.is_empty());
+}
+
+#[test]
+fn rejects_bytes_over_the_maximum_size() {
+    let bytes = BytesResource::with_max_size("application/pdf", 4);
+    bytes.set(b"1234").unwrap();
+    assert!(bytes.set(b"12345").is_err());
+}
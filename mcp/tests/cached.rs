@@ -0,0 +1,83 @@
+//! Reading through a `CachedSource` within and past its time to live.
+
+use mcp::registry::resource::Source;
+use mcp::resources::CachedSource;
+use mcp_schema::ResourceContents;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+const URI: &str = "db://report";
+const TTL: Duration = Duration::from_secs(60);
+
+/// Counts its reads and returns the count as the text, so a cached read returns an older count
+#[derive(Clone, Default)]
+struct Counting {
+    reads: Arc<AtomicUsize>,
+}
+
+impl Source<()> for Counting {
+    fn read(
+        &self,
+        (): (),
+        uri: String,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, mcp::Error>> + 'static + Send {
+        let reads = self.reads.fetch_add(1, Ordering::SeqCst) + 1;
+        std::future::ready(Ok(vec![ResourceContents::Text(
+            mcp_schema::TextResourceContents {
+                uri,
+                mime_type: None,
+                text: reads.to_string(),
+            },
+        )]))
+    }
+
+    fn wait_for_change(&self, (): (), _uri: String) -> impl Future<Output = ()> + 'static + Send {
+        std::future::pending()
+    }
+}
+
+/// Reads the uri and returns the text of its contents
+async fn read(cached: &CachedSource<Counting>) -> String {
+    let contents = Source::<()>::read(cached, (), URI.to_string())
+        .await
+        .unwrap();
+    match &contents[..] {
+        [ResourceContents::Text(contents)] => contents.text.clone(),
+        _ => panic!("expected one text contents"),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn serves_from_the_cache_within_the_ttl() {
+    let source = Counting::default();
+    let cached = CachedSource::new(source.clone(), TTL);
+
+    assert_eq!(read(&cached).await, "1");
+    tokio::time::advance(TTL / 2).await;
+    assert_eq!(read(&cached).await, "1");
+    assert_eq!(source.reads.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn refetches_once_the_ttl_expires() {
+    let source = Counting::default();
+    let cached = CachedSource::new(source.clone(), TTL);
+
+    assert_eq!(read(&cached).await, "1");
+    tokio::time::advance(TTL).await;
+    assert_eq!(read(&cached).await, "2");
+    // The refetched contents are cached again
+    assert_eq!(read(&cached).await, "2");
+    assert_eq!(source.reads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn refetches_after_invalidation() {
+    let source = Counting::default();
+    let cached = CachedSource::new(source.clone(), TTL);
+
+    assert_eq!(read(&cached).await, "1");
+    cached.invalidate(URI);
+    assert_eq!(read(&cached).await, "2");
+}
@@ -0,0 +1,34 @@
+//! Shaping of `completion/complete` results with `total` and `hasMore`.
+
+use mcp::completion::CompletionResult;
+
+fn completion(result: CompletionResult) -> serde_json::Value {
+    serde_json::to_value(result.build().unwrap()).unwrap()["completion"].clone()
+}
+
+#[test]
+fn keeps_the_first_hundred_values() {
+    let completion = completion((0..150).map(|n| n.to_string()).collect::<Vec<_>>().into());
+
+    assert_eq!(completion["values"].as_array().unwrap().len(), 100);
+    assert_eq!(completion["values"][0], "0");
+    assert_eq!(completion["total"], 150);
+    assert_eq!(completion["hasMore"], true);
+}
+
+#[test]
+fn reports_values_the_provider_left_out() {
+    let completion = completion(CompletionResult::new().value("Paris").total(12));
+
+    assert_eq!(completion["values"], serde_json::json!(["Paris"]));
+    assert_eq!(completion["total"], 12);
+    assert_eq!(completion["hasMore"], true);
+}
+
+#[test]
+fn has_no_more_when_every_value_is_returned() {
+    let completion = completion(CompletionResult::new().values(["Paris", "Prague"]));
+
+    assert_eq!(completion["total"], 2);
+    assert_eq!(completion["hasMore"], false);
+}
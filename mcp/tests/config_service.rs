@@ -0,0 +1,135 @@
+//! Serving tools, prompts and resources declared in a configuration file.
+
+use mcp::Service;
+use mcp::config::ConfigService;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// A configuration file in the temporary directory, removed when dropped
+struct ConfigFile(PathBuf);
+
+impl ConfigFile {
+    fn new(name: &str, contents: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("mcp-{}-{name}", std::process::id()));
+        let file = Self(path);
+        file.write(contents);
+        file
+    }
+
+    fn write(&self, contents: &str) {
+        std::fs::write(&self.0, contents).unwrap();
+    }
+}
+
+impl Drop for ConfigFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn config(response: &str) -> String {
+    json!({
+        "tools": [{ "name": "greet", "response": response }],
+        "prompts": [{
+            "name": "review",
+            "arguments": [{ "name": "code", "required": true }],
+            "template": "Review {code}",
+        }],
+        "resources": [{ "uri": "file:///readme", "name": "readme", "text": "Read me" }],
+    })
+    .to_string()
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: serde_json::Value) -> T {
+    serde_json::from_value(params).unwrap()
+}
+
+#[tokio::test]
+async fn serves_the_loaded_configuration() {
+    let file = ConfigFile::new("loaded.json", &config("Hello {name}"));
+    let service = ConfigService::load(&file.0).unwrap();
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let result = client
+        .call_tool("greet", json!({ "name": "Ada" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "Hello Ada"
+    );
+
+    let arguments = HashMap::from([("code".to_string(), "main.rs".to_string())]);
+    let prompt = client.get_prompt("review", arguments).await.unwrap();
+    let messages = serde_json::to_value(&prompt.messages).unwrap();
+    assert_eq!(messages[0]["content"]["text"], "Review main.rs");
+
+    let resource = client.read_resource("file:///readme").await.unwrap();
+    let contents = serde_json::to_value(&resource.contents).unwrap();
+    assert_eq!(contents[0]["text"], "Read me");
+
+    let error = client.call_tool("wave", json!({})).await.unwrap_err();
+    assert_eq!(error.code, mcp::ErrorCode::InvalidParams);
+}
+
+#[test]
+fn rejects_invalid_configurations() {
+    let file = ConfigFile::new("syntax.json", "{ \"tools\": [");
+    assert!(ConfigService::load(&file.0).is_err());
+
+    let file = ConfigFile::new("missing.json", r#"{ "tools": [{ "name": "greet" }] }"#);
+    let error = ConfigService::load(&file.0).err().unwrap();
+    assert!(error.message.contains("response"), "{}", error.message);
+
+    let path = std::env::temp_dir().join(format!("mcp-{}-absent.json", std::process::id()));
+    assert!(ConfigService::load(path).is_err());
+}
+
+#[tokio::test]
+async fn reloads_and_notifies_of_changes() {
+    let file = ConfigFile::new("reloaded.json", &config("Hello"));
+    let mut service = ConfigService::load(&file.0).unwrap();
+    let notifications = Arc::new(Mutex::new(Vec::new()));
+    let sent = notifications.clone();
+    service.set_notification_handler(Box::new(move |notification| {
+        let notification = serde_json::to_value(notification).unwrap();
+        sent.lock().unwrap().push(notification["method"].clone());
+    }));
+
+    file.write(&config("Goodbye"));
+    service.reload().unwrap();
+
+    let result = service
+        .call_tool(params(json!({ "name": "greet" })))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "Goodbye"
+    );
+    assert_eq!(
+        *notifications.lock().unwrap(),
+        ["notifications/tools/list_changed"]
+    );
+}
+
+#[tokio::test]
+async fn keeps_the_previous_configuration_when_reloading_fails() {
+    let file = ConfigFile::new("broken.json", &config("Hello"));
+    let service = ConfigService::load(&file.0).unwrap();
+
+    file.write("not json");
+    assert!(service.reload().is_err());
+
+    let result = service
+        .call_tool(params(json!({ "name": "greet" })))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "Hello"
+    );
+    assert_eq!(service.config().resources.len(), 1);
+}
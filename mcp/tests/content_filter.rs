@@ -0,0 +1,94 @@
+//! Applying content filters to both the content and structured content of tool results.
+
+use mcp::content::Json;
+use mcp::filter::RegexScrubber;
+use mcp::{BasicService, Tool};
+use mcp_schema::PromptContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+struct LookupParams {}
+
+#[derive(Serialize)]
+struct Contact {
+    name: String,
+    email: String,
+}
+
+async fn lookup((): (), _: LookupParams) -> Result<Json<Contact>, mcp::Error> {
+    Ok(Json(Contact {
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+    }))
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(()).tool(
+        Tool::builder()
+            .name("lookup")
+            .handler(lookup)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn structured(result: &mcp_schema::CallToolResult) -> Option<serde_json::Value> {
+    serde_json::to_value(result)
+        .unwrap()
+        .get("structuredContent")
+        .cloned()
+}
+
+#[tokio::test]
+async fn keeps_structured_content_without_filters() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result = client.call_tool("lookup", json!({})).await.unwrap();
+    assert_eq!(
+        structured(&result),
+        Some(json!({ "name": "Ada", "email": "ada@example.com" }))
+    );
+}
+
+#[tokio::test]
+async fn scrubs_structured_content() {
+    let service = service().content_filter(RegexScrubber::emails());
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let result = client.call_tool("lookup", json!({})).await.unwrap();
+    let content = serde_json::to_value(&result.content).unwrap();
+    assert!(!content[0]["text"].as_str().unwrap().contains("ada@"));
+    let structured = structured(&result).unwrap();
+    assert_eq!(structured["name"], "Ada");
+    assert_eq!(structured["email"], "[email]");
+}
+
+#[tokio::test]
+async fn drops_structured_content_the_filters_remove() {
+    let service = service().content_filter(|content: PromptContent| match content {
+        PromptContent::Text(_) => None,
+        content => Some(content),
+    });
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let result = client.call_tool("lookup", json!({})).await.unwrap();
+    assert!(result.content.is_empty());
+    assert_eq!(structured(&result), None);
+}
+
+#[tokio::test]
+async fn drops_structured_content_the_filters_leave_invalid() {
+    let service = service().content_filter(|content: PromptContent| match content {
+        PromptContent::Text(mut text) => {
+            text.text.truncate(4);
+            Some(PromptContent::Text(text))
+        }
+        content => Some(content),
+    });
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let result = client.call_tool("lookup", json!({})).await.unwrap();
+    assert_eq!(structured(&result), None);
+}
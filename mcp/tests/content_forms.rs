@@ -0,0 +1,88 @@
+//! Converting content to the forms each session's client declared it can render.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header};
+use mcp::{BasicService, McpImpl, Tool};
+use mcp_schema::PromptContent;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[derive(Deserialize, JsonSchema)]
+struct ChartParams {}
+
+async fn chart((): (), _: ChartParams) -> Result<PromptContent, mcp::Error> {
+    Ok(mcp::content::image(b"chart", "image/png"))
+}
+
+async fn post(app: &Router, session: &str, message: serde_json::Value) -> serde_json::Value {
+    let request = Request::post(format!("/message?sessionId={session}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(message.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn initialize(app: &Router, session: &str, capabilities: serde_json::Value) {
+    post(
+        app,
+        session,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": mcp_schema::LATEST_PROTOCOL_VERSION,
+                "capabilities": capabilities,
+                "clientInfo": { "name": session, "version": "1.0.0" },
+            },
+        }),
+    )
+    .await;
+}
+
+async fn chart_kind(app: &Router, session: &str) -> serde_json::Value {
+    let response = post(
+        app,
+        session,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "chart", "arguments": {} },
+        }),
+    )
+    .await;
+    response["result"]["content"][0]["type"].clone()
+}
+
+#[tokio::test]
+async fn converts_content_per_session() {
+    let service = BasicService::new(()).tool(
+        Tool::builder()
+            .name("chart")
+            .handler(chart)
+            .build()
+            .unwrap(),
+    );
+    let app = Arc::new(McpImpl::new(service)).into_router();
+
+    initialize(
+        &app,
+        "text-only",
+        json!({ "experimental": { "contentForms": { "images": false } } }),
+    )
+    .await;
+    initialize(&app, "rich", json!({})).await;
+
+    assert_eq!(chart_kind(&app, "text-only").await, "text");
+    assert_eq!(chart_kind(&app, "rich").await, "image");
+    assert_eq!(chart_kind(&app, "text-only").await, "text");
+}
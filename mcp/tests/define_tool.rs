@@ -0,0 +1,88 @@
+//! Building tools from inline handlers that capture variables.
+
+use mcp::ToolRegistry;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Deserialize, JsonSchema)]
+struct SearchInput {
+    query: String,
+}
+
+/// Stands in for a client of an external service, which isn't `Copy`
+#[derive(Clone)]
+struct SearchClient {
+    api_key: Arc<str>,
+}
+
+impl SearchClient {
+    fn search(&self, query: &str) -> String {
+        format!("{query} with {}", self.api_key)
+    }
+}
+
+fn search() -> mcp_schema::CallToolParams {
+    serde_json::from_value(json!({ "name": "search", "arguments": { "query": "rust" } })).unwrap()
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[tokio::test]
+async fn keeps_the_given_state() {
+    let client = SearchClient {
+        api_key: "secret".into(),
+    };
+    let mut registry = ToolRegistry::<u32>::new();
+    registry.register(
+        mcp::define_tool! {
+            name: "search",
+            description: "Searches the web",
+            state: client,
+            handler: |client: SearchClient, input: SearchInput| async move {
+                Ok::<_, mcp::Error>(client.search(&input.query))
+            },
+        }
+        .unwrap(),
+    );
+
+    let result = registry.call_tool(7, search()).await.unwrap();
+    assert_eq!(text(&result), "rust with secret");
+}
+
+#[tokio::test]
+async fn captures_variables_alongside_the_service_state() {
+    let prefix = String::from("Results for");
+    let mut registry = ToolRegistry::<u32>::new();
+    registry.register(
+        mcp::define_tool! {
+            name: "search",
+            handler: move |limit: u32, input: SearchInput| {
+                let text = format!("{prefix} {} (at most {limit})", input.query);
+                async move { Ok::<_, mcp::Error>(text) }
+            },
+        }
+        .unwrap(),
+    );
+
+    let result = registry.call_tool(7, search()).await.unwrap();
+    assert_eq!(text(&result), "Results for rust (at most 7)");
+}
+
+#[test]
+fn advertises_the_output_schema() {
+    let tool: mcp::Tool<u32> = mcp::define_tool! {
+        name: "search",
+        output: String,
+        handler: |_: u32, input: SearchInput| async move {
+            Ok::<_, mcp::Error>(input.query)
+        },
+    }
+    .unwrap();
+    let tool = serde_json::to_value(mcp_schema::Tool::try_from(&tool).unwrap()).unwrap();
+
+    assert_eq!(tool["outputSchema"]["type"], "string");
+}
@@ -0,0 +1,71 @@
+//! Deriving the conversion of tool results into content.
+
+use mcp::IntoContents;
+use mcp::content::IntoContents as _;
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Serialize, IntoContents)]
+struct Forecast {
+    #[contents(text)]
+    summary: String,
+    temperature: f64,
+}
+
+#[derive(Serialize, IntoContents)]
+#[contents(unstructured)]
+struct Summary(#[contents(text)] u32, &'static str);
+
+#[derive(Serialize, IntoContents)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Deployment<T> {
+    Running { version: T },
+    Stopped,
+}
+
+fn texts(contents: &mcp::content::Contents) -> Vec<serde_json::Value> {
+    contents
+        .content
+        .iter()
+        .map(|content| serde_json::to_value(content).unwrap()["text"].clone())
+        .collect()
+}
+
+#[test]
+fn sends_marked_fields_as_text_before_the_json() {
+    let contents = Forecast {
+        summary: "Sunny".to_string(),
+        temperature: 21.5,
+    }
+    .into_contents()
+    .unwrap();
+
+    let structured = json!({ "summary": "Sunny", "temperature": 21.5 });
+    let json = serde_json::to_string_pretty(&structured).unwrap();
+    assert_eq!(texts(&contents), [json!("Sunny"), json!(json)]);
+    assert_eq!(contents.structured, Some(structured));
+}
+
+#[test]
+fn leaves_out_structured_content_when_unstructured() {
+    let contents = Summary(3, "issues").into_contents().unwrap();
+
+    assert_eq!(texts(&contents)[0], "3");
+    assert_eq!(contents.content.len(), 2);
+    assert_eq!(contents.structured, None);
+}
+
+#[test]
+fn converts_generic_enums() {
+    let contents = Deployment::Running { version: "1.2.0" }
+        .into_contents()
+        .unwrap();
+    assert_eq!(
+        contents.structured,
+        Some(json!({ "status": "running", "version": "1.2.0" }))
+    );
+
+    let contents = Deployment::<()>::Stopped.into_contents().unwrap();
+    assert_eq!(contents.structured, Some(json!({ "status": "stopped" })));
+    assert_eq!(contents.content.len(), 1);
+}
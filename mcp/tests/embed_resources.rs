@@ -0,0 +1,93 @@
+//! Embedding registered resources into prompt messages with `embed`.
+
+use mcp::content::{PromptMessagesBuilder, embed};
+use mcp::registry::resource::FixedResourceUri;
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, ErrorCode, Prompt, Resource};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+const NOTES: &str = "memory://notes";
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn review_notes((): (), _: Empty) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(PromptMessagesBuilder::new()
+        .user("Review these notes:")
+        .user_content(embed(NOTES))
+        .build())
+}
+
+async fn review_missing((): (), _: Empty) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(PromptMessagesBuilder::new()
+        .user_content(embed("memory://missing"))
+        .build())
+}
+
+fn service() -> BasicService<()> {
+    let notes = MemoryResource::new();
+    notes.set([serde_json::from_value(json!({
+        "uri": NOTES,
+        "mimeType": "text/markdown",
+        "text": "# Lighthouses",
+    }))
+    .unwrap()]);
+
+    BasicService::new(())
+        .prompt(
+            Prompt::builder()
+                .name("review_notes")
+                .handler(review_notes)
+                .build()
+                .unwrap(),
+        )
+        .prompt(
+            Prompt::builder()
+                .name("review_missing")
+                .handler(review_missing)
+                .build()
+                .unwrap(),
+        )
+        .fixed_resource(
+            Resource::<(), FixedResourceUri>::builder()
+                .name("notes")
+                .fixed_uri(NOTES)
+                .source(notes)
+                .build()
+                .unwrap(),
+        )
+}
+
+#[tokio::test]
+async fn embeds_the_contents_of_a_registered_resource() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result = client
+        .get_prompt("review_notes", HashMap::new())
+        .await
+        .unwrap();
+    let messages = serde_json::to_value(result.messages).unwrap();
+    assert_eq!(messages[0]["content"]["text"], "Review these notes:");
+    let embedded = &messages[1]["content"];
+    assert_eq!(embedded["type"], "resource");
+    assert_eq!(embedded["resource"]["uri"], NOTES);
+    assert_eq!(embedded["resource"]["mimeType"], "text/markdown");
+    assert_eq!(embedded["resource"]["text"], "# Lighthouses");
+    // The placeholder's marker isn't sent to the client
+    assert!(embedded.get("pendingEmbed").is_none());
+}
+
+#[tokio::test]
+async fn fails_to_embed_an_unknown_resource() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let error = client
+        .get_prompt("review_missing", HashMap::new())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::ResourceNotFound);
+    assert!(error.message.contains("memory://missing"));
+}
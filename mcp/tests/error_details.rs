@@ -0,0 +1,65 @@
+//! Sending the causes of errors to clients only when enabled.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header};
+use eyre::WrapErr;
+use mcp::{BasicService, McpImpl, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[derive(Deserialize, JsonSchema)]
+struct ReadParams {}
+
+async fn read((): (), _: ReadParams) -> Result<String, mcp::Error> {
+    Err(eyre::eyre!("Disk unplugged"))
+        .wrap_err("Failed to read the config")
+        .map_err(Into::into)
+}
+
+fn app(error_details: bool) -> Router {
+    let service =
+        BasicService::new(()).tool(Tool::builder().name("read").handler(read).build().unwrap());
+    Arc::new(McpImpl::new(service).error_details(error_details)).into_router()
+}
+
+async fn call(app: Router) -> serde_json::Value {
+    let message = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": { "name": "read", "arguments": {} },
+    });
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(message.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn reports_causes_of_tool_failures_when_enabled() {
+    let response = call(app(true)).await;
+
+    let result = &response["result"];
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["content"][0]["text"], "Failed to read the config");
+    let report = result["content"][1]["text"].as_str().unwrap();
+    assert!(report.contains("Disk unplugged"), "{report}");
+}
+
+#[tokio::test]
+async fn hides_causes_of_tool_failures_by_default() {
+    let response = call(app(false)).await;
+
+    let content = response["result"]["content"].as_array().unwrap();
+    assert_eq!(content.len(), 1);
+    assert!(!response.to_string().contains("Disk unplugged"));
+}
@@ -0,0 +1,116 @@
+//! Searching the tools, prompts and resources of a service with the built-in `find_capability`
+//! tool over the in-memory harness.
+
+use mcp::catalog::{FIND_CAPABILITY, FuzzyRanker};
+use mcp::registry::resource::FixedResourceUri;
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, Prompt, Resource, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+async fn no_messages((): (), _: Empty) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(Vec::new())
+}
+
+fn tool(name: &str, description: &str) -> Tool<()> {
+    Tool::builder()
+        .name(name)
+        .description(description)
+        .handler(noop)
+        .build()
+        .unwrap()
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(())
+        .catalog_search(FuzzyRanker)
+        .tool(tool("send_email", "Sends an email"))
+        .tool(tool("draft_email", "Drafts an email"))
+        .tool(tool("search_web", "Searches the web"))
+        .prompt(
+            Prompt::builder()
+                .name("summarize_inbox")
+                .description("Summarizes unread email")
+                .handler(no_messages)
+                .build()
+                .unwrap(),
+        )
+        .fixed_resource(
+            Resource::<(), FixedResourceUri>::builder()
+                .name("mailbox")
+                .description("Drafts and received email")
+                .fixed_uri("memory://mailbox")
+                .source(MemoryResource::new())
+                .build()
+                .unwrap(),
+        )
+}
+
+/// The kind and name of each capability found, best first
+async fn find(
+    client: &mut mcp::testing::McpClient,
+    arguments: serde_json::Value,
+) -> Vec<(String, String)> {
+    let result = client.call_tool(FIND_CAPABILITY, arguments).await.unwrap();
+    let text = serde_json::to_value(&result.content).unwrap()[0]["text"].clone();
+    let found: Vec<serde_json::Value> = serde_json::from_str(text.as_str().unwrap()).unwrap();
+    found
+        .iter()
+        .map(|capability| {
+            (
+                capability["kind"].as_str().unwrap().to_string(),
+                capability["name"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn found(kind: &str, name: &str) -> (String, String) {
+    (kind.to_string(), name.to_string())
+}
+
+#[tokio::test]
+async fn lists_the_search_tool() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let tools = client.list_tools().await.unwrap();
+    assert!(tools.tools.iter().any(|tool| tool.name == FIND_CAPABILITY));
+}
+
+#[tokio::test]
+async fn ranks_tools_prompts_and_resources_by_relevance() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let capabilities = find(&mut client, json!({ "query": "email draft" })).await;
+    assert_eq!(
+        capabilities,
+        [
+            found("tool", "draft_email"),
+            found("tool", "send_email"),
+            found("resource", "mailbox"),
+            found("prompt", "summarize_inbox"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn limits_the_number_of_results() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let capabilities = find(&mut client, json!({ "query": "email draft", "limit": 2 })).await;
+    assert_eq!(
+        capabilities,
+        [found("tool", "draft_email"), found("tool", "send_email")]
+    );
+
+    let capabilities = find(&mut client, json!({ "query": "weather" })).await;
+    assert!(capabilities.is_empty());
+}
@@ -0,0 +1,145 @@
+//! End-to-end calls through the in-memory test harness.
+
+use mcp::{BasicService, ErrorCode, Prompt, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+#[derive(Deserialize, JsonSchema)]
+struct AddParams {
+    a: i64,
+    b: i64,
+}
+
+async fn add((): (), params: AddParams) -> Result<String, mcp::Error> {
+    params
+        .a
+        .checked_add(params.b)
+        .map(|sum| sum.to_string())
+        .ok_or_else(|| eyre::eyre!("The sum overflows").into())
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct ExplainParams {
+    /// The expression to explain
+    expression: String,
+}
+
+async fn explain(
+    (): (),
+    params: ExplainParams,
+) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(mcp::content::PromptMessagesBuilder::new()
+        .user(format!("Explain how to work out {}", params.expression))
+        .build())
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(())
+        .name("calculator".to_string())
+        .tool(Tool::builder().name("add").handler(add).build().unwrap())
+        .prompt(
+            Prompt::builder()
+                .name("explain")
+                .handler(explain)
+                .build()
+                .unwrap(),
+        )
+}
+
+#[tokio::test]
+async fn lists_and_calls_tools() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let tools = client.list_tools().await.unwrap();
+    assert_eq!(tools.tools.len(), 1);
+    assert_eq!(tools.tools[0].name, "add");
+
+    let result = client
+        .call_tool("add", json!({ "a": 1, "b": 2 }))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "3"
+    );
+
+    let result = client
+        .call_tool("add", json!({ "a": i64::MAX, "b": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn lists_and_gets_prompts() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let prompts = client.list_prompts().await.unwrap();
+    assert_eq!(prompts.prompts.len(), 1);
+    assert_eq!(prompts.prompts[0].name, "explain");
+
+    let arguments = HashMap::from([("expression".to_string(), "1 + 2".to_string())]);
+    let prompt = client.get_prompt("explain", arguments).await.unwrap();
+    assert_eq!(
+        serde_json::to_value(&prompt.messages).unwrap()[0]["content"]["text"],
+        "Explain how to work out 1 + 2"
+    );
+
+    let error = client
+        .get_prompt("explain", HashMap::new())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn returns_protocol_errors_with_their_codes() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let error = client.call_tool("subtract", json!({})).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn receives_notifications() {
+    let service = service();
+    let logger = service.logger("calculator");
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    logger.info("ready");
+    let notification = client
+        .next_notification(Duration::from_secs(1))
+        .await
+        .unwrap();
+    let notification = serde_json::to_value(notification).unwrap();
+    assert_eq!(notification["method"], "notifications/message");
+    assert_eq!(notification["params"]["data"], "ready");
+}
+
+#[tokio::test]
+async fn tears_down_once_the_input_ends() {
+    let torn_down = Arc::new(AtomicBool::new(false));
+    let flag = torn_down.clone();
+    let service = BasicService::new(()).tool(
+        Tool::builder()
+            .name("add")
+            .handler(add)
+            .teardown(move |()| {
+                let flag = flag.clone();
+                async move { flag.store(true, Ordering::SeqCst) }
+            })
+            .build()
+            .unwrap(),
+    );
+    let client = mcp::testing::harness(service).await.unwrap();
+    assert!(!torn_down.load(Ordering::SeqCst));
+
+    client.close().await.unwrap();
+    assert!(torn_down.load(Ordering::SeqCst));
+}
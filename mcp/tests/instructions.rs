@@ -0,0 +1,53 @@
+//! Sending instructions to clients when they initialize.
+
+use mcp::{BasicService, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+fn tool(name: &str) -> Tool<()> {
+    Tool::builder().name(name).handler(noop).build().unwrap()
+}
+
+/// Lists the registered tools, so the instructions follow what is registered
+fn list_tools(service: &BasicService<()>) -> String {
+    let mut names: Vec<_> = service
+        .tool_registry()
+        .tools_iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    names.sort_unstable();
+    format!("Use these tools: {}", names.join(", "))
+}
+
+#[tokio::test]
+async fn sends_the_computed_instructions() {
+    let service = BasicService::new(())
+        .instructions("Static instructions")
+        .instructions_with(list_tools)
+        .tool(tool("search"))
+        .tool(tool("fetch"));
+    let client = mcp::testing::harness(service).await.unwrap();
+
+    assert_eq!(
+        client.initialize_result().instructions.as_deref(),
+        Some("Use these tools: fetch, search")
+    );
+}
+
+#[tokio::test]
+async fn sends_static_instructions() {
+    let service = BasicService::new(()).instructions("Be brief");
+    let client = mcp::testing::harness(service).await.unwrap();
+
+    assert_eq!(
+        client.initialize_result().instructions.as_deref(),
+        Some("Be brief")
+    );
+}
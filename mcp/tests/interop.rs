@@ -0,0 +1,110 @@
+//! Interoperability tests that drive the `todo` example over stdio with the official MCP
+//! Inspector CLI, asserting that the major flows of the reference client work against this
+//! server.
+//!
+//! These download the Inspector with `npx`, so they need Node.js and network access. Run with
+//! `cargo test --features interop-tests --test interop`.
+#![cfg(feature = "interop-tests")]
+
+use serde_json::Value;
+use std::process::{Command, Output};
+
+const INSPECTOR: &str = "@modelcontextprotocol/inspector";
+
+/// Runs one Inspector CLI method against a freshly started `todo` example
+fn run_inspector(args: &[&str]) -> Output {
+    Command::new("npx")
+        .args(["--yes", INSPECTOR, "--cli"])
+        .args([
+            "cargo",
+            "run",
+            "--quiet",
+            "--manifest-path",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+            "--example",
+            "todo",
+        ])
+        .args(args)
+        .output()
+        .expect("npx is not installed")
+}
+
+/// Runs one Inspector CLI method and parses its output
+fn inspect(args: &[&str]) -> Value {
+    let output = run_inspector(args);
+
+    assert!(
+        output.status.success(),
+        "inspector {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("inspector printed invalid JSON")
+}
+
+fn names(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .expect("expected a list")
+        .iter()
+        .filter_map(|item| item["name"].as_str())
+        .collect()
+}
+
+#[test]
+fn lists_tools() {
+    let tools = inspect(&["--method", "tools/list"]);
+    let names = names(&tools["tools"]);
+    for name in ["add_todo", "complete_todo", "remove_todo", "list_todos"] {
+        assert!(names.contains(&name), "{name} is not listed in {names:?}");
+    }
+    assert_eq!(tools["tools"][0]["inputSchema"]["type"], "object");
+}
+
+#[test]
+fn calls_tool() {
+    let result = inspect(&[
+        "--method",
+        "tools/call",
+        "--tool-name",
+        "add_todo",
+        "--tool-arg",
+        "title=interop",
+    ]);
+    assert_ne!(result["isError"], true, "{result}");
+    assert!(!result["content"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn rejects_invalid_tool_arguments() {
+    let result = run_inspector(&["--method", "tools/call", "--tool-name", "complete_todo"]);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        !result.status.success() || stdout.contains("error"),
+        "missing arguments were accepted: {stdout}{stderr}"
+    );
+}
+
+#[test]
+fn lists_and_reads_resources() {
+    let resources = inspect(&["--method", "resources/list"]);
+    let uris: Vec<_> = resources["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|resource| resource["uri"].as_str())
+        .collect();
+    assert!(uris.contains(&"todo://all"), "{uris:?}");
+
+    let read = inspect(&["--method", "resources/read", "--uri", "todo://all"]);
+    assert_eq!(read["contents"][0]["uri"], "todo://all");
+}
+
+#[test]
+fn lists_and_gets_prompts() {
+    let prompts = inspect(&["--method", "prompts/list"]);
+    assert!(names(&prompts["prompts"]).contains(&"plan_day"));
+
+    let prompt = inspect(&["--method", "prompts/get", "--prompt-name", "plan_day"]);
+    assert!(!prompt["messages"].as_array().unwrap().is_empty());
+}
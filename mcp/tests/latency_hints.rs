@@ -0,0 +1,58 @@
+//! Listing the latency of each tool's recent calls in its `_meta.latency`.
+
+use mcp::{BasicService, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+const DELAY: Duration = Duration::from_millis(20);
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn slow((): (), _: Empty) -> Result<String, mcp::Error> {
+    tokio::time::sleep(DELAY).await;
+    Ok(String::new())
+}
+
+fn service(hints: bool) -> BasicService<()> {
+    BasicService::new(())
+        .latency_hints(hints)
+        .tool(Tool::builder().name("slow").handler(slow).build().unwrap())
+}
+
+/// The `_meta` of the listed tool
+async fn meta(client: &mut mcp::testing::McpClient) -> serde_json::Value {
+    let tools = client.list_tools().await.unwrap();
+    serde_json::to_value(&tools.tools[0]).unwrap()["_meta"].clone()
+}
+
+#[tokio::test]
+async fn lists_the_latency_of_recent_calls() {
+    let mut client = mcp::testing::harness(service(true)).await.unwrap();
+
+    // Tools that haven't been called have no latency yet
+    let before = meta(&mut client).await;
+    assert!(before.get("latency").is_none());
+
+    for _ in 0..3 {
+        client.call_tool("slow", json!({})).await.unwrap();
+    }
+    let after = meta(&mut client).await;
+    let latency = &after["latency"];
+    assert_eq!(latency["samples"], 3);
+    let delay = u64::try_from(DELAY.as_millis()).unwrap();
+    for percentile in ["p50Ms", "p95Ms", "p99Ms"] {
+        assert!(latency[percentile].as_u64().unwrap() >= delay, "{latency}");
+    }
+    assert_eq!(after["schemaHash"], before["schemaHash"]);
+}
+
+#[tokio::test]
+async fn leaves_out_latency_unless_enabled() {
+    let mut client = mcp::testing::harness(service(false)).await.unwrap();
+
+    client.call_tool("slow", json!({})).await.unwrap();
+    assert!(meta(&mut client).await.get("latency").is_none());
+}
@@ -0,0 +1,91 @@
+//! Listing tools without their schemas and fetching the schemas later as resources.
+
+use mcp::{BasicService, Tool};
+use mcp_schema::ResourceContents;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct WeatherParams {
+    /// The city to get the weather of
+    city: String,
+}
+
+async fn weather((): (), params: WeatherParams) -> Result<String, mcp::Error> {
+    Ok(format!("Rainy in {}", params.city))
+}
+
+fn service(lazy: bool) -> BasicService<()> {
+    BasicService::new(()).lazy_schemas(lazy).tool(
+        Tool::builder()
+            .name("weather")
+            .handler(weather)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// The listed tool as JSON
+async fn listed(client: &mut mcp::testing::McpClient) -> serde_json::Value {
+    let tools = client.list_tools().await.unwrap();
+    serde_json::to_value(&tools.tools[0]).unwrap()
+}
+
+#[tokio::test]
+async fn lists_tools_without_their_schemas() {
+    let mut client = mcp::testing::harness(service(true)).await.unwrap();
+
+    let tool = listed(&mut client).await;
+    assert_eq!(tool["inputSchema"]["type"], "object");
+    assert!(tool["inputSchema"].get("properties").is_none());
+    assert_eq!(tool["_meta"]["schemaUri"], "mcp://tools/weather/schema");
+}
+
+#[tokio::test]
+async fn fetches_the_schema_later() {
+    let mut client = mcp::testing::harness(service(true)).await.unwrap();
+    let tool = listed(&mut client).await;
+
+    let result = client
+        .read_resource(tool["_meta"]["schemaUri"].as_str().unwrap())
+        .await
+        .unwrap();
+    let [ResourceContents::Text(contents)] = &result.contents[..] else {
+        panic!("expected one text contents");
+    };
+    assert_eq!(
+        contents.mime_type.as_deref(),
+        Some("application/schema+json")
+    );
+    let schema: serde_json::Value = serde_json::from_str(&contents.text).unwrap();
+    assert_eq!(schema["properties"]["city"]["type"], "string");
+    assert_eq!(
+        schema["properties"]["city"]["description"],
+        "The city to get the weather of"
+    );
+
+    // Tools are still called with the arguments the fetched schema describes
+    let result = client
+        .call_tool("weather", serde_json::json!({ "city": "Oslo" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "Rainy in Oslo"
+    );
+}
+
+#[tokio::test]
+async fn lists_schemas_inline_by_default() {
+    let mut client = mcp::testing::harness(service(false)).await.unwrap();
+
+    let tool = listed(&mut client).await;
+    assert_eq!(tool["inputSchema"]["properties"]["city"]["type"], "string");
+    assert!(tool["_meta"].get("schemaUri").is_none());
+    assert!(
+        client
+            .read_resource("mcp://tools/weather/schema")
+            .await
+            .is_err()
+    );
+}
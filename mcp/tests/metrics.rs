@@ -0,0 +1,87 @@
+//! Counting requests and timing tool calls in the Prometheus metrics.
+#![cfg(feature = "metrics")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header};
+use mcp::{BasicService, McpImpl, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const CALLS: &str = r#"mcp_requests_total{method="tools/call",outcome="ok"}"#;
+const DURATIONS: &str = r#"mcp_tool_call_duration_seconds_count{tool="noop"}"#;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+async fn post(app: &Router, message: &serde_json::Value) {
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(message.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert!(response.status().is_success());
+}
+
+/// The value of a sample in the metrics, or 0 if it hasn't been recorded
+async fn sample(metrics: &Router, name: &str) -> u64 {
+    let request = Request::get("/metrics").body(Body::empty()).unwrap();
+    let response = metrics.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    text.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .map_or(0, |value| value.parse().unwrap())
+}
+
+#[tokio::test]
+async fn records_tool_calls() {
+    let service =
+        BasicService::new(()).tool(Tool::builder().name("noop").handler(noop).build().unwrap());
+    let mcp = Arc::new(McpImpl::new(service));
+    let app = mcp.clone().into_router();
+    let metrics = mcp.metrics_router();
+
+    assert_eq!(sample(&metrics, CALLS).await, 0);
+    assert_eq!(sample(&metrics, DURATIONS).await, 0);
+
+    for id in 1..=2 {
+        post(
+            &app,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": "noop", "arguments": {} },
+            }),
+        )
+        .await;
+    }
+    assert_eq!(sample(&metrics, CALLS).await, 2);
+    assert_eq!(sample(&metrics, DURATIONS).await, 2);
+
+    // Failed requests are counted apart, and aren't timed as tool calls
+    post(
+        &app,
+        &json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/read", "params": { "uri": "memory://missing" } }),
+    )
+    .await;
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"mcp_requests_total{method="resources/read",outcome="error"}"#
+        )
+        .await,
+        1
+    );
+    assert_eq!(sample(&metrics, DURATIONS).await, 2);
+}
@@ -0,0 +1,130 @@
+//! Message middleware, which sees typed messages, and tower layers, which see HTTP requests.
+
+use axum::extract::Request;
+use axum::middleware::Next;
+use mcp::rpc::ClientMessage;
+use mcp::{BasicService, ErrorCode, ServerOptions, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Deserialize, JsonSchema)]
+struct AddParams {
+    a: i64,
+    b: i64,
+}
+
+fn service(calls: Arc<AtomicUsize>) -> BasicService<()> {
+    BasicService::new(()).tool(
+        Tool::builder()
+            .name("add")
+            .handler(move |(): (), params: AddParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, mcp::Error>((params.a + params.b).to_string()) }
+            })
+            .build()
+            .unwrap(),
+    )
+}
+
+/// The tool called by a message, if it calls one
+fn called_tool(message: &ClientMessage) -> Option<String> {
+    let message = serde_json::to_value(message).ok()?;
+    if message["method"] != "tools/call" {
+        return None;
+    }
+    message["params"]["name"].as_str().map(str::to_string)
+}
+
+#[tokio::test]
+async fn rejects_requests_before_they_are_handled() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let service = service(calls.clone()).middleware(|message: &mut ClientMessage| {
+        if called_tool(message).as_deref() == Some("add") {
+            return Err(mcp::Error::invalid_request("Adding is disabled"));
+        }
+        Ok(())
+    });
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let error = client
+        .call_tool("add", json!({ "a": 1, "b": 2 }))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidRequest);
+    assert_eq!(error.message, "Adding is disabled");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    // Other requests pass through
+    assert_eq!(client.list_tools().await.unwrap().tools.len(), 1);
+}
+
+#[tokio::test]
+async fn rewrites_requests_before_they_are_handled() {
+    let service = service(Arc::default()).middleware(|message: &mut ClientMessage| {
+        if called_tool(message).is_none() {
+            return Ok(());
+        }
+        let mut value = serde_json::to_value(&*message)?;
+        value["params"]["arguments"]["b"] = json!(40);
+        *message = serde_json::from_value(value)?;
+        Ok(())
+    });
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let result = client
+        .call_tool("add", json!({ "a": 2, "b": 2 }))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&result.content).unwrap()[0]["text"],
+        "42"
+    );
+}
+
+#[tokio::test]
+async fn passes_http_requests_through_tower_layers() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorded = seen.clone();
+    let layer = axum::middleware::from_fn(move |request: Request, next: Next| {
+        let recorded = recorded.clone();
+        async move {
+            let header = request
+                .headers()
+                .get("x-request-tag")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            recorded
+                .lock()
+                .unwrap()
+                .push((request.uri().path().to_string(), header));
+            next.run(request).await
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(mcp::serve_over_sse_with_layer(
+        listener,
+        BasicService::new(()),
+        ServerOptions::new(),
+        layer,
+        std::future::pending(),
+    ));
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{address}/api/message"))
+        .header("x-request-tag", "smoke")
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [("/api/message".to_string(), Some("smoke".to_string()))]
+    );
+}
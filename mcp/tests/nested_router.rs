@@ -0,0 +1,40 @@
+//! Serving the MCP endpoints nested under a prefix of an existing application.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn round_trips_a_message_under_a_prefix() {
+    let mcp = Arc::new(McpImpl::new(BasicService::new(())));
+    let app: Router = Router::new().nest("/mcp", mcp.into_router());
+
+    let request = Request::post("/mcp/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 7, "method": "ping" }).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["id"], 7);
+    assert!(body.get("result").is_some());
+
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 8, "method": "ping" }).to_string(),
+        ))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
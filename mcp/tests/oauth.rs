@@ -0,0 +1,119 @@
+//! Authorizing requests with access tokens checked by introspection at the issuer.
+#![cfg(feature = "oauth")]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use axum::routing::post;
+use axum::{Form, Json, Router};
+use mcp::oauth::OAuth;
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tower::ServiceExt;
+
+const RESOURCE: &str = "https://mcp.example.com";
+const ISSUER: &str = "https://auth.example.com";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// What the issuer knows about each token
+fn claims(token: &str) -> serde_json::Value {
+    let mut claims = json!({
+        "active": true,
+        "sub": "ada",
+        "scope": "mcp:tools",
+        "iss": ISSUER,
+        "aud": RESOURCE,
+        "exp": now() + 3600,
+    });
+    match token {
+        "valid" => {}
+        "expired" => claims["exp"] = json!(now() - 60),
+        "other-audience" => claims["aud"] = json!(["https://other.example.com"]),
+        "other-issuer" => claims["iss"] = json!("https://evil.example.com"),
+        "read-only" => claims["scope"] = json!("mcp:read"),
+        // Revoked or unknown tokens
+        _ => claims = json!({ "active": false }),
+    }
+    claims
+}
+
+/// Serves an introspection endpoint on a local port, returning its url
+async fn issuer() -> String {
+    let app = Router::new().route(
+        "/introspect",
+        post(
+            |Form(form): Form<HashMap<String, String>>| async move { Json(claims(&form["token"])) },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{address}/introspect")
+}
+
+async fn post_message(token: &str) -> axum::response::Response {
+    let oauth = OAuth::new(RESOURCE, ISSUER)
+        .require_scopes(["mcp:tools"])
+        .introspection(issuer().await, "mcp", "secret");
+    let app = Arc::new(McpImpl::new(BasicService::new(())))
+        .into_router()
+        .layer(oauth);
+    let request = Request::post("/message")
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }).to_string(),
+        ))
+        .unwrap();
+    app.oneshot(request).await.unwrap()
+}
+
+fn challenge(response: &axum::response::Response) -> &str {
+    response.headers()[header::WWW_AUTHENTICATE]
+        .to_str()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn accepts_valid_tokens() {
+    let response = post_message("valid").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_inactive_and_expired_tokens() {
+    for token in ["revoked", "expired"] {
+        let response = post_message(token).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{token}");
+        assert!(challenge(&response).contains("error=\"invalid_token\""));
+    }
+}
+
+#[tokio::test]
+async fn rejects_tokens_for_another_audience_or_issuer() {
+    for token in ["other-audience", "other-issuer"] {
+        let response = post_message(token).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{token}");
+        assert!(challenge(&response).contains("error=\"invalid_token\""));
+    }
+}
+
+#[tokio::test]
+async fn forbids_tokens_without_the_required_scopes() {
+    let response = post_message("read-only").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let challenge = challenge(&response);
+    assert!(
+        challenge.contains("error=\"insufficient_scope\""),
+        "{challenge}"
+    );
+    assert!(challenge.contains("scope=\"mcp:tools\""), "{challenge}");
+}
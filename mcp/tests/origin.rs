@@ -0,0 +1,85 @@
+//! Rejecting browser requests from origins that aren't allowed.
+
+use mcp::{BasicService, ServerOptions};
+use reqwest::StatusCode;
+use serde_json::json;
+
+/// Serves on a local port, returning the url of the message endpoint
+async fn serve(options: ServerOptions) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(mcp::serve_over_sse_with_options(
+        listener,
+        BasicService::new(()),
+        options,
+    ));
+    format!("http://{address}/api/message")
+}
+
+async fn ping(url: &str, origin: Option<&str>) -> StatusCode {
+    let mut request = reqwest::Client::new()
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }));
+    if let Some(origin) = origin {
+        request = request.header(reqwest::header::ORIGIN, origin);
+    }
+    request.send().await.unwrap().status()
+}
+
+#[tokio::test]
+async fn rejects_origins_that_are_not_allowed() {
+    let url = serve(ServerOptions::new().allow_origin("https://app.example.com")).await;
+
+    assert_eq!(
+        ping(&url, Some("https://evil.example.com")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        ping(&url, Some("https://app.example.com.evil.com")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(ping(&url, Some("null")).await, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn accepts_allowed_origins_and_requests_without_one() {
+    let url = serve(ServerOptions::new().allow_origin("https://app.example.com/")).await;
+
+    assert_eq!(
+        ping(&url, Some("https://app.example.com")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        ping(&url, Some("http://localhost:6274")).await,
+        StatusCode::OK
+    );
+    assert_eq!(ping(&url, Some("http://[::1]:8080")).await, StatusCode::OK);
+    assert_eq!(ping(&url, None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_localhost_when_disallowed() {
+    let url = serve(ServerOptions::new().allow_localhost(false)).await;
+
+    assert_eq!(
+        ping(&url, Some("http://localhost:6274")).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn answers_preflight_requests_of_allowed_origins() {
+    let url = serve(ServerOptions::new().allow_origin("https://app.example.com")).await;
+
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, &url)
+        .header(reqwest::header::ORIGIN, "https://app.example.com")
+        .header(reqwest::header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()[reqwest::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://app.example.com"
+    );
+}
@@ -0,0 +1,77 @@
+//! Continuing the trace of the host from W3C trace context in a request's `_meta`.
+#![cfg(feature = "otel")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header};
+use mcp::{BasicService, McpImpl, Tool};
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+/// Returns the id of the trace the call runs in
+async fn trace_id((): (), _: Empty) -> Result<String, mcp::Error> {
+    let context = tracing::Span::current().context();
+    Ok(context.span().span_context().trace_id().to_string())
+}
+
+/// Calls the tool with the given `_meta` and returns the trace id it saw
+async fn call(app: &Router, meta: serde_json::Value) -> String {
+    let message = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": { "name": "trace_id", "arguments": {}, "_meta": meta },
+    });
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(message.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    body["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn continues_the_trace_in_meta() {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    let tracer = TracerProvider::builder().build().tracer("otel-test");
+    let subscriber =
+        tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let service = BasicService::new(()).tool(
+        Tool::builder()
+            .name("trace_id")
+            .handler(trace_id)
+            .build()
+            .unwrap(),
+    );
+    let app = Arc::new(McpImpl::new(service)).into_router();
+
+    assert_eq!(
+        call(&app, json!({ "traceparent": TRACEPARENT })).await,
+        TRACE_ID
+    );
+    // Without trace context, the request starts a trace of its own
+    assert_ne!(call(&app, json!({})).await, TRACE_ID);
+}
@@ -0,0 +1,80 @@
+//! SSE connections that fall behind on notifications past their capacity.
+
+use axum::Router;
+use axum::body::{Body, BodyDataStream};
+use axum::http::Request;
+use futures::StreamExt;
+use mcp::logging::Logger;
+use mcp::{BasicService, McpImpl, Overflow};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const CAPACITY: usize = 2;
+
+fn app(overflow: Overflow) -> (Router, Logger) {
+    let service = BasicService::new(());
+    let logger = service.logger("overflow");
+    let mcp = McpImpl::with_capacity(service, CAPACITY).on_overflow(overflow);
+    (Arc::new(mcp).into_router(), logger)
+}
+
+/// Opens a session and reads its endpoint event
+async fn open(app: &Router) -> BodyDataStream {
+    let request = Request::get("/events").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let mut events = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("sessionId=") {
+        let chunk = events.next().await.unwrap().unwrap();
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    events
+}
+
+/// Everything received until the stream is quiet for a while, and whether it ended
+async fn drain(events: &mut BodyDataStream) -> (String, bool) {
+    let mut received = String::new();
+    loop {
+        match tokio::time::timeout(Duration::from_millis(200), events.next()).await {
+            Ok(Some(chunk)) => received.push_str(std::str::from_utf8(&chunk.unwrap()).unwrap()),
+            Ok(None) => return (received, true),
+            Err(_) => return (received, false),
+        }
+    }
+}
+
+#[tokio::test]
+async fn disconnects_sessions_that_fall_behind() {
+    let (app, logger) = app(Overflow::Disconnect);
+    let mut events = open(&app).await;
+
+    // The session doesn't read while more than its capacity is sent
+    for i in 0..CAPACITY * 3 {
+        logger.info(format!("message {i}"));
+    }
+
+    let (received, ended) = drain(&mut events).await;
+    assert!(ended, "the connection stayed open");
+    assert!(!received.contains("message"), "{received}");
+}
+
+#[tokio::test]
+async fn skips_the_oldest_messages_of_sessions_that_fall_behind() {
+    let (app, logger) = app(Overflow::Skip);
+    let mut events = open(&app).await;
+
+    for i in 0..CAPACITY * 3 {
+        logger.info(format!("message {i}"));
+    }
+
+    let (received, ended) = drain(&mut events).await;
+    assert!(!ended);
+    assert!(!received.contains("message 0"), "{received}");
+    assert!(received.contains(&format!("message {}", CAPACITY * 3 - 1)));
+
+    // The connection keeps receiving later messages
+    logger.info("caught up");
+    let (received, _) = drain(&mut events).await;
+    assert!(received.contains("caught up"), "{received}");
+}
@@ -0,0 +1,184 @@
+//! Paging through registries with cursors.
+
+use mcp::registry::resource::{FixedResourceUri, TemplateResourceUri};
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, ErrorCode, Resource, ResourceRegistry, Service, Tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+fn tools(names: &[&str]) -> ToolRegistry<()> {
+    let mut registry = ToolRegistry::new();
+    for name in names {
+        register(&mut registry, name);
+    }
+    registry
+}
+
+fn register(registry: &mut ToolRegistry<()>, name: &str) {
+    registry.register(Tool::builder().name(name).handler(noop).build().unwrap());
+}
+
+/// The names on a page of tools and the cursor of the next page
+fn page(registry: &ToolRegistry<()>, cursor: Option<&str>) -> (Vec<String>, Option<String>) {
+    let page = registry.page(cursor, 2).unwrap();
+    let names = page
+        .items
+        .iter()
+        .map(|tool| tool.name().to_string())
+        .collect();
+    (names, page.next_cursor)
+}
+
+fn template(name: &str) -> Resource<(), TemplateResourceUri> {
+    Resource::builder()
+        .name(name)
+        .template_uri(format!("{name}://{{id}}"))
+        .source(MemoryResource::new())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn pages_through_every_entry_in_order() {
+    let registry = tools(&["e", "c", "a", "d", "b"]);
+
+    let (names, cursor) = page(&registry, None);
+    assert_eq!(names, ["a", "b"]);
+    let (names, cursor) = page(&registry, cursor.as_deref());
+    assert_eq!(names, ["c", "d"]);
+    let (names, cursor) = page(&registry, cursor.as_deref());
+    assert_eq!(names, ["e"]);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn ends_on_a_full_last_page() {
+    let registry = tools(&["a", "b", "c", "d"]);
+
+    let (_, cursor) = page(&registry, None);
+    let (names, cursor) = page(&registry, cursor.as_deref());
+    assert_eq!(names, ["c", "d"]);
+    assert_eq!(cursor, None);
+
+    let empty = tools(&[]);
+    assert_eq!(page(&empty, None), (Vec::new(), None));
+}
+
+#[test]
+fn keeps_pages_stable_when_entries_are_registered_between_requests() {
+    let mut registry = tools(&["b", "d", "f", "h"]);
+
+    let (names, cursor) = page(&registry, None);
+    assert_eq!(names, ["b", "d"]);
+
+    // Entries before the cursor are neither repeated nor do they push others onto later pages
+    register(&mut registry, "a");
+    register(&mut registry, "c");
+    register(&mut registry, "e");
+    let (names, cursor) = page(&registry, cursor.as_deref());
+    assert_eq!(names, ["e", "f"]);
+    let (names, cursor) = page(&registry, cursor.as_deref());
+    assert_eq!(names, ["h"]);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn keeps_template_pages_stable_when_templates_are_registered_between_requests() {
+    let mut registry = ResourceRegistry::new();
+    for name in ["a", "b", "c"] {
+        registry.register_template(template(name));
+    }
+    let names = |page: mcp::registry::Page<&Resource<(), TemplateResourceUri>>| {
+        let names = page
+            .items
+            .into_iter()
+            .map(|resource| {
+                mcp_schema::ResourceTemplate::try_from(resource)
+                    .unwrap()
+                    .name
+            })
+            .collect::<Vec<_>>();
+        (names, page.next_cursor)
+    };
+
+    let (first, cursor) = names(registry.template_resources_page(None, 2).unwrap());
+    assert_eq!(first, ["a", "b"]);
+
+    registry.register_template(template("d"));
+    let page = registry
+        .template_resources_page(cursor.as_deref(), 2)
+        .unwrap();
+    let (second, cursor) = names(page);
+    assert_eq!(second, ["c", "d"]);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn rejects_invalid_cursors() {
+    let registry = tools(&["a", "b", "c"]);
+    let error = registry.page(Some("not a cursor!"), 2).err().unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+
+    let mut resources = ResourceRegistry::new();
+    resources.register_template(template("a"));
+    // A cursor of the tool registry holds a name rather than a position
+    let (_, cursor) = page(&registry, None);
+    let error = resources
+        .template_resources_page(cursor.as_deref(), 2)
+        .err()
+        .unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn pages_resources_of_tools_along_with_registered_resources() {
+    let service = ["a", "b", "c"].iter().fold(
+        BasicService::new(())
+            .lazy_schemas(true)
+            .page_size(2)
+            .fixed_resource(
+                Resource::<(), FixedResourceUri>::builder()
+                    .name("notes")
+                    .fixed_uri("notes://all")
+                    .source(MemoryResource::new())
+                    .build()
+                    .unwrap(),
+            ),
+        |service, name| service.tool(Tool::builder().name(*name).handler(noop).build().unwrap()),
+    );
+
+    let mut uris = Vec::new();
+    let mut cursor = None;
+    loop {
+        let params = match &cursor {
+            Some(cursor) => serde_json::json!({ "cursor": cursor }),
+            None => serde_json::json!({}),
+        };
+        let page = service
+            .list_resources(serde_json::from_value(params).unwrap())
+            .await
+            .unwrap();
+        assert!(page.resources.len() <= 2);
+        uris.extend(page.resources.into_iter().map(|resource| resource.uri));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(
+        uris,
+        [
+            "mcp://tools/a/schema",
+            "mcp://tools/b/schema",
+            "mcp://tools/c/schema",
+            "notes://all",
+        ]
+    );
+}
@@ -0,0 +1,98 @@
+//! Polling for notifications instead of holding an SSE connection open.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use futures::StreamExt;
+use mcp::logging::Logger;
+use mcp::{BasicService, McpImpl};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn app() -> (Router, Logger) {
+    let service = BasicService::new(());
+    let logger = service.logger("poll");
+    (Arc::new(McpImpl::new(service)).into_router(), logger)
+}
+
+/// Polls the uri and returns the logged data of the notifications, and the next cursor
+async fn poll(app: &Router, uri: &str) -> (Vec<serde_json::Value>, u64) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["missed"], false);
+    let data = body["notifications"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|notification| notification["params"]["data"].clone())
+        .collect();
+    (data, body["cursor"].as_u64().unwrap())
+}
+
+#[tokio::test]
+async fn returns_notifications_sent_since_the_cursor() {
+    let (app, logger) = app();
+
+    // Without a cursor, polling starts from now
+    logger.info("before");
+    let (data, cursor) = poll(&app, "/poll").await;
+    assert!(data.is_empty());
+
+    logger.info("first");
+    logger.info("second");
+    let (data, cursor) = poll(&app, &format!("/poll?cursor={cursor}")).await;
+    assert_eq!(data, ["first", "second"]);
+
+    logger.info("third");
+    let (data, cursor) = poll(&app, &format!("/poll?cursor={cursor}")).await;
+    assert_eq!(data, ["third"]);
+
+    let (data, _) = poll(&app, &format!("/poll?cursor={cursor}")).await;
+    assert!(data.is_empty());
+}
+
+#[tokio::test]
+async fn polls_for_open_sessions() {
+    let (app, logger) = app();
+    let request = Request::get("/events").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let mut events = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("sessionId=") {
+        let chunk = events.next().await.unwrap().unwrap();
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    let session_id = received
+        .split("sessionId=")
+        .nth(1)
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .to_string();
+
+    let (_, cursor) = poll(&app, &format!("/poll?sessionId={session_id}")).await;
+    logger.info("hello");
+    let (data, _) = poll(
+        &app,
+        &format!("/poll?sessionId={session_id}&cursor={cursor}"),
+    )
+    .await;
+    assert_eq!(data, ["hello"]);
+}
+
+#[tokio::test]
+async fn rejects_polls_of_unknown_sessions() {
+    let (app, _) = app();
+
+    let request = Request::get("/poll?sessionId=unknown")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
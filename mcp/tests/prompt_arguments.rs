@@ -0,0 +1,55 @@
+//! Listing of prompt arguments as the spec's `PromptArgument` entries.
+
+use mcp::Prompt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct ReviewParams {
+    /// The code to review
+    code: String,
+    /// The language of the code, guessed if left out
+    language: Option<String>,
+    #[serde(default)]
+    focus: String,
+}
+
+async fn review(
+    (): (),
+    _params: ReviewParams,
+) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(Vec::new())
+}
+
+#[test]
+fn lists_arguments_with_descriptions_and_required_flags() {
+    let prompt = Prompt::builder()
+        .name("review")
+        .handler(review)
+        .build()
+        .unwrap();
+    let listed = mcp_schema::Prompt::try_from(&prompt).unwrap();
+
+    let arguments: Vec<_> = listed
+        .arguments
+        .unwrap()
+        .into_iter()
+        .map(|argument| (argument.name, argument.description, argument.required))
+        .collect();
+    assert_eq!(
+        arguments,
+        [
+            (
+                "code".to_string(),
+                Some("The code to review".to_string()),
+                Some(true)
+            ),
+            ("focus".to_string(), None, Some(false)),
+            (
+                "language".to_string(),
+                Some("The language of the code, guessed if left out".to_string()),
+                Some(false)
+            ),
+        ]
+    );
+}
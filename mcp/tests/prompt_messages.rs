@@ -0,0 +1,82 @@
+//! Building prompt messages of each content kind with `PromptMessagesBuilder`.
+
+use base64::Engine;
+use mcp::content::{PromptMessagesBuilder, embedded, image};
+use mcp_schema::{PromptContent, ResourceContents};
+use serde_json::json;
+
+#[test]
+fn builds_text_messages_in_order() {
+    let messages = PromptMessagesBuilder::new()
+        .user("What's the weather in Oslo?")
+        .assistant("Rainy, 8 °C.")
+        .user("And tomorrow?")
+        .build();
+
+    let messages: Vec<_> = serde_json::to_value(messages)
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            assert_eq!(message["content"]["type"], "text");
+            json!([message["role"], message["content"]["text"]])
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            json!(["user", "What's the weather in Oslo?"]),
+            json!(["assistant", "Rainy, 8 °C."]),
+            json!(["user", "And tomorrow?"]),
+        ]
+    );
+}
+
+#[test]
+fn builds_image_messages() {
+    let png = [0x89, b'P', b'N', b'G'];
+    let messages = PromptMessagesBuilder::new()
+        .user_content(image(&png, "image/png"))
+        .build();
+
+    let [message] = &messages[..] else {
+        panic!("expected one message");
+    };
+    let PromptContent::Image(content) = &message.content else {
+        panic!("expected image content");
+    };
+    assert_eq!(content.mime_type, "image/png");
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(&content.data)
+        .unwrap();
+    assert_eq!(data, png);
+
+    let json = serde_json::to_value(message).unwrap();
+    assert_eq!(json["role"], "user");
+    assert_eq!(json["content"]["type"], "image");
+}
+
+#[test]
+fn builds_embedded_resource_messages() {
+    let notes = ResourceContents::Text(mcp_schema::TextResourceContents {
+        uri: "file:///notes.md".to_string(),
+        mime_type: Some("text/markdown".to_string()),
+        text: "# Lighthouses".to_string(),
+    });
+    let messages = PromptMessagesBuilder::new()
+        .assistant_content(embedded(notes))
+        .build();
+
+    let [message] = &messages[..] else {
+        panic!("expected one message");
+    };
+    let json = serde_json::to_value(message).unwrap();
+    assert_eq!(json["role"], "assistant");
+    assert_eq!(json["content"]["type"], "resource");
+    assert_eq!(json["content"]["resource"]["uri"], "file:///notes.md");
+    assert_eq!(json["content"]["resource"]["mimeType"], "text/markdown");
+    assert_eq!(json["content"]["resource"]["text"], "# Lighthouses");
+    // Embedding read contents directly doesn't leave the placeholder marker behind
+    assert!(mcp::content::pending_embed(&message.content).is_none());
+}
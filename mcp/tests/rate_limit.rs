@@ -0,0 +1,119 @@
+//! Limiting the messages each client may post.
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, header};
+use mcp::rate_limit::RateLimit;
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+/// Bursts of two messages, refilling one every 200ms
+fn app() -> Router {
+    let limit = RateLimit::new(2, Duration::from_millis(400));
+    Arc::new(McpImpl::new(BasicService::new(())).rate_limit(limit)).into_router()
+}
+
+/// Posts a ping for the session
+async fn ping(app: &Router, session: &str) -> Option<(String, serde_json::Value)> {
+    send(app, post(&format!("/message?sessionId={session}"))).await
+}
+
+/// A ping posted to the URI
+fn post(uri: &str) -> Request<Body> {
+    Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }).to_string(),
+        ))
+        .unwrap()
+}
+
+/// Sends the request, returning the `Retry-After` seconds and the error data if it was rate
+/// limited
+async fn send(app: &Router, request: Request<Body>) -> Option<(String, serde_json::Value)> {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let retry_after = response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    Some((retry_after, body["error"]["data"].clone()))
+}
+
+#[tokio::test]
+async fn allows_bursts_up_to_the_capacity() {
+    let app = app();
+
+    assert!(ping(&app, "a").await.is_none());
+    assert!(ping(&app, "a").await.is_none());
+    assert!(ping(&app, "a").await.is_some());
+}
+
+#[tokio::test]
+async fn tells_limited_clients_when_to_retry() {
+    let app = app();
+    ping(&app, "a").await;
+    ping(&app, "a").await;
+
+    let (retry_after, data) = ping(&app, "a").await.unwrap();
+    assert_eq!(retry_after, "1");
+    let retry_after_ms = data["retryAfterMs"].as_u64().unwrap();
+    assert!((100..=200).contains(&retry_after_ms), "{retry_after_ms}");
+}
+
+#[tokio::test]
+async fn refills_over_time() {
+    let app = app();
+    ping(&app, "a").await;
+    ping(&app, "a").await;
+    assert!(ping(&app, "a").await.is_some());
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(ping(&app, "a").await.is_none());
+    assert!(ping(&app, "a").await.is_some());
+}
+
+#[tokio::test]
+async fn limits_each_client_separately() {
+    let app = app();
+    ping(&app, "a").await;
+    ping(&app, "a").await;
+    assert!(ping(&app, "a").await.is_some());
+
+    assert!(ping(&app, "b").await.is_none());
+}
+
+#[tokio::test]
+async fn limits_clients_without_a_session_by_address() {
+    let app = app();
+    let from = |address: &str| {
+        let mut request = post("/message");
+        let address: SocketAddr = address.parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(address));
+        request
+    };
+    send(&app, from("10.0.0.1:1000")).await;
+    send(&app, from("10.0.0.1:1001")).await;
+
+    assert!(send(&app, from("10.0.0.1:1002")).await.is_some());
+    assert!(send(&app, from("10.0.0.2:1000")).await.is_none());
+}
+
+#[tokio::test]
+async fn does_not_limit_unidentified_clients() {
+    let app = app();
+
+    for _ in 0..5 {
+        assert!(send(&app, post("/message")).await.is_none());
+    }
+}
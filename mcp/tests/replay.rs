@@ -0,0 +1,89 @@
+//! Replaying the notifications a reconnecting SSE client missed.
+
+use axum::Router;
+use axum::body::{Body, BodyDataStream};
+use axum::http::Request;
+use futures::StreamExt;
+use mcp::logging::Logger;
+use mcp::{BasicService, McpImpl};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn app() -> (Router, Logger) {
+    let service = BasicService::new(());
+    let logger = service.logger("replay");
+    let mcp = McpImpl::new(service).replay_notifications(10);
+    (Arc::new(mcp).into_router(), logger)
+}
+
+async fn open(app: &Router, last_event_id: Option<&str>) -> BodyDataStream {
+    let mut request = Request::get("/events");
+    if let Some(id) = last_event_id {
+        request = request.header("last-event-id", id);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    response.into_body().into_data_stream()
+}
+
+/// The id and logged data of every message event received until the stream is quiet for a while
+async fn messages(events: &mut BodyDataStream) -> Vec<(String, serde_json::Value)> {
+    let mut received = String::new();
+    while let Ok(Some(chunk)) =
+        tokio::time::timeout(Duration::from_millis(200), events.next()).await
+    {
+        received.push_str(std::str::from_utf8(&chunk.unwrap()).unwrap());
+    }
+
+    received
+        .split("\n\n")
+        .filter(|event| event.contains("event: message"))
+        .map(|event| {
+            let field = |name: &str| {
+                event
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap()
+                    .to_string()
+            };
+            let data: serde_json::Value = serde_json::from_str(&field("data: ")).unwrap();
+            (field("id: "), data["params"]["data"].clone())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn replays_only_the_notifications_missed_after_the_last_event() {
+    let (app, logger) = app();
+
+    let mut first = open(&app, None).await;
+    logger.info("one");
+    logger.info("two");
+    let received = messages(&mut first).await;
+    assert_eq!(received.len(), 2);
+    // The client lost the connection after receiving the first notification
+    let (last_event_id, _) = &received[0];
+    drop(first);
+
+    logger.info("three");
+    let mut second = open(&app, Some(last_event_id)).await;
+    let received: Vec<_> = messages(&mut second)
+        .await
+        .into_iter()
+        .map(|(_, data)| data)
+        .collect();
+    assert_eq!(received, ["two", "three"]);
+
+    // Later notifications arrive once each
+    logger.info("four");
+    let received: Vec<_> = messages(&mut second)
+        .await
+        .into_iter()
+        .map(|(_, data)| data)
+        .collect();
+    assert_eq!(received, ["four"]);
+}
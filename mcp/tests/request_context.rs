@@ -0,0 +1,150 @@
+//! What handlers see of their request through `RequestContext`, and the progress and log
+//! notifications they send through it.
+
+use axum::Router;
+use axum::body::{Body, BodyDataStream};
+use axum::http::{Request, header};
+use futures::StreamExt;
+use mcp::context::RequestContext;
+use mcp::{BasicService, McpImpl, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+/// Reports progress and logs through the context, and returns what it knows of the request
+async fn work(context: RequestContext, _: Empty) -> Result<String, mcp::Error> {
+    context.progress(1.0, Some(2.0));
+    context.logger("work").info("halfway");
+    context.progress(2.0, Some(2.0));
+    Ok(json!({
+        "requestId": context.request_id(),
+        "sessionId": context.session_id(),
+    })
+    .to_string())
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(()).tool(Tool::builder().name("work").handler(work).build().unwrap())
+}
+
+fn call(id: u64) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "work", "arguments": {}, "_meta": { "progressToken": "job" } },
+    })
+}
+
+#[tokio::test]
+async fn sends_progress_and_logs_over_stdio() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let params = call(0)["params"].clone();
+    let result: mcp_schema::CallToolResult = client.request("tools/call", params).await.unwrap();
+    let text = serde_json::to_value(&result.content).unwrap()[0]["text"].clone();
+    let seen: serde_json::Value = serde_json::from_str(text.as_str().unwrap()).unwrap();
+    // The harness sent `initialize` as its first request
+    assert_eq!(seen, json!({ "requestId": 2, "sessionId": null }));
+
+    let mut notifications = Vec::new();
+    while let Some(notification) = client.next_notification(TIMEOUT).await {
+        notifications.push(serde_json::to_value(notification).unwrap());
+    }
+    let progress: Vec<_> = notifications
+        .iter()
+        .filter(|notification| notification["method"] == "notifications/progress")
+        .map(|notification| notification["params"].clone())
+        .collect();
+    assert_eq!(
+        progress,
+        [
+            json!({ "progressToken": "job", "progress": 1.0, "total": 2.0 }),
+            json!({ "progressToken": "job", "progress": 2.0, "total": 2.0 }),
+        ]
+    );
+    let log = notifications
+        .iter()
+        .find(|notification| notification["method"] == "notifications/message")
+        .unwrap();
+    assert_eq!(log["params"]["logger"], "work");
+    assert_eq!(log["params"]["data"], "halfway");
+}
+
+/// An SSE connection and everything it received
+struct Session {
+    events: BodyDataStream,
+    received: String,
+}
+
+impl Session {
+    async fn open(app: &Router) -> (Self, String) {
+        let request = Request::get("/events").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let mut session = Self {
+            events: response.into_body().into_data_stream(),
+            received: String::new(),
+        };
+        while !session.received.contains("sessionId=") {
+            let chunk = session.events.next().await.unwrap().unwrap();
+            session
+                .received
+                .push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+        let endpoint = session
+            .received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap()
+            .to_string();
+        session.received.clear();
+        (session, endpoint)
+    }
+
+    /// Everything received until the stream is quiet for a while
+    async fn drain(&mut self) -> String {
+        while let Ok(Some(Ok(chunk))) =
+            tokio::time::timeout(Duration::from_millis(200), self.events.next()).await
+        {
+            self.received.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+        std::mem::take(&mut self.received)
+    }
+}
+
+#[tokio::test]
+async fn sends_notifications_only_to_the_requesting_session() {
+    let app = Arc::new(McpImpl::new(service())).into_router();
+    let (mut caller, endpoint) = Session::open(&app).await;
+    let (mut other, _) = Session::open(&app).await;
+
+    let request = Request::post(&endpoint)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(call(7).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let text = body["result"]["content"][0]["text"].as_str().unwrap();
+    let seen: serde_json::Value = serde_json::from_str(text).unwrap();
+    let session_id = endpoint.split("sessionId=").nth(1).unwrap();
+    assert_eq!(seen, json!({ "requestId": 7, "sessionId": session_id }));
+
+    let received = caller.drain().await;
+    assert!(received.contains("notifications/progress"), "{received}");
+    assert!(received.contains("halfway"), "{received}");
+
+    let received = other.drain().await;
+    assert!(!received.contains("notifications/progress"), "{received}");
+    assert!(!received.contains("halfway"), "{received}");
+}
@@ -0,0 +1,82 @@
+//! Hooks that observe every request and its response.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, header};
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+async fn post(app: &Router, message: &serde_json::Value) -> serde_json::Value {
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(message.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn runs_hooks_once_per_request_with_matching_ids() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let responses = Arc::new(Mutex::new(Vec::new()));
+    let (seen_requests, seen_responses) = (requests.clone(), responses.clone());
+    let app = Arc::new(
+        McpImpl::new(BasicService::new(()))
+            .on_request(move |request| {
+                let request = serde_json::to_value(&*request).unwrap();
+                seen_requests.lock().unwrap().push(request["id"].clone());
+            })
+            .on_response(move |request, response, _| {
+                let request = serde_json::to_value(request).unwrap();
+                let response = serde_json::to_value(response).unwrap();
+                seen_responses
+                    .lock()
+                    .unwrap()
+                    .push((request["id"].clone(), response["id"].clone()));
+            }),
+    )
+    .into_router();
+
+    let response = post(
+        &app,
+        &json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }),
+    )
+    .await;
+    assert!(response.get("result").is_some());
+    let response = post(
+        &app,
+        &json!({ "jsonrpc": "2.0", "id": "two", "method": "tools/list", "params": {} }),
+    )
+    .await;
+    assert!(response.get("result").is_some());
+    let response = post(
+        &app,
+        &json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "missing" } }),
+    )
+    .await;
+    assert!(response.get("error").is_some());
+    // Notifications aren't requests
+    post(
+        &app,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+
+    assert_eq!(
+        *requests.lock().unwrap(),
+        [json!(1), json!("two"), json!(3)]
+    );
+    assert_eq!(
+        *responses.lock().unwrap(),
+        [
+            (json!(1), json!(1)),
+            (json!("two"), json!("two")),
+            (json!(3), json!(3)),
+        ]
+    );
+}
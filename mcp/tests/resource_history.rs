@@ -0,0 +1,105 @@
+//! Reading past versions of a resource recorded by `ResourceHistory`.
+
+use mcp::ErrorCode;
+use mcp::registry::resource::Source;
+use mcp::resources::{MemoryResource, ResourceHistory};
+use mcp_schema::ResourceContents;
+
+const URI: &str = "memory://notes";
+
+fn text(text: &str) -> ResourceContents {
+    ResourceContents::Text(mcp_schema::TextResourceContents {
+        uri: URI.to_string(),
+        mime_type: Some("text/plain".to_string()),
+        text: text.to_string(),
+    })
+}
+
+/// Reads the uri and returns the text of its contents
+async fn read(history: &ResourceHistory<MemoryResource>, uri: &str) -> Result<String, mcp::Error> {
+    let contents = Source::<()>::read(history, (), uri.to_string()).await?;
+    Ok(match &contents[..] {
+        [ResourceContents::Text(contents)] => contents.text.clone(),
+        _ => panic!("expected one text contents"),
+    })
+}
+
+/// Serves each text in turn, so the history records it as a version
+async fn record(
+    history: &ResourceHistory<MemoryResource>,
+    memory: &MemoryResource,
+    texts: &[&str],
+) {
+    for contents in texts {
+        memory.set([text(contents)]);
+        read(history, URI).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn reads_past_versions() {
+    let memory = MemoryResource::new();
+    let history = ResourceHistory::new(memory.clone(), 10);
+    record(&history, &memory, &["first", "first", "second"]).await;
+
+    // Reading the same contents again doesn't record a version
+    let versions: Vec<_> = history
+        .versions()
+        .iter()
+        .map(|recorded| recorded.version)
+        .collect();
+    assert_eq!(versions, [1, 2]);
+
+    assert_eq!(
+        read(&history, &format!("{URI}?version=1")).await.unwrap(),
+        "first"
+    );
+    assert_eq!(
+        read(&history, &format!("{URI}?version=2")).await.unwrap(),
+        "second"
+    );
+    assert_eq!(read(&history, URI).await.unwrap(), "second");
+}
+
+#[tokio::test]
+async fn evicts_the_oldest_versions_at_capacity() {
+    let memory = MemoryResource::new();
+    let history = ResourceHistory::new(memory.clone(), 2);
+    record(&history, &memory, &["first", "second", "third"]).await;
+
+    let versions: Vec<_> = history
+        .versions()
+        .iter()
+        .map(|recorded| recorded.version)
+        .collect();
+    assert_eq!(versions, [2, 3]);
+
+    let error = read(&history, &format!("{URI}?version=1"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::ResourceNotFound);
+    assert_eq!(
+        read(&history, &format!("{URI}?version=2")).await.unwrap(),
+        "second"
+    );
+}
+
+#[tokio::test]
+async fn fails_to_read_unknown_versions() {
+    let memory = MemoryResource::new();
+    let history = ResourceHistory::new(memory.clone(), 10);
+    record(&history, &memory, &["first"]).await;
+
+    let error = read(&history, &format!("{URI}?version=7"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::ResourceNotFound);
+    assert_eq!(error.message, "Version 7 is not in the history");
+
+    let error = read(&history, &format!("{URI}?version=latest"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+
+    assert!(history.diff(1, Some(7)).is_err());
+}
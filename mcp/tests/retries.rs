@@ -0,0 +1,106 @@
+//! Retrying idempotent tools after transient failures.
+
+use mcp::registry::ToolAnnotations;
+use mcp::{Tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type Calls = Arc<AtomicUsize>;
+
+#[derive(Deserialize, JsonSchema)]
+struct FetchParams {
+    /// Code of the error the first two calls fail with, or an internal error if unset
+    code: Option<i32>,
+}
+
+async fn fetch(calls: Calls, params: FetchParams) -> Result<String, mcp::Error> {
+    if calls.fetch_add(1, Ordering::Relaxed) < 2 {
+        return Err(match params.code {
+            Some(code) => mcp::Error::custom(code, "Not available"),
+            None => eyre::eyre!("Connection reset").into(),
+        });
+    }
+    Ok("fetched".to_string())
+}
+
+#[mcp::tool(timeout = "30s", retries = 2, annotations(idempotent = true))]
+async fn fetch_declared(calls: Calls, params: FetchParams) -> Result<String, mcp::Error> {
+    fetch(calls, params).await
+}
+
+fn idempotent() -> ToolAnnotations {
+    ToolAnnotations {
+        idempotent_hint: Some(true),
+        ..ToolAnnotations::default()
+    }
+}
+
+fn registry() -> ToolRegistry<Calls> {
+    let mut registry = ToolRegistry::new();
+    registry.register(
+        Tool::builder()
+            .name("fetch")
+            .handler(fetch)
+            .annotations(idempotent())
+            .retries(2)
+            .build()
+            .unwrap(),
+    );
+    registry
+}
+
+fn call(arguments: serde_json::Value) -> mcp_schema::CallToolParams {
+    serde_json::from_value(serde_json::json!({ "name": "fetch", "arguments": arguments })).unwrap()
+}
+
+#[tokio::test]
+async fn retries_internal_errors() {
+    let calls = Calls::default();
+    let result = registry()
+        .call_tool(calls.clone(), call(serde_json::json!({})))
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test]
+async fn does_not_retry_other_errors() {
+    let calls = Calls::default();
+    let result = registry()
+        .call_tool(calls.clone(), call(serde_json::json!({ "code": -32010 })))
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn rejects_retries_of_tools_that_are_not_idempotent() {
+    let tool = Tool::<Calls>::builder()
+        .name("fetch")
+        .handler(fetch)
+        .retries(2)
+        .build();
+
+    assert!(tool.is_err());
+}
+
+#[tokio::test]
+async fn retries_tools_declaring_retries_in_the_attribute() {
+    let mut registry = ToolRegistry::new();
+    registry.register(fetch_declared_tool().unwrap());
+    let calls = Calls::default();
+    let params = serde_json::json!({ "name": "fetch_declared", "arguments": {} });
+    let result = registry
+        .call_tool(calls.clone(), serde_json::from_value(params).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
@@ -0,0 +1,76 @@
+//! Post-processing the input schemas of tools.
+
+use mcp::Tool;
+use mcp::registry::SchemaOptions;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Query {
+    /// Named like the keyword holding the properties of an object schema
+    properties: Vec<String>,
+    filter: Filter,
+    #[serde(default)]
+    limits: Limits,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Filter {
+    field: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Limits {
+    max: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self { max: 10 }
+    }
+}
+
+async fn query((): (), _: Query) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+fn strict_schema() -> serde_json::Value {
+    let tool = Tool::builder()
+        .name("query")
+        .schema_options(SchemaOptions::strict())
+        .handler(query)
+        .build()
+        .unwrap();
+    let listed = serde_json::to_value(mcp_schema::Tool::try_from(&tool).unwrap()).unwrap();
+    listed["inputSchema"].clone()
+}
+
+#[test]
+fn denies_additional_properties_of_every_object_schema() {
+    let schema = strict_schema();
+
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(
+        schema["properties"]["filter"]["additionalProperties"],
+        false
+    );
+}
+
+#[test]
+fn leaves_property_maps_and_values_as_they_are() {
+    let schema = strict_schema();
+    let properties = schema["properties"].as_object().unwrap();
+
+    assert!(!properties.contains_key("additionalProperties"));
+    assert_eq!(properties["properties"]["type"], "array");
+    assert!(
+        !properties["properties"]
+            .as_object()
+            .unwrap()
+            .contains_key("additionalProperties")
+    );
+    assert_eq!(properties["limits"]["default"], json!({ "max": 10 }));
+}
@@ -0,0 +1,116 @@
+//! Values resolved by type from the `Scope` of each request.
+
+use mcp::inject::Scope;
+use mcp::{BasicService, ErrorCode, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+/// A singleton provided to every request
+#[derive(Clone)]
+struct Greeting(&'static str);
+
+/// A per-request value read from `_meta`
+#[derive(Clone)]
+struct Caller(String);
+
+/// A value nothing provides
+#[derive(Clone)]
+struct Database;
+
+async fn greet(scope: Scope, _: Empty) -> Result<String, mcp::Error> {
+    let Greeting(greeting) = scope.require()?;
+    let caller = scope
+        .get::<Caller>()
+        .map_or_else(|| "nobody".to_string(), |Caller(caller)| caller);
+    Ok(format!("{greeting}, {caller}"))
+}
+
+async fn query(scope: Scope, _: Empty) -> Result<String, mcp::Error> {
+    let Database = scope.require()?;
+    Ok("rows".to_string())
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(())
+        .provide(Greeting("Hello"))
+        .request_scope(|scope, request| {
+            if let Some(caller) = request["_meta"]["caller"].as_str() {
+                scope.insert(Caller(caller.to_string()));
+            }
+        })
+        .tool(
+            Tool::builder()
+                .name("greet")
+                .handler(greet)
+                .build()
+                .unwrap(),
+        )
+        .tool(
+            Tool::builder()
+                .name("query")
+                .handler(query)
+                .build()
+                .unwrap(),
+        )
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[tokio::test]
+async fn resolves_provided_values() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result = client.call_tool("greet", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(text(&result), "Hello, nobody");
+}
+
+#[tokio::test]
+async fn keeps_per_request_values_to_their_request() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result: mcp_schema::CallToolResult = client
+        .request(
+            "tools/call",
+            json!({ "name": "greet", "arguments": {}, "_meta": { "caller": "Ada" } }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(text(&result), "Hello, Ada");
+
+    let result = client.call_tool("greet", json!({})).await.unwrap();
+    assert_eq!(text(&result), "Hello, nobody");
+}
+
+#[tokio::test]
+async fn fails_calls_needing_values_that_were_not_provided() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result = client.call_tool("query", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let message = text(&result);
+    assert!(message.as_str().unwrap().contains("Database"), "{message}");
+
+    // The server is still serving after the failed call
+    let result = client.call_tool("greet", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+}
+
+#[test]
+fn requires_values_outside_of_requests() {
+    let mut scope = Scope::default();
+    assert_eq!(
+        scope.require::<Database>().unwrap_err().code,
+        ErrorCode::Internal
+    );
+
+    scope.insert(Caller("Ada".to_string()));
+    assert_eq!(scope.get::<Caller>().unwrap().0, "Ada");
+    assert!(Scope::current().get::<Caller>().is_none());
+}
@@ -0,0 +1,124 @@
+//! Problems found by `BasicService::self_check` before serving.
+
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, Resource, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, JsonSchema)]
+struct DivideParams {
+    a: i64,
+    b: i64,
+}
+
+async fn divide((): (), params: DivideParams) -> Result<String, mcp::Error> {
+    params
+        .a
+        .checked_div(params.b)
+        .map(|quotient| quotient.to_string())
+        .ok_or_else(|| mcp::Error::invalid_params("Can't divide by zero"))
+}
+
+async fn echo((): (), text: String) -> Result<String, mcp::Error> {
+    Ok(text)
+}
+
+fn divide_tool(a: i64, b: i64) -> Tool<()> {
+    Tool::builder()
+        .name("divide")
+        .handler(divide)
+        .dry_run(HashMap::from([
+            ("a".to_string(), a.into()),
+            ("b".to_string(), b.into()),
+        ]))
+        .build()
+        .unwrap()
+}
+
+fn template(uri: &str) -> Resource<(), mcp::registry::resource::TemplateResourceUri> {
+    Resource::builder()
+        .name("file")
+        .template_uri(uri)
+        .source(MemoryResource::new())
+        .build()
+        .unwrap()
+}
+
+/// The subjects of the problems the report found
+async fn problems(service: BasicService<()>) -> Vec<(String, String)> {
+    service
+        .self_check()
+        .await
+        .problems
+        .into_iter()
+        .map(|problem| (problem.subject, problem.message))
+        .collect()
+}
+
+#[tokio::test]
+async fn passes_a_valid_service() {
+    let service = BasicService::new(())
+        .tool(divide_tool(6, 3))
+        .template_resource(template("file:///{path}"));
+
+    let report = service.self_check().await;
+    assert!(report.is_ok(), "{report}");
+    assert_eq!(report.checked, 3);
+}
+
+#[tokio::test]
+async fn reports_colliding_names() {
+    let service = BasicService::new(())
+        .tool(divide_tool(6, 3))
+        .tool(divide_tool(6, 2));
+
+    let problems = problems(service).await;
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].0, "tool 'divide'");
+    assert!(problems[0].1.contains("registered more than once"));
+}
+
+#[tokio::test]
+async fn reports_templates_that_do_not_parse() {
+    let service = BasicService::new(())
+        .template_resource(template("file:///{path"))
+        .template_resource(template("file:///{}/raw"));
+
+    assert_eq!(
+        problems(service).await,
+        [
+            (
+                "resource template 'file:///{path'".to_string(),
+                "unclosed '{' in template".to_string()
+            ),
+            (
+                "resource template 'file:///{}/raw'".to_string(),
+                "empty variable name in '{}'".to_string()
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn reports_failing_dry_runs() {
+    let service = BasicService::new(()).tool(divide_tool(1, 0));
+
+    let problems = problems(service).await;
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].0, "tool 'divide' dry run");
+}
+
+#[tokio::test]
+async fn reports_input_schemas_that_are_not_objects() {
+    let service =
+        BasicService::new(()).tool(Tool::builder().name("echo").handler(echo).build().unwrap());
+
+    assert_eq!(
+        problems(service).await,
+        [(
+            "tool 'echo'".to_string(),
+            "input schema must describe an object".to_string()
+        )]
+    );
+}
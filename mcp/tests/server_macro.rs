@@ -0,0 +1,90 @@
+//! Assembling a service with `server!`.
+
+use mcp::resources::MemoryResource;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+#[tokio::test]
+async fn assembles_a_service() {
+    let service = mcp::server! {
+        name: "notes",
+        version: "1.0.0",
+        state: (),
+        tool {
+            name: "search",
+            handler: noop,
+        },
+        tool {
+            name: "fetch",
+            handler: noop,
+        },
+        resource {
+            name: "notes",
+            fixed_uri: "memory://notes",
+            source: MemoryResource::new(),
+        },
+    }
+    .unwrap();
+    let mut client = mcp::testing::harness(service).await.unwrap();
+
+    let tools = client.list_tools().await.unwrap();
+    let mut names: Vec<_> = tools.tools.into_iter().map(|tool| tool.name).collect();
+    names.sort();
+    assert_eq!(names, ["fetch", "search"]);
+}
+
+#[test]
+fn fails_on_duplicate_tool_names() {
+    let result = mcp::server! {
+        name: "notes",
+        version: "1.0.0",
+        state: (),
+        tool {
+            name: "search",
+            handler: noop,
+        },
+        tool {
+            name: "search",
+            description: "Searches again",
+            handler: noop,
+        },
+    };
+
+    let error = result.err().unwrap();
+    assert_eq!(
+        error.message,
+        "The tool 'search' is registered more than once"
+    );
+}
+
+#[test]
+fn fails_on_duplicate_resource_uris() {
+    let result = mcp::server! {
+        name: "notes",
+        version: "1.0.0",
+        state: (),
+        resource {
+            name: "notes",
+            fixed_uri: "memory://notes",
+            source: MemoryResource::new(),
+        },
+        resource {
+            name: "more notes",
+            fixed_uri: "memory://notes",
+            source: MemoryResource::new(),
+        },
+    };
+
+    let error = result.err().unwrap();
+    assert_eq!(
+        error.message,
+        "The resource 'memory://notes' is registered more than once"
+    );
+}
@@ -0,0 +1,152 @@
+//! Serving several services as one with a `ServiceRouter`.
+
+use mcp::registry::resource::FixedResourceUri;
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, Prompt, Resource, ServiceRouter, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// The state of each backend is its prefix, so results tell which backend handled a request
+type Backend = &'static str;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn whoami(backend: Backend, _: Empty) -> Result<String, mcp::Error> {
+    Ok(backend.to_string())
+}
+
+async fn greet(backend: Backend, _: Empty) -> Result<Vec<mcp_schema::PromptMessage>, mcp::Error> {
+    Ok(mcp::content::PromptMessagesBuilder::new()
+        .user(format!("Hello from {backend}"))
+        .build())
+}
+
+fn tool(name: &str) -> Tool<Backend> {
+    Tool::builder().name(name).handler(whoami).build().unwrap()
+}
+
+fn backend(prefix: Backend, tools: &[&str]) -> BasicService<Backend> {
+    let resource = MemoryResource::new();
+    resource.set([serde_json::from_value(json!({
+        "uri": format!("{prefix}://readme"),
+        "text": format!("{prefix} readme"),
+    }))
+    .unwrap()]);
+
+    let service = BasicService::new(prefix)
+        .prompt(
+            Prompt::builder()
+                .name("greet")
+                .handler(greet)
+                .build()
+                .unwrap(),
+        )
+        .fixed_resource(
+            Resource::<Backend, FixedResourceUri>::builder()
+                .name("readme")
+                .fixed_uri(format!("{prefix}://readme"))
+                .source(resource)
+                .build()
+                .unwrap(),
+        );
+    tools
+        .iter()
+        .fold(service, |service, name| service.tool(tool(name)))
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[tokio::test]
+async fn routes_each_method_to_its_service() {
+    let router = ServiceRouter::new()
+        .mount("github", backend("github", &["search"]))
+        .mount("jira", backend("jira", &["search"]));
+    let mut client = mcp::testing::harness(router).await.unwrap();
+
+    let result = client.call_tool("jira_search", json!({})).await.unwrap();
+    assert_eq!(text(&result), "jira");
+    let result = client.call_tool("github_search", json!({})).await.unwrap();
+    assert_eq!(text(&result), "github");
+
+    let prompt = client
+        .get_prompt("jira_greet", HashMap::new())
+        .await
+        .unwrap();
+    let messages = serde_json::to_value(&prompt.messages).unwrap();
+    assert_eq!(messages[0]["content"]["text"], "Hello from jira");
+
+    let resource = client.read_resource("jira://readme").await.unwrap();
+    let contents = serde_json::to_value(&resource.contents).unwrap();
+    assert_eq!(contents[0]["text"], "jira readme");
+
+    let error = client
+        .call_tool("confluence_search", json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, mcp::ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn routes_colliding_names_to_the_longest_prefix() {
+    let router = ServiceRouter::new()
+        .mount(
+            "github",
+            backend("github", &["search", "enterprise_search"]),
+        )
+        .mount(
+            "github_enterprise",
+            backend("github_enterprise", &["search"]),
+        );
+    let mut client = mcp::testing::harness(router).await.unwrap();
+
+    let mut names: Vec<_> = client
+        .list_tools()
+        .await
+        .unwrap()
+        .tools
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["github_enterprise_search", "github_search"]);
+
+    let result = client
+        .call_tool("github_enterprise_search", json!({}))
+        .await
+        .unwrap();
+    assert_eq!(text(&result), "github_enterprise");
+}
+
+#[tokio::test]
+async fn lists_every_page_of_each_service() {
+    let router = ServiceRouter::new()
+        .mount(
+            "github",
+            backend("github", &["search", "create_issue", "close_issue"]).page_size(1),
+        )
+        .mount("jira", backend("jira", &["search", "comment"]).page_size(2));
+    let mut client = mcp::testing::harness(router).await.unwrap();
+
+    let tools = client.list_tools().await.unwrap();
+    assert_eq!(tools.next_cursor, None);
+    let mut names: Vec<_> = tools.tools.into_iter().map(|tool| tool.name).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "github_close_issue",
+            "github_create_issue",
+            "github_search",
+            "jira_comment",
+            "jira_search",
+        ]
+    );
+
+    let resources = client.list_resources().await.unwrap();
+    assert_eq!(resources.resources.len(), 2);
+}
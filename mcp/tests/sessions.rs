@@ -0,0 +1,139 @@
+//! Delivering responses only to the SSE session that posted the request, and log messages at the
+//! level each session set.
+
+use axum::Router;
+use axum::body::{Body, BodyDataStream};
+use axum::http::{Request, header};
+use futures::StreamExt;
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+struct Session {
+    events: BodyDataStream,
+    received: String,
+}
+
+impl Session {
+    async fn open(app: &Router) -> (Self, String) {
+        let request = Request::get("/events").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let mut session = Self {
+            events: response.into_body().into_data_stream(),
+            received: String::new(),
+        };
+        while !session.received.contains("sessionId=") {
+            session.receive().await;
+        }
+        let endpoint = session
+            .received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap()
+            .to_string();
+        session.received.clear();
+        (session, endpoint)
+    }
+
+    async fn receive(&mut self) {
+        let chunk = self.events.next().await.unwrap().unwrap();
+        self.received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+
+    /// Everything received until the stream is quiet for a while
+    async fn drain(&mut self) -> String {
+        while tokio::time::timeout(Duration::from_millis(200), self.receive())
+            .await
+            .is_ok()
+        {}
+        std::mem::take(&mut self.received)
+    }
+}
+
+async fn ping(app: &Router, uri: &str, id: u64) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": id, "method": "ping" }).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["id"], id);
+}
+
+#[tokio::test]
+async fn sessions_only_receive_their_own_responses() {
+    let app = Arc::new(McpImpl::new(BasicService::new(()))).into_router();
+    let (mut first, first_endpoint) = Session::open(&app).await;
+    let (mut second, second_endpoint) = Session::open(&app).await;
+    assert_ne!(first_endpoint, second_endpoint);
+
+    ping(&app, &first_endpoint, 1).await;
+    ping(&app, &second_endpoint, 2).await;
+    ping(&app, "/message", 3).await;
+
+    let received = first.drain().await;
+    assert!(received.contains(r#""id":1"#), "{received}");
+    assert!(!received.contains(r#""id":2"#), "{received}");
+    assert!(!received.contains(r#""id":3"#), "{received}");
+
+    let received = second.drain().await;
+    assert!(received.contains(r#""id":2"#), "{received}");
+    assert!(!received.contains(r#""id":1"#), "{received}");
+    assert!(!received.contains(r#""id":3"#), "{received}");
+}
+
+#[tokio::test]
+async fn session_ids_have_128_random_bits() {
+    let app = Arc::new(McpImpl::new(BasicService::new(()))).into_router();
+    let (_session, endpoint) = Session::open(&app).await;
+
+    let id = endpoint.split("sessionId=").nth(1).unwrap();
+    assert_eq!(id.len(), 32);
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+async fn set_level(app: &Router, uri: &str, level: &str) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "logging/setLevel",
+                "params": { "level": level },
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn log_levels_only_filter_the_session_that_set_them() {
+    let service = BasicService::new(());
+    let logger = service.logger("sessions");
+    let app = Arc::new(McpImpl::new(service)).into_router();
+    let (mut quiet, quiet_endpoint) = Session::open(&app).await;
+    let (mut chatty, _) = Session::open(&app).await;
+
+    set_level(&app, &quiet_endpoint, "error").await;
+    quiet.drain().await;
+    logger.info("routine");
+    logger.error("alarm");
+
+    let received = quiet.drain().await;
+    assert!(received.contains("alarm"), "{received}");
+    assert!(!received.contains("routine"), "{received}");
+
+    let received = chatty.drain().await;
+    assert!(received.contains("alarm"), "{received}");
+    assert!(received.contains("routine"), "{received}");
+}
@@ -0,0 +1,206 @@
+//! Long-running soak tests that churn requests, cancellations, subscriptions and SSE sessions
+//! while asserting that the bookkeeping structures behind them stay bounded.
+//!
+//! Run with `cargo test --features soak --test soak --release`.
+#![cfg(feature = "soak")]
+#![allow(clippy::unused_async)]
+
+use axum::Json;
+use axum::extract::{OriginalUri, Query, State};
+use axum::http::{HeaderMap, Uri};
+use futures::future::pending;
+use mcp::resources::MemoryResource;
+use mcp::rpc::{ClientMessage, SessionQuery};
+use mcp::{BasicService, McpImpl, Resource, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const ITERATIONS: u64 = 5_000;
+const CHURN_CYCLES: u64 = 1_000;
+const RESOURCE_URI: &str = "soak://counter";
+
+#[derive(Deserialize, JsonSchema)]
+struct EchoParams {
+    text: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct HangParams {}
+
+async fn echo(
+    _state: (),
+    params: EchoParams,
+) -> Result<Vec<mcp_schema::PromptContent>, mcp::Error> {
+    Ok(vec![mcp_schema::PromptContent::Text(
+        mcp_schema::TextContent {
+            kind: "text".to_string(),
+            text: params.text,
+            annotated: mcp_schema::Annotated {
+                annotations: None,
+                extra: HashMap::new(),
+            },
+        },
+    )])
+}
+
+async fn hang(
+    _state: (),
+    _params: HangParams,
+) -> Result<Vec<mcp_schema::PromptContent>, mcp::Error> {
+    pending().await
+}
+
+fn service(resource: MemoryResource) -> BasicService<()> {
+    BasicService::new(())
+        .tool(Tool::builder().name("echo").handler(echo).build().unwrap())
+        .tool(Tool::builder().name("hang").handler(hang).build().unwrap())
+        .fixed_resource(
+            Resource::builder()
+                .name("counter")
+                .fixed_uri(RESOURCE_URI)
+                .source(resource)
+                .build()
+                .unwrap(),
+        )
+}
+
+fn message(value: serde_json::Value) -> Json<ClientMessage> {
+    Json(serde_json::from_value(value).unwrap())
+}
+
+fn request(id: u64, method: &str, params: serde_json::Value) -> Json<ClientMessage> {
+    message(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": params,
+    }))
+}
+
+async fn wait_until(mut condition: impl FnMut() -> bool) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !condition() {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("condition was not reached in time");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn completed_requests_do_not_grow_cancel_map() {
+    let mcp = Arc::new(McpImpl::new(service(MemoryResource::new())));
+
+    for id in 0..ITERATIONS {
+        McpImpl::message_handler(
+            State(mcp.clone()),
+            Query(SessionQuery::default()),
+            request(
+                id,
+                "tools/call",
+                json!({ "name": "echo", "arguments": { "text": "hi" } }),
+            ),
+        )
+        .await;
+    }
+
+    assert_eq!(mcp.pending_requests(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cancelled_and_dropped_requests_do_not_grow_cancel_map() {
+    let mcp = Arc::new(McpImpl::new(service(MemoryResource::new())));
+
+    for id in 0..CHURN_CYCLES {
+        let call = tokio::spawn(McpImpl::message_handler(
+            State(mcp.clone()),
+            Query(SessionQuery::default()),
+            request(id, "tools/call", json!({ "name": "hang", "arguments": {} })),
+        ));
+        wait_until(|| mcp.pending_requests() == 1).await;
+
+        if id % 2 == 0 {
+            // Cancelled by the client
+            McpImpl::message_handler(
+                State(mcp.clone()),
+                Query(SessionQuery::default()),
+                message(json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/cancelled",
+                    "params": { "requestId": id, "reason": "soak" },
+                })),
+            )
+            .await;
+            call.await.unwrap();
+        } else {
+            // Dropped by the transport, such as an HTTP client disconnecting
+            call.abort();
+            let _ = call.await;
+        }
+
+        wait_until(|| mcp.pending_requests() == 0).await;
+    }
+
+    assert_eq!(mcp.pending_requests(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subscription_churn_does_not_leak_tasks() {
+    let resource = MemoryResource::new();
+    let mcp = Arc::new(McpImpl::new(service(resource.clone())));
+    let params = json!({ "uri": RESOURCE_URI });
+
+    for cycle in 0..CHURN_CYCLES {
+        let id = cycle * 3;
+        McpImpl::message_handler(
+            State(mcp.clone()),
+            Query(SessionQuery::default()),
+            request(id, "resources/subscribe", params.clone()),
+        )
+        .await;
+        // Subscribing twice must replace, not duplicate, the subscription task
+        McpImpl::message_handler(
+            State(mcp.clone()),
+            Query(SessionQuery::default()),
+            request(id + 1, "resources/subscribe", params.clone()),
+        )
+        .await;
+        resource.set([]);
+        assert!(mcp.service().active_subscriptions() <= 1);
+
+        McpImpl::message_handler(
+            State(mcp.clone()),
+            Query(SessionQuery::default()),
+            request(id + 2, "resources/unsubscribe", params.clone()),
+        )
+        .await;
+    }
+
+    wait_until(|| mcp.service().active_subscriptions() == 0).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sse_session_churn_releases_receivers() {
+    let mcp = Arc::new(McpImpl::new(service(MemoryResource::new())));
+    let baseline = mcp.receiver_count();
+
+    for _ in 0..CHURN_CYCLES {
+        let sessions: Vec<_> = futures::future::join_all((0..8).map(|_| {
+            McpImpl::sse_handler(
+                State(mcp.clone()),
+                OriginalUri(Uri::from_static("/api/events")),
+                HeaderMap::new(),
+            )
+        }))
+        .await;
+        assert_eq!(mcp.receiver_count(), baseline + sessions.len());
+        assert_eq!(mcp.session_count(), sessions.len());
+        drop(sessions);
+        assert_eq!(mcp.receiver_count(), baseline);
+        assert_eq!(mcp.session_count(), 0);
+    }
+}
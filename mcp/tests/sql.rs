@@ -0,0 +1,139 @@
+//! Reading Postgres rows through a `SqlSource` and being notified of changes through `LISTEN`.
+//!
+//! These need a database, so they are skipped unless `DATABASE_URL` is set. Run with
+//! `DATABASE_URL=postgres://... cargo test --features postgres --test sql`.
+#![cfg(feature = "postgres")]
+
+use mcp::resources::SqlSource;
+use mcp::{BasicService, Resource};
+use mcp_schema::ResourceContents;
+use sqlx::PgPool;
+use std::time::Duration;
+
+const CHANNEL: &str = "mcp_changes";
+
+/// A table of its own, dropped at the end of the test, so tests can share a database
+struct Table {
+    pool: PgPool,
+    name: String,
+}
+
+impl Table {
+    /// Connects to `DATABASE_URL` and creates a table of users, or returns `None` if it is unset
+    async fn create() -> Option<Self> {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            eprintln!("Skipping, as DATABASE_URL is unset");
+            return None;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        let name = format!("mcp_users_{}", rand::random::<u32>());
+        sqlx::query(&format!(
+            "CREATE TABLE {name} (id integer PRIMARY KEY, name text NOT NULL)"
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+        Some(Self { pool, name })
+    }
+
+    async fn execute(&self, sql: &str) {
+        sqlx::query(sql).execute(&self.pool).await.unwrap();
+    }
+
+    async fn remove(self) {
+        self.execute(&format!("DROP TABLE {}", self.name)).await;
+    }
+
+    fn service(&self) -> BasicService<()> {
+        BasicService::new(()).template_resource(
+            Resource::builder()
+                .name("rows")
+                .template_uri("db://{table}/{id}")
+                .source(
+                    SqlSource::new(self.pool.clone(), "db://{table}/{id}")
+                        .table(&self.name)
+                        .notify_channel(CHANNEL),
+                )
+                .build()
+                .unwrap(),
+        )
+    }
+}
+
+async fn read_json(client: &mut mcp::testing::McpClient, uri: &str) -> serde_json::Value {
+    let result = client.read_resource(uri).await.unwrap();
+    let [ResourceContents::Text(contents)] = &result.contents[..] else {
+        panic!("expected one text contents");
+    };
+    assert_eq!(contents.mime_type.as_deref(), Some("application/json"));
+    serde_json::from_str(&contents.text).unwrap()
+}
+
+#[tokio::test]
+async fn reads_rows_as_json() {
+    let Some(table) = Table::create().await else {
+        return;
+    };
+    let name = &table.name;
+    table
+        .execute(&format!(
+            "INSERT INTO {name} VALUES (1, 'Ada'), (2, 'Grace')"
+        ))
+        .await;
+    let mut client = mcp::testing::harness(table.service()).await.unwrap();
+
+    assert_eq!(
+        read_json(&mut client, &format!("db://{name}/2")).await,
+        serde_json::json!({ "id": 2, "name": "Grace" })
+    );
+    assert!(
+        client
+            .read_resource(&format!("db://{name}/3"))
+            .await
+            .is_err()
+    );
+    // Tables that aren't allowed aren't found, even if they exist
+    assert!(client.read_resource("db://pg_user/1").await.is_err());
+
+    client.close().await.unwrap();
+    table.remove().await;
+}
+
+#[tokio::test]
+async fn notifies_subscribers_of_listened_changes() {
+    let Some(table) = Table::create().await else {
+        return;
+    };
+    let name = &table.name;
+    table
+        .execute(&format!("INSERT INTO {name} VALUES (1, 'Ada')"))
+        .await;
+    let mut client = mcp::testing::harness(table.service()).await.unwrap();
+    let uri = format!("db://{name}/1");
+    let _updates = client.subscribe(&uri).await.unwrap();
+
+    // The listener may connect just after the subscription is acknowledged, so the change is
+    // announced until it is noticed
+    table
+        .execute(&format!(
+            "UPDATE {name} SET name = 'Ada Lovelace' WHERE id = 1"
+        ))
+        .await;
+    let mut notification = None;
+    for _ in 0..20 {
+        table
+            .execute(&format!("SELECT pg_notify('{CHANNEL}', '{uri}')"))
+            .await;
+        notification = client.next_notification(Duration::from_millis(250)).await;
+        if notification.is_some() {
+            break;
+        }
+    }
+    let notification = serde_json::to_value(notification.unwrap()).unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], uri);
+    assert_eq!(read_json(&mut client, &uri).await["name"], "Ada Lovelace");
+
+    client.close().await.unwrap();
+    table.remove().await;
+}
@@ -0,0 +1,112 @@
+//! Subscribing to resources of a service that isn't served, and the capabilities it advertises.
+
+use mcp::resources::MemoryResource;
+use mcp::{BasicService, ErrorCode, Resource, Service, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+/// The capabilities the service advertises when a client initializes
+async fn capabilities(service: &BasicService<()>) -> serde_json::Value {
+    let request = serde_json::from_value(serde_json::json!({
+        "protocolVersion": mcp_schema::LATEST_PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "subscribe", "version": "1.0.0" },
+    }))
+    .unwrap();
+    let result = service.init(request).await.unwrap();
+    serde_json::to_value(result.capabilities).unwrap()
+}
+
+/// The resources capability the service advertises when a client initializes
+async fn resources_capability(service: &BasicService<()>) -> serde_json::Value {
+    capabilities(service).await["resources"].clone()
+}
+
+#[tokio::test]
+async fn fails_to_subscribe_without_a_notification_handler() {
+    let service = BasicService::new(()).fixed_resource(
+        Resource::builder()
+            .name("readme")
+            .fixed_uri("file:///readme")
+            .source(MemoryResource::new())
+            .build()
+            .unwrap(),
+    );
+    let request = serde_json::from_value(serde_json::json!({ "uri": "file:///readme" })).unwrap();
+
+    let error = service.subscribe(request).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::Internal);
+    assert_eq!(service.active_subscriptions(), 0);
+}
+
+#[tokio::test]
+async fn advertises_subscriptions_to_registered_resources() {
+    let service = BasicService::new(()).fixed_resource(
+        Resource::builder()
+            .name("readme")
+            .fixed_uri("file:///readme")
+            .source(MemoryResource::new())
+            .build()
+            .unwrap(),
+    );
+
+    assert_eq!(resources_capability(&service).await["subscribe"], true);
+}
+
+#[tokio::test]
+async fn does_not_advertise_subscriptions_to_only_tool_documentation() {
+    let service = BasicService::new(()).tool_docs(true).tool(
+        Tool::builder()
+            .name("noop")
+            .docs("Does nothing")
+            .handler(noop)
+            .build()
+            .unwrap(),
+    );
+
+    let resources = resources_capability(&service).await;
+    assert!(resources.is_object());
+    assert_eq!(resources["subscribe"], false);
+}
+
+#[tokio::test]
+async fn advertises_only_logging_when_nothing_is_registered() {
+    let capabilities = capabilities(&BasicService::new(())).await;
+
+    assert_eq!(capabilities["logging"], serde_json::json!({}));
+    assert!(capabilities["tools"].is_null());
+    assert!(capabilities["prompts"].is_null());
+    assert!(capabilities["resources"].is_null());
+}
+
+#[tokio::test]
+async fn advertises_subscriptions_to_template_resources() {
+    let service = BasicService::new(()).template_resource(
+        Resource::builder()
+            .name("file")
+            .template_uri("file:///{path}")
+            .source(MemoryResource::new())
+            .build()
+            .unwrap(),
+    );
+
+    assert_eq!(resources_capability(&service).await["subscribe"], true);
+}
+
+#[tokio::test]
+async fn does_not_advertise_subscriptions_to_only_tool_schemas() {
+    let service = BasicService::new(())
+        .lazy_schemas(true)
+        .tool(Tool::builder().name("noop").handler(noop).build().unwrap());
+
+    let resources = resources_capability(&service).await;
+    assert!(resources.is_object());
+    assert_eq!(resources["subscribe"], false);
+}
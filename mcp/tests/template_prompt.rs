@@ -0,0 +1,95 @@
+//! Rendering prompts from templates with `TemplatePrompt`.
+#![cfg(feature = "templates")]
+
+use mcp::template_prompt::TemplatePrompt;
+use mcp::{BasicService, ErrorCode};
+use std::collections::HashMap;
+
+fn service() -> BasicService<()> {
+    BasicService::new(()).prompt(
+        TemplatePrompt::new("review")
+            .description("Review code")
+            .argument("code", "The code to review", true)
+            .argument("language", "The language of the code", false)
+            .user("Review this{% if language %} {{ language }}{% endif %} code:\n\n{{ code }}")
+            .assistant("{% if language %}I know {{ language }} well.{% endif %}")
+            .build()
+            .unwrap(),
+    )
+}
+
+fn arguments(arguments: &[(&str, &str)]) -> HashMap<String, String> {
+    arguments
+        .iter()
+        .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+        .collect()
+}
+
+/// The role and text of each message of the rendered prompt
+async fn render(
+    client: &mut mcp::testing::McpClient,
+    args: &[(&str, &str)],
+) -> Result<Vec<(String, String)>, mcp::Error> {
+    let result = client.get_prompt("review", arguments(args)).await?;
+    let messages = serde_json::to_value(result.messages).unwrap();
+    Ok(messages
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            (
+                message["role"].as_str().unwrap().to_string(),
+                message["content"]["text"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect())
+}
+
+#[tokio::test]
+async fn renders_messages_with_the_arguments() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let messages = render(
+        &mut client,
+        &[("code", "fn main() {}"), ("language", "Rust")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        messages,
+        [
+            (
+                "user".to_string(),
+                "Review this Rust code:\n\nfn main() {}".to_string()
+            ),
+            ("assistant".to_string(), "I know Rust well.".to_string()),
+        ]
+    );
+
+    // Messages that render to nothing are left out
+    let messages = render(&mut client, &[("code", "print(1)")]).await.unwrap();
+    assert_eq!(
+        messages,
+        [(
+            "user".to_string(),
+            "Review this code:\n\nprint(1)".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn fails_without_a_required_argument() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let error = render(&mut client, &[("language", "Rust")])
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.message, "Missing required argument 'code'");
+}
+
+#[test]
+fn fails_to_build_invalid_templates() {
+    let result = TemplatePrompt::new("broken").user("{% if %}").build::<()>();
+    assert!(result.is_err());
+}
@@ -0,0 +1,206 @@
+//! The todo example driven end to end through the in-memory test harness.
+
+#[path = "../examples/todo/service.rs"]
+mod service;
+
+use futures::StreamExt;
+use serde_json::json;
+use service::{TODOS_URI, Todo};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The methods of the notifications that arrive within the timeout
+async fn methods(client: &mut mcp::testing::McpClient) -> Vec<serde_json::Value> {
+    let mut methods = Vec::new();
+    while let Some(notification) = client.next_notification(TIMEOUT).await {
+        methods.push(serde_json::to_value(notification).unwrap()["method"].clone());
+    }
+    methods
+}
+
+#[tokio::test]
+async fn adds_todos_with_typed_calls() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+
+    let todo: Todo = client
+        .call("add_todo", json!({ "title": "Water the plants" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        todo,
+        Todo {
+            id: 0,
+            title: "Water the plants".to_string(),
+            done: false,
+        }
+    );
+
+    let todos: Vec<Todo> = client.call("list_todos", json!({})).await.unwrap();
+    assert_eq!(todos, [todo]);
+
+    let error = client
+        .call::<Todo>("complete_todo", json!({ "id": 7 }))
+        .await
+        .unwrap_err();
+    assert!(error.message.contains("todo 7 does not exist"));
+}
+
+#[tokio::test]
+async fn lists_and_reads_a_resource_for_each_todo() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+    for title in ["Buy milk", "Call mom"] {
+        client
+            .call::<Todo>("add_todo", json!({ "title": title }))
+            .await
+            .unwrap();
+    }
+
+    let uris: Vec<_> = client
+        .list_resources()
+        .await
+        .unwrap()
+        .resources
+        .into_iter()
+        .map(|resource| resource.uri)
+        .collect();
+    assert_eq!(uris, ["todo://0", "todo://1", TODOS_URI]);
+
+    let result = client.read_resource("todo://1").await.unwrap();
+    let contents = serde_json::to_value(&result.contents).unwrap();
+    let todo: Todo = serde_json::from_str(contents[0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(todo.title, "Call mom");
+
+    client
+        .call_tool("remove_todo", json!({ "id": 1 }))
+        .await
+        .unwrap();
+    assert!(client.read_resource("todo://1").await.is_err());
+}
+
+#[tokio::test]
+async fn notifies_when_the_resource_list_changes() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+
+    client
+        .call::<Todo>("add_todo", json!({ "title": "Buy milk" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        methods(&mut client).await,
+        ["notifications/resources/list_changed"]
+    );
+
+    // Completing a todo changes it, but not which todos there are
+    client
+        .call::<Todo>("complete_todo", json!({ "id": 0 }))
+        .await
+        .unwrap();
+    assert!(methods(&mut client).await.is_empty());
+}
+
+#[tokio::test]
+async fn notifies_subscribers_of_a_todo_when_it_changes() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+    for title in ["Buy milk", "Call mom"] {
+        client
+            .call::<Todo>("add_todo", json!({ "title": title }))
+            .await
+            .unwrap();
+    }
+    client.drain_notifications();
+
+    let mut updates = client.subscribe("todo://0").await.unwrap();
+    // Give the subscription a moment to start watching
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Another todo changing doesn't update this one
+    client
+        .call::<Todo>("complete_todo", json!({ "id": 1 }))
+        .await
+        .unwrap();
+    assert!(tokio::time::timeout(TIMEOUT, updates.next()).await.is_err());
+
+    client
+        .call::<Todo>("complete_todo", json!({ "id": 0 }))
+        .await
+        .unwrap();
+    let update = tokio::time::timeout(TIMEOUT, updates.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.uri, "todo://0");
+    // The update also arrives as a notification
+    let notification = client.next_notification(TIMEOUT).await.unwrap();
+    let notification = serde_json::to_value(notification).unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "todo://0");
+}
+
+#[tokio::test]
+async fn stops_notifying_once_the_subscription_is_dropped() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+    client
+        .call::<Todo>("add_todo", json!({ "title": "Buy milk" }))
+        .await
+        .unwrap();
+    let updates = client.subscribe("todo://0").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    drop(updates);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    client.drain_notifications();
+    client
+        .call::<Todo>("complete_todo", json!({ "id": 0 }))
+        .await
+        .unwrap();
+    let methods = methods(&mut client).await;
+    assert!(
+        !methods.contains(&json!("notifications/resources/updated")),
+        "{methods:?}"
+    );
+}
+
+#[tokio::test]
+async fn plans_the_day_around_open_todos() {
+    let mut client = mcp::testing::harness(service::service().unwrap())
+        .await
+        .unwrap();
+    for title in ["Buy milk", "Call mom"] {
+        client
+            .call::<Todo>("add_todo", json!({ "title": title }))
+            .await
+            .unwrap();
+    }
+    client
+        .call::<Todo>("complete_todo", json!({ "id": 0 }))
+        .await
+        .unwrap();
+
+    let prompts = client.list_prompts().await.unwrap();
+    assert_eq!(prompts.prompts[0].name, "plan_day");
+
+    let result = client
+        .get_prompt(
+            "plan_day",
+            HashMap::from([("focus".to_string(), "family".to_string())]),
+        )
+        .await
+        .unwrap();
+    let messages = serde_json::to_value(&result.messages).unwrap();
+    let text = messages[0]["content"]["text"].as_str().unwrap();
+    assert!(text.contains("- [1] Call mom"));
+    assert!(!text.contains("Buy milk"));
+    assert!(text.ends_with("Prioritize anything related to family."));
+}
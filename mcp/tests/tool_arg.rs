@@ -0,0 +1,55 @@
+//! Folding field attributes of tool inputs into their schemas.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[mcp::tool_input]
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct ForecastInput {
+    #[tool_arg(description = "The city to forecast", example = "London")]
+    city: String,
+    #[tool_arg(min = 1, max = 14, example = 7)]
+    days: u32,
+    /// Kept as written, since no description is given
+    #[tool_arg(pattern = "^[A-Z]{2}$")]
+    country: String,
+    #[tool_arg(min = -90)]
+    #[tool_arg(max = 90)]
+    latitude: f64,
+}
+
+fn property(name: &str) -> serde_json::Value {
+    let schema = serde_json::to_value(schemars::schema_for!(ForecastInput)).unwrap();
+    schema["properties"][name].clone()
+}
+
+#[test]
+fn folds_descriptions_and_examples_into_the_schema() {
+    let city = property("city");
+    assert_eq!(city["description"], "The city to forecast");
+    assert_eq!(city["examples"], serde_json::json!(["London"]));
+
+    let country = property("country");
+    assert_eq!(
+        country["description"],
+        "Kept as written, since no description is given"
+    );
+}
+
+#[test]
+fn folds_ranges_into_the_schema() {
+    let days = property("days");
+    assert_eq!(days["minimum"].as_f64(), Some(1.0));
+    assert_eq!(days["maximum"].as_f64(), Some(14.0));
+    assert_eq!(days["examples"], serde_json::json!([7]));
+
+    let latitude = property("latitude");
+    assert_eq!(latitude["minimum"].as_f64(), Some(-90.0));
+    assert_eq!(latitude["maximum"].as_f64(), Some(90.0));
+}
+
+#[test]
+fn folds_patterns_into_the_schema() {
+    assert_eq!(property("country")["pattern"], "^[A-Z]{2}$");
+}
@@ -0,0 +1,114 @@
+//! Listing only the tools that changed since a client last listed them.
+
+use mcp::{BasicService, Service, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+fn tool(name: &str, description: &str) -> Tool<()> {
+    Tool::builder()
+        .name(name)
+        .description(description)
+        .handler(noop)
+        .build()
+        .unwrap()
+}
+
+fn before() -> BasicService<()> {
+    BasicService::new(())
+        .tool(tool("echo", "Echoes"))
+        .tool(tool("search", "Searches"))
+        .tool(tool("legacy", "Going away"))
+}
+
+fn after() -> BasicService<()> {
+    BasicService::new(())
+        .tool(tool("echo", "Echoes"))
+        .tool(tool("search", "Searches the web"))
+        .tool(tool("fetch", "Fetches"))
+}
+
+async fn list(service: &BasicService<()>, params: serde_json::Value) -> serde_json::Value {
+    let result = service
+        .list_tools(serde_json::from_value(params).unwrap())
+        .await
+        .unwrap();
+    serde_json::to_value(result).unwrap()
+}
+
+fn names(listing: &serde_json::Value) -> Vec<&str> {
+    let tools = listing["tools"].as_array().unwrap();
+    tools
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect()
+}
+
+/// The `schemaHash` of each listed tool by name, as a client would cache them
+fn known_tools(listing: &serde_json::Value) -> serde_json::Value {
+    let tools = listing["tools"].as_array().unwrap();
+    tools
+        .iter()
+        .map(|tool| (tool["name"].clone(), tool["_meta"]["schemaHash"].clone()))
+        .map(|(name, hash)| (name.as_str().unwrap().to_string(), hash))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+#[tokio::test]
+async fn leaves_out_tools_when_the_catalog_is_unchanged() {
+    let service = before();
+    let listing = list(&service, json!({})).await;
+
+    let listing = list(&service, json!({ "ifNoneMatch": listing["catalogHash"] })).await;
+    assert_eq!(listing["notModified"], true);
+    assert!(names(&listing).is_empty());
+}
+
+#[tokio::test]
+async fn lists_the_changes_since_the_known_tools() {
+    let known = known_tools(&list(&before(), json!({})).await);
+
+    let listing = list(&after(), json!({ "knownTools": known })).await;
+    assert_eq!(
+        listing["changes"],
+        json!({ "added": ["fetch"], "changed": ["search"], "removed": ["legacy"] })
+    );
+    let mut listed = names(&listing);
+    listed.sort_unstable();
+    assert_eq!(listed, ["fetch", "search"]);
+    assert_eq!(
+        listing["catalogHash"],
+        list(&after(), json!({})).await["catalogHash"]
+    );
+}
+
+#[tokio::test]
+async fn lists_nothing_when_every_known_tool_is_current() {
+    let service = after();
+    let known = known_tools(&list(&service, json!({})).await);
+
+    let listing = list(&service, json!({ "_meta": { "knownTools": known } })).await;
+    assert_eq!(
+        listing["changes"],
+        json!({ "added": [], "changed": [], "removed": [] })
+    );
+    assert!(names(&listing).is_empty());
+}
+
+#[tokio::test]
+async fn lists_every_tool_when_paged() {
+    let service = after().page_size(10);
+    let known = known_tools(&list(&before(), json!({})).await);
+
+    let listing = list(&service, json!({ "knownTools": known })).await;
+    assert!(listing["changes"].is_null());
+    assert_eq!(names(&listing).len(), 3);
+}
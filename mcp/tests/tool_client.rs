@@ -0,0 +1,111 @@
+//! Typed client methods generated from the `tools.json` manifest of a service.
+
+use mcp::{BasicService, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+mcp::tool_client!(CalculatorTools, "tests/tools.json");
+
+#[derive(Deserialize, JsonSchema)]
+struct AddParams {
+    a: i64,
+    b: i64,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct GreetParams {
+    /// Who to greet
+    name: String,
+    excited: Option<bool>,
+}
+
+async fn add((): (), params: AddParams) -> Result<String, mcp::Error> {
+    Ok((params.a + params.b).to_string())
+}
+
+async fn greet((): (), params: GreetParams) -> Result<String, mcp::Error> {
+    let punctuation = if params.excited.unwrap_or(false) {
+        "!"
+    } else {
+        "."
+    };
+    Ok(format!("Hello, {}{punctuation}", params.name))
+}
+
+fn service() -> BasicService<()> {
+    BasicService::new(())
+        .name("calculator".to_string())
+        .version("1.0.0".to_string())
+        .tool(
+            Tool::builder()
+                .name("add")
+                .description("Adds two numbers")
+                .handler(add)
+                .build()
+                .unwrap(),
+        )
+        .tool(
+            Tool::builder()
+                .name("greet")
+                .description("Greets someone")
+                .handler(greet)
+                .build()
+                .unwrap(),
+        )
+}
+
+fn text(result: &mcp_schema::CallToolResult) -> serde_json::Value {
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[test]
+fn manifest_matches_the_service() {
+    let manifest: serde_json::Value = serde_json::from_str(include_str!("tools.json")).unwrap();
+    let exported = service().tool_manifest().unwrap();
+
+    let inputs = |manifest: &serde_json::Value| -> Vec<_> {
+        manifest["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| {
+                let schema = &tool["inputSchema"];
+                let mut properties: Vec<_> = schema["properties"]
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect();
+                properties.sort();
+                (tool["name"].clone(), properties, schema["required"].clone())
+            })
+            .collect()
+    };
+    assert_eq!(inputs(&manifest), inputs(&exported));
+}
+
+#[tokio::test]
+async fn calls_tools_with_typed_inputs() {
+    let mut client = mcp::testing::harness(service()).await.unwrap();
+
+    let result = client.add(AddInput { a: 1, b: 2 }).await.unwrap();
+    assert_eq!(text(&result), "3");
+
+    let result = client
+        .greet(GreetInput {
+            name: "Ada".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(text(&result), "Hello, Ada.");
+
+    let result = client
+        .greet(GreetInput {
+            name: "Ada".to_string(),
+            excited: Some(true),
+        })
+        .await
+        .unwrap();
+    assert_eq!(text(&result), "Hello, Ada!");
+}
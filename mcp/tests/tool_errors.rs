@@ -0,0 +1,112 @@
+//! Reporting tool failures as `isError` results and request problems as JSON-RPC errors.
+
+use mcp::{ErrorCode, Tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct DivideParams {
+    dividend: i64,
+    divisor: i64,
+}
+
+async fn divide((): (), params: DivideParams) -> Result<String, mcp::Error> {
+    if params.divisor < 0 {
+        return Err(mcp::Error::invalid_params(
+            "The divisor must not be negative",
+        ));
+    }
+    if params.divisor == 0 {
+        return Err(eyre::eyre!("Cannot divide by zero").into());
+    }
+    Ok((params.dividend / params.divisor).to_string())
+}
+
+fn registry() -> ToolRegistry<()> {
+    let mut registry = ToolRegistry::new();
+    registry.register(
+        Tool::builder()
+            .name("divide")
+            .handler(divide)
+            .build()
+            .unwrap(),
+    );
+    registry
+}
+
+fn call(dividend: i64, divisor: serde_json::Value) -> mcp_schema::CallToolParams {
+    serde_json::from_value(serde_json::json!({
+        "name": "divide",
+        "arguments": { "dividend": dividend, "divisor": divisor },
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn reports_failures_of_the_tool_as_results() {
+    let result = registry()
+        .call_tool((), call(1, serde_json::json!(0)))
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(true));
+    let content = serde_json::to_value(&result.content).unwrap();
+    assert_eq!(content[0]["text"], "Cannot divide by zero");
+}
+
+#[tokio::test]
+async fn reports_failures_to_initialize_the_tool_as_results() {
+    let mut registry = ToolRegistry::new();
+    registry.register(
+        Tool::builder()
+            .name("divide")
+            .handler(divide)
+            .init(|()| async { Err::<(), _>(mcp::Error::internal("Database unreachable")) })
+            .init_on_first_call(true)
+            .build()
+            .unwrap(),
+    );
+
+    let result = registry
+        .call_tool((), call(8, serde_json::json!(4)))
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(true));
+    let content = serde_json::to_value(&result.content).unwrap();
+    assert_eq!(
+        content[0]["text"],
+        "Tool failed to initialize: Database unreachable"
+    );
+}
+
+#[tokio::test]
+async fn sends_invalid_arguments_as_errors() {
+    let error = registry()
+        .call_tool((), call(1, serde_json::json!("two")))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn sends_errors_the_handler_marks_as_invalid_params() {
+    let error = registry()
+        .call_tool((), call(1, serde_json::json!(-1)))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.message, "The divisor must not be negative");
+}
+
+#[tokio::test]
+async fn returns_results_of_successful_calls() {
+    let result = registry()
+        .call_tool((), call(8, serde_json::json!(4)))
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(false));
+}
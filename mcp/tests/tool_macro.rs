@@ -0,0 +1,136 @@
+//! Building tools from handlers checked by `#[tool]`.
+
+use mcp::{FromRef, ToolRegistry};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone)]
+struct AppState {
+    greeting: Greeting,
+}
+
+#[derive(Clone)]
+struct Greeting(&'static str);
+
+impl FromRef<AppState> for Greeting {
+    fn from_ref(state: &AppState) -> Self {
+        state.greeting.clone()
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct GreetInput {
+    name: String,
+}
+
+/// Greets someone
+///
+/// by their name
+#[mcp::tool]
+async fn greet(state: AppState, input: GreetInput) -> Result<String, mcp::Error> {
+    Ok(format!("{} {}", state.greeting.0, input.name))
+}
+
+#[mcp::tool(
+    name = "greet-briefly",
+    title = "Greet briefly",
+    version = "1.2",
+    annotations(read_only = true, open_world = false)
+)]
+async fn greet_briefly(greeting: Greeting, input: GreetInput) -> Result<String, mcp::Error> {
+    Ok(format!("{}, {}", greeting.0, input.name))
+}
+
+#[derive(Serialize, JsonSchema, mcp::IntoContents)]
+struct Greeted {
+    text: String,
+}
+
+#[mcp::tool(output)]
+async fn greet_structured(state: AppState, input: GreetInput) -> Result<Greeted, mcp::Error> {
+    Ok(Greeted {
+        text: format!("{} {}", state.greeting.0, input.name),
+    })
+}
+
+#[mcp::tool(output = Greeted)]
+async fn greet_declared(state: AppState, input: GreetInput) -> Result<Greeted, mcp::Error> {
+    greet_structured(state, input).await
+}
+
+fn registry() -> ToolRegistry<AppState> {
+    let mut registry = ToolRegistry::new();
+    registry.register(greet_tool().unwrap());
+    registry.register(greet_briefly_tool().unwrap());
+    registry.register(greet_structured_tool().unwrap());
+    registry.register(greet_declared_tool().unwrap());
+    registry
+}
+
+async fn call(name: &str) -> serde_json::Value {
+    let state = AppState {
+        greeting: Greeting("Hello"),
+    };
+    let request = json!({ "name": name, "arguments": { "name": "Ada" } });
+    let result = registry()
+        .call_tool(state, serde_json::from_value(request).unwrap())
+        .await
+        .unwrap();
+    serde_json::to_value(&result.content).unwrap()[0]["text"].clone()
+}
+
+#[test]
+fn describes_tools_with_the_doc_comments_of_their_handlers() {
+    let registry = registry();
+    let tools: Vec<_> = registry
+        .page(None, 10)
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|tool| serde_json::to_value(mcp_schema::Tool::try_from(tool).unwrap()).unwrap())
+        .collect();
+
+    assert_eq!(tools[0]["name"], "greet");
+    assert_eq!(tools[0]["description"], "Greets someone\n\nby their name");
+    assert_eq!(tools[1]["name"], "greet-briefly");
+    assert!(tools[1]["description"].is_null());
+}
+
+#[test]
+fn lists_the_title_version_and_annotations_of_tools() {
+    let registry = registry();
+    let tool = registry.get("greet-briefly").unwrap();
+    let tool = serde_json::to_value(mcp_schema::Tool::try_from(tool).unwrap()).unwrap();
+
+    assert_eq!(tool["title"], "Greet briefly");
+    assert_eq!(tool["_meta"]["version"], "1.2");
+    assert_eq!(
+        tool["annotations"],
+        json!({ "readOnlyHint": true, "openWorldHint": false })
+    );
+}
+
+#[tokio::test]
+async fn calls_the_handler() {
+    assert_eq!(call("greet").await, "Hello Ada");
+}
+
+#[tokio::test]
+async fn extracts_the_sub_state_of_the_handler() {
+    assert_eq!(call("greet-briefly").await, "Hello, Ada");
+}
+
+#[test]
+fn advertises_the_output_schema_of_tools() {
+    let registry = registry();
+    for name in ["greet_structured", "greet_declared"] {
+        let tool = registry.get(name).unwrap();
+        let tool = serde_json::to_value(mcp_schema::Tool::try_from(tool).unwrap()).unwrap();
+
+        assert_eq!(tool["outputSchema"]["properties"]["text"]["type"], "string");
+    }
+    let tool = registry.get("greet").unwrap();
+    let tool = serde_json::to_value(mcp_schema::Tool::try_from(tool).unwrap()).unwrap();
+    assert!(tool["outputSchema"].is_null());
+}
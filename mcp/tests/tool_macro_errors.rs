@@ -0,0 +1,8 @@
+//! Mistakes in `#[mcp::tool]` handlers are compile errors at the offending code. The expected
+//! errors are in `tests/ui/tool/*.stderr`, which `TRYBUILD=overwrite` regenerates.
+
+#[test]
+fn reports_invalid_handlers() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/tool/*.rs");
+}
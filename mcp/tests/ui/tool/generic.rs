@@ -0,0 +1,15 @@
+use mcp::Error;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Input {}
+
+#[mcp::tool]
+async fn search<T>((): (), _input: Input) -> Result<String, Error> {
+    Ok(String::new())
+}
+
+fn main() {
+    let _ = Input {};
+}
@@ -0,0 +1,14 @@
+use mcp::Error;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Input {}
+
+#[mcp::tool]
+async fn search((): (), _input: Input) -> Result<String, Error> {
+    Ok(String::new())
+}
+
+fn main() {
+    let _ = Input {};
+}
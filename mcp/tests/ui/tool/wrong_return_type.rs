@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+struct Input {}
+
+#[mcp::tool]
+async fn search((): (), _input: Input) -> String {
+    String::new()
+}
+
+fn main() {
+    let _ = Input {};
+}
@@ -0,0 +1,294 @@
+//! Matching of uris against RFC 6570 uri templates, and the choice between overlapping template
+//! resources.
+
+use mcp::error::ErrorCode;
+use mcp::registry::UriTemplate;
+use mcp::registry::resource::TemplateSource;
+use mcp::resources::MemoryResource;
+use mcp::{Resource, ResourceRegistry};
+use mcp_schema::{ResourceContents, TextResourceContents};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+fn matches(template: &str, uri: &str) -> Option<HashMap<String, String>> {
+    UriTemplate::new(template).matches(uri)
+}
+
+fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+        .collect()
+}
+
+#[test]
+fn matches_literal_templates_exactly() {
+    assert_eq!(matches("todo://all", "todo://all"), Some(HashMap::new()));
+    assert_eq!(matches("todo://all", "todo://all/"), None);
+    assert_eq!(matches("todo://all", "todo://al"), None);
+}
+
+#[test]
+fn extracts_simple_variables() {
+    assert_eq!(
+        matches("users://{id}", "users://42"),
+        Some(variables(&[("id", "42")]))
+    );
+    assert_eq!(
+        matches("users://{id}/posts/{post}", "users://42/posts/7"),
+        Some(variables(&[("id", "42"), ("post", "7")]))
+    );
+}
+
+#[test]
+fn simple_variables_stop_at_reserved_characters() {
+    assert_eq!(matches("users://{id}", "users://42/posts"), None);
+    assert_eq!(matches("users://{id}", "users://42?page=2"), None);
+}
+
+#[test]
+fn decodes_percent_encoded_values() {
+    assert_eq!(
+        matches("search://{query}", "search://hello%20world%2Fagain"),
+        Some(variables(&[("query", "hello world/again")]))
+    );
+}
+
+#[test]
+fn reserved_expansion_spans_slashes() {
+    assert_eq!(
+        matches("file:///{+path}", "file:///home/user/notes.txt"),
+        Some(variables(&[("path", "home/user/notes.txt")]))
+    );
+    assert_eq!(
+        matches("file:///{+path}/raw", "file:///a/b/raw"),
+        Some(variables(&[("path", "a/b")]))
+    );
+}
+
+#[test]
+fn fragment_expansion() {
+    assert_eq!(
+        matches("doc://readme{#section}", "doc://readme#install"),
+        Some(variables(&[("section", "install")]))
+    );
+    assert_eq!(
+        matches("doc://readme{#section}", "doc://readme"),
+        Some(HashMap::new())
+    );
+}
+
+#[test]
+fn label_and_path_expansion() {
+    assert_eq!(
+        matches("file://{name}{.ext}", "file://report.pdf"),
+        Some(variables(&[("name", "report"), ("ext", "pdf")]))
+    );
+    assert_eq!(
+        matches("repo://{owner}{/path*}", "repo://me/src/lib.rs"),
+        Some(variables(&[("owner", "me"), ("path", "src/lib.rs")]))
+    );
+    assert_eq!(
+        matches("repo://x{/a,b}", "repo://x/one/two"),
+        Some(variables(&[("a", "one"), ("b", "two")]))
+    );
+}
+
+#[test]
+fn query_expansion_is_optional_and_named() {
+    let template = "users://{id}/posts{?page,limit}";
+    assert_eq!(
+        matches(template, "users://1/posts?page=2&limit=10"),
+        Some(variables(&[("id", "1"), ("page", "2"), ("limit", "10")]))
+    );
+    assert_eq!(
+        matches(template, "users://1/posts?limit=10"),
+        Some(variables(&[("id", "1"), ("limit", "10")]))
+    );
+    assert_eq!(
+        matches(template, "users://1/posts"),
+        Some(variables(&[("id", "1")]))
+    );
+    assert_eq!(matches(template, "users://1/posts?sort=new"), None);
+    assert_eq!(
+        matches(
+            "users://{id}/posts?page=1{&limit}",
+            "users://1/posts?page=1&limit=5"
+        ),
+        Some(variables(&[("id", "1"), ("limit", "5")]))
+    );
+}
+
+#[test]
+fn multiple_simple_variables_in_one_expression() {
+    assert_eq!(
+        matches("map://{x,y}", "map://3,4"),
+        Some(variables(&[("x", "3"), ("y", "4")]))
+    );
+    assert_eq!(matches("map://{x,y}", "map://3,4,5"), None);
+}
+
+#[test]
+fn repeated_variables_must_agree() {
+    assert_eq!(
+        matches("mirror://{name}/{name}", "mirror://a/a"),
+        Some(variables(&[("name", "a")]))
+    );
+    assert_eq!(matches("mirror://{name}/{name}", "mirror://a/b"), None);
+}
+
+#[test]
+fn ignores_prefix_modifiers() {
+    assert_eq!(
+        matches("hash://{digest:7}", "hash://abcdef0"),
+        Some(variables(&[("digest", "abcdef0")]))
+    );
+}
+
+#[test]
+fn unclosed_braces_are_literal() {
+    assert_eq!(matches("odd://{id", "odd://{id"), Some(HashMap::new()));
+}
+
+fn text_resource(uri: &str, text: &str) -> MemoryResource {
+    let resource = MemoryResource::new();
+    resource.set([ResourceContents::Text(TextResourceContents {
+        uri: uri.to_string(),
+        mime_type: None,
+        text: text.to_string(),
+    })]);
+    resource
+}
+
+fn template(uri: &str, text: &str) -> Resource<(), mcp::registry::resource::TemplateResourceUri> {
+    Resource::builder()
+        .name(text)
+        .template_uri(uri)
+        .source(text_resource(uri, text))
+        .build()
+        .unwrap()
+}
+
+async fn read(registry: &ResourceRegistry<()>, uri: &str) -> Option<String> {
+    let result = registry.read_resource((), uri.to_string()).await.ok()?;
+    let ResourceContents::Text(contents) = result.contents.into_iter().next()? else {
+        return None;
+    };
+    Some(contents.text)
+}
+
+#[tokio::test]
+async fn most_specific_overlapping_template_wins() {
+    let mut registry = ResourceRegistry::new();
+    registry.register_template(template("repo://{owner}/{+path}", "generic"));
+    registry.register_template(template("repo://{owner}/README.md", "readme"));
+    registry.register_template(template("repo://{owner}/docs/{page}", "docs"));
+
+    assert_eq!(
+        read(&registry, "repo://me/README.md").await.as_deref(),
+        Some("readme")
+    );
+    assert_eq!(
+        read(&registry, "repo://me/docs/intro").await.as_deref(),
+        Some("docs")
+    );
+    assert_eq!(
+        read(&registry, "repo://me/src/lib.rs").await.as_deref(),
+        Some("generic")
+    );
+    assert_eq!(read(&registry, "other://me").await, None);
+}
+
+#[tokio::test]
+async fn first_registered_wins_between_equally_specific_templates() {
+    let mut registry = ResourceRegistry::new();
+    registry.register_template(template("items://{id}", "first"));
+    registry.register_template(template("items://{name}", "second"));
+
+    assert_eq!(read(&registry, "items://1").await.as_deref(), Some("first"));
+}
+
+#[tokio::test]
+async fn fixed_resources_take_precedence_over_templates() {
+    let mut registry = ResourceRegistry::new();
+    registry.register_template(template("items://{id}", "template"));
+    registry.register_fixed(
+        Resource::builder()
+            .name("all")
+            .fixed_uri("items://all")
+            .source(text_resource("items://all", "fixed"))
+            .build()
+            .unwrap(),
+    );
+
+    assert_eq!(
+        read(&registry, "items://all").await.as_deref(),
+        Some("fixed")
+    );
+    assert_eq!(
+        read(&registry, "items://7").await.as_deref(),
+        Some("template")
+    );
+}
+
+#[derive(Deserialize)]
+struct PostParams {
+    id: u32,
+    page: Option<u32>,
+}
+
+struct Posts;
+
+impl TemplateSource<()> for Posts {
+    type Params = PostParams;
+
+    fn read(
+        &self,
+        (): (),
+        uri: String,
+        PostParams { id, page }: PostParams,
+    ) -> impl Future<Output = Result<Vec<ResourceContents>, mcp::Error>> + Send + 'static {
+        std::future::ready(Ok(vec![ResourceContents::Text(TextResourceContents {
+            uri,
+            mime_type: None,
+            text: format!("{id}:{}", page.unwrap_or(1)),
+        })]))
+    }
+
+    fn wait_for_change(
+        &self,
+        (): (),
+        _: String,
+        _: PostParams,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        std::future::pending()
+    }
+}
+
+#[tokio::test]
+async fn typed_sources_receive_deserialized_variables() {
+    let mut registry = ResourceRegistry::new();
+    registry.register_template(
+        Resource::builder()
+            .name("posts")
+            .template_uri("users://{id}/posts{?page}")
+            .typed_source(Posts)
+            .build()
+            .unwrap(),
+    );
+
+    assert_eq!(
+        read(&registry, "users://7/posts?page=3").await.as_deref(),
+        Some("7:3")
+    );
+    assert_eq!(
+        read(&registry, "users://7/posts").await.as_deref(),
+        Some("7:1")
+    );
+
+    let error = registry
+        .read_resource((), "users://me/posts".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
@@ -0,0 +1,67 @@
+//! Running the init hooks of tools once with `warm_up`, before they are first called.
+
+use mcp::{BasicService, Tool};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Deserialize, JsonSchema)]
+struct Empty {}
+
+async fn noop((): (), _: Empty) -> Result<String, mcp::Error> {
+    Ok(String::new())
+}
+
+/// A tool whose init hook counts its runs
+fn tool(name: &str, on_first_call: bool) -> (Tool<()>, Arc<AtomicUsize>) {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let counter = inits.clone();
+    let tool = Tool::builder()
+        .name(name)
+        .handler(noop)
+        .init(move |()| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        })
+        .init_on_first_call(on_first_call)
+        .build()
+        .unwrap();
+    (tool, inits)
+}
+
+#[tokio::test]
+async fn runs_each_init_once_before_the_first_call() {
+    let (search, search_inits) = tool("search", false);
+    let (fetch, fetch_inits) = tool("fetch", false);
+    let service = BasicService::new(()).tool(search).tool(fetch);
+
+    let report = service.warm_up().await;
+    assert!(report.is_ok(), "{report}");
+    assert_eq!(search_inits.load(Ordering::SeqCst), 1);
+    assert_eq!(fetch_inits.load(Ordering::SeqCst), 1);
+
+    let mut client = mcp::testing::harness(service).await.unwrap();
+    for _ in 0..2 {
+        client.call_tool("search", json!({})).await.unwrap();
+        client.call_tool("fetch", json!({})).await.unwrap();
+    }
+    assert_eq!(search_inits.load(Ordering::SeqCst), 1);
+    assert_eq!(fetch_inits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn leaves_inits_on_first_call_for_the_first_call() {
+    let (search, inits) = tool("search", true);
+    let service = BasicService::new(()).tool(search);
+
+    let report = service.warm_up().await;
+    assert!(report.is_ok(), "{report}");
+    assert_eq!(inits.load(Ordering::SeqCst), 0);
+
+    let mut client = mcp::testing::harness(service).await.unwrap();
+    client.call_tool("search", json!({})).await.unwrap();
+    client.call_tool("search", json!({})).await.unwrap();
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,70 @@
+//! Subscribing to a `WatchedFileResource` and being notified as the file is written.
+#![cfg(feature = "notify")]
+
+use mcp::resources::WatchedFileResource;
+use mcp::{BasicService, Resource};
+use mcp_schema::ResourceContents;
+use std::path::Path;
+use std::time::Duration;
+
+const URI: &str = "file:///notes.md";
+
+fn service(path: &Path) -> BasicService<()> {
+    BasicService::new(()).fixed_resource(
+        Resource::builder()
+            .name("notes")
+            .fixed_uri(URI)
+            .source(WatchedFileResource::new(path).mime_type("text/markdown"))
+            .build()
+            .unwrap(),
+    )
+}
+
+async fn read_text(client: &mut mcp::testing::McpClient) -> String {
+    let result = client.read_resource(URI).await.unwrap();
+    match &result.contents[..] {
+        [ResourceContents::Text(contents)] => contents.text.clone(),
+        _ => panic!("expected one text contents"),
+    }
+}
+
+#[tokio::test]
+async fn notifies_subscribers_when_the_file_is_written() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("notes.md");
+    std::fs::write(&path, "# Monday").unwrap();
+    let mut client = mcp::testing::harness(service(&path)).await.unwrap();
+
+    assert_eq!(read_text(&mut client).await, "# Monday");
+    let _updates = client.subscribe(URI).await.unwrap();
+
+    // The watcher may start just after the subscription is acknowledged, so the file is written
+    // until a change is noticed
+    let mut notification = None;
+    for attempt in 0..20 {
+        std::fs::write(&path, format!("# Tuesday {attempt}")).unwrap();
+        notification = client.next_notification(Duration::from_millis(250)).await;
+        if notification.is_some() {
+            break;
+        }
+    }
+    let notification = serde_json::to_value(notification.unwrap()).unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], URI);
+
+    assert!(read_text(&mut client).await.starts_with("# Tuesday"));
+}
+
+#[tokio::test]
+async fn reads_binary_files_as_blobs() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("notes.md");
+    std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+    let mut client = mcp::testing::harness(service(&path)).await.unwrap();
+
+    let result = client.read_resource(URI).await.unwrap();
+    let [ResourceContents::Blob(contents)] = &result.contents[..] else {
+        panic!("expected one blob contents");
+    };
+    assert_eq!(contents.mime_type.as_deref(), Some("text/markdown"));
+}
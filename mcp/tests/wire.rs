@@ -0,0 +1,296 @@
+//! Serving over byte streams in each wire format.
+
+use mcp::{BasicService, McpImpl};
+use serde_json::json;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::task::JoinHandle;
+
+const LIMIT: usize = 1024;
+
+struct Client {
+    writer: DuplexStream,
+    reader: BufReader<DuplexStream>,
+    served: JoinHandle<std::io::Result<()>>,
+}
+
+/// Serves over in-memory pipes, returning the client end
+fn serve(service: BasicService<()>, limit: usize) -> Client {
+    let (writer, server_reader) = tokio::io::duplex(1024 * 1024);
+    let (server_writer, reader) = tokio::io::duplex(1024 * 1024);
+    let mcp = Arc::new(McpImpl::new(service).max_message_size(limit));
+    Client {
+        writer,
+        reader: BufReader::new(reader),
+        served: tokio::spawn(mcp.serve_over(server_reader, server_writer)),
+    }
+}
+
+impl Client {
+    async fn send_json(&mut self, message: &serde_json::Value) {
+        let mut line = serde_json::to_vec(message).unwrap();
+        line.push(b'\n');
+        self.writer.write_all(&line).await.unwrap();
+    }
+
+    async fn receive_json(&mut self) -> serde_json::Value {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+}
+
+fn initialize(capabilities: &serde_json::Value) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": mcp_schema::LATEST_PROTOCOL_VERSION,
+            "capabilities": capabilities,
+            "clientInfo": { "name": "wire", "version": "1.0.0" },
+        },
+    })
+}
+
+fn ping(id: u64) -> serde_json::Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": "ping" })
+}
+
+#[tokio::test]
+async fn round_trips_json() {
+    let mut client = serve(BasicService::new(()), LIMIT);
+
+    client.send_json(&initialize(&json!({}))).await;
+    let response = client.receive_json().await;
+    assert_eq!(response["id"], 1);
+    assert!(
+        response["result"]["capabilities"]["experimental"]
+            .get("encoding")
+            .is_none()
+    );
+
+    client.send_json(&ping(2)).await;
+    assert_eq!(client.receive_json().await["id"], 2);
+}
+
+#[tokio::test]
+async fn rejects_json_lines_over_the_limit() {
+    let mut client = serve(BasicService::new(()), LIMIT);
+
+    let mut line = vec![b' '; LIMIT * 4];
+    line.push(b'\n');
+    client.writer.write_all(&line).await.unwrap();
+
+    let error = client.served.await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn drops_a_json_line_cut_off_by_the_end_of_the_input() {
+    let mut client = serve(BasicService::new(()), LIMIT);
+
+    client
+        .writer
+        .write_all(br#"{"jsonrpc":"2.0","id":1,"me"#)
+        .await
+        .unwrap();
+    client.writer.shutdown().await.unwrap();
+
+    client.served.await.unwrap().unwrap();
+    let mut line = String::new();
+    client.reader.read_line(&mut line).await.unwrap();
+    assert!(line.is_empty(), "unexpected response {line:?}");
+}
+
+#[tokio::test]
+async fn answers_requests_in_flight_before_returning_at_the_end_of_the_input() {
+    let mut client = serve(BasicService::new(()), LIMIT);
+
+    client.send_json(&ping(1)).await;
+    client.writer.shutdown().await.unwrap();
+
+    assert_eq!(client.receive_json().await["id"], 1);
+    client.served.await.unwrap().unwrap();
+}
+
+#[cfg(feature = "msgpack")]
+mod msgpack {
+    use super::*;
+    use mcp::Error;
+    use mcp::rpc::ClientMessage;
+    use tokio::io::AsyncReadExt;
+
+    impl Client {
+        async fn send_frame(&mut self, message: &serde_json::Value) {
+            let body = rmp_serde::to_vec_named(message).unwrap();
+            self.send_body(&body, 0).await;
+        }
+
+        async fn send_body(&mut self, body: &[u8], flag: u32) {
+            let len = u32::try_from(body.len()).unwrap() | flag;
+            self.writer.write_all(&len.to_be_bytes()).await.unwrap();
+            self.writer.write_all(body).await.unwrap();
+        }
+
+        /// Reads a frame, returning whether it was compressed
+        async fn receive_frame(&mut self) -> (serde_json::Value, bool) {
+            let prefix = self.reader.read_u32().await.unwrap();
+            let mut body = vec![0; (prefix & !(1 << 31)) as usize];
+            self.reader.read_exact(&mut body).await.unwrap();
+            let compressed = prefix & (1 << 31) != 0;
+            #[cfg(feature = "zstd")]
+            if compressed {
+                body = zstd::decode_all(&body[..]).unwrap();
+            }
+            (rmp_serde::from_slice(&body).unwrap(), compressed)
+        }
+
+        /// Initializes in JSON and switches to the negotiated wire format
+        async fn negotiate(&mut self, capabilities: &serde_json::Value) -> serde_json::Value {
+            self.send_json(&initialize(capabilities)).await;
+            self.receive_json().await
+        }
+    }
+
+    #[tokio::test]
+    async fn switches_to_message_pack_after_initialize() {
+        let mut client = serve(BasicService::new(()), LIMIT);
+
+        let response = client
+            .negotiate(&json!({ "experimental": { "encodings": ["msgpack"] } }))
+            .await;
+        assert_eq!(
+            response["result"]["capabilities"]["experimental"]["encoding"],
+            "msgpack"
+        );
+
+        client.send_frame(&ping(2)).await;
+        let (response, compressed) = client.receive_frame().await;
+        assert_eq!(response["id"], 2);
+        assert!(!compressed);
+    }
+
+    #[tokio::test]
+    async fn keeps_json_when_initialize_fails() {
+        let service =
+            BasicService::new(()).middleware(|message: &mut ClientMessage| match message {
+                ClientMessage::Request(mcp_schema::ClientRequest::Initialize { .. }) => {
+                    Err(Error::invalid_request("Not now"))
+                }
+                _ => Ok(()),
+            });
+        let mut client = serve(service, LIMIT);
+
+        let response = client
+            .negotiate(&json!({ "experimental": { "encodings": ["msgpack"] } }))
+            .await;
+        assert!(response.get("error").is_some());
+
+        client.send_json(&ping(2)).await;
+        assert_eq!(client.receive_json().await["id"], 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_length_prefixes_over_the_limit() {
+        let mut client = serve(BasicService::new(()), LIMIT);
+        client
+            .negotiate(&json!({ "experimental": { "encodings": ["msgpack"] } }))
+            .await;
+
+        // Only the prefix is sent, so the body must not be waited for
+        let prefix = u32::try_from(LIMIT * 1024).unwrap();
+        client
+            .writer
+            .write_all(&prefix.to_be_bytes())
+            .await
+            .unwrap();
+
+        let error = client.served.await.unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn fails_on_a_frame_cut_off_by_the_end_of_the_input() {
+        let mut client = serve(BasicService::new(()), LIMIT);
+        client
+            .negotiate(&json!({ "experimental": { "encodings": ["msgpack"] } }))
+            .await;
+
+        let body = rmp_serde::to_vec_named(&ping(2)).unwrap();
+        let len = u32::try_from(body.len()).unwrap();
+        client.writer.write_all(&len.to_be_bytes()).await.unwrap();
+        client
+            .writer
+            .write_all(&body[..body.len() / 2])
+            .await
+            .unwrap();
+        client.writer.shutdown().await.unwrap();
+
+        let error = client.served.await.unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "zstd")]
+    mod zstd_compression {
+        use super::*;
+        use mcp::Tool;
+        use schemars::JsonSchema;
+        use serde::Deserialize;
+
+        #[derive(Deserialize, JsonSchema)]
+        struct EchoParams {
+            text: String,
+        }
+
+        async fn echo((): (), params: EchoParams) -> Result<String, Error> {
+            Ok(params.text)
+        }
+
+        fn capabilities() -> serde_json::Value {
+            json!({ "experimental": { "encodings": ["msgpack"], "compression": ["zstd"] } })
+        }
+
+        #[tokio::test]
+        async fn round_trips_compressed_frames() {
+            let service = BasicService::new(())
+                .tool(Tool::builder().name("echo").handler(echo).build().unwrap());
+            let mut client = serve(service, 4 * 1024 * 1024);
+            let response = client.negotiate(&capabilities()).await;
+            assert_eq!(
+                response["result"]["capabilities"]["experimental"]["compression"],
+                "zstd"
+            );
+
+            let text = "compressible ".repeat(4096);
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "echo", "arguments": { "text": text } },
+            });
+            let body = rmp_serde::to_vec_named(&request).unwrap();
+            client
+                .send_body(&zstd::encode_all(&body[..], 0).unwrap(), 1 << 31)
+                .await;
+
+            let (response, compressed) = client.receive_frame().await;
+            assert!(compressed);
+            assert_eq!(response["result"]["content"][0]["text"], text);
+        }
+
+        #[tokio::test]
+        async fn rejects_frames_that_decompress_over_the_limit() {
+            let mut client = serve(BasicService::new(()), LIMIT);
+            client.negotiate(&capabilities()).await;
+
+            let bomb = zstd::encode_all(&vec![0; LIMIT * 1024][..], 0).unwrap();
+            assert!(bomb.len() < LIMIT);
+            client.send_body(&bomb, 1 << 31).await;
+
+            let error = client.served.await.unwrap().unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+    }
+}